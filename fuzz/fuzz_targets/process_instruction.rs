@@ -0,0 +1,127 @@
+#![no_main]
+
+use std::sync::Once;
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use metality_game_contract::{processor::Processor, validations::admin};
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::{ProgramResult, SUCCESS},
+    instruction::Instruction,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program, sysvar,
+};
+
+const MAX_ACCOUNTS: usize = 32;
+
+const PROGRAM_ID: Pubkey = Pubkey::new_from_array([0xaa; 32]);
+
+/// Stands in for the runtime: every CPI succeeds without touching accounts and
+/// sysvars read as their defaults, so the processor runs to completion on
+/// whatever account contents the fuzzer picks.
+struct FuzzSyscallStubs;
+
+impl SyscallStubs for FuzzSyscallStubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_invoke_signed(
+        &self,
+        _instruction: &Instruction,
+        _account_infos: &[AccountInfo],
+        _signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        Ok(())
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe {
+            *(var_addr as *mut Rent) = Rent::default();
+        }
+
+        SUCCESS
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+struct FuzzAccount {
+    key: u8,
+    owner: u8,
+    is_signer: bool,
+    is_writable: bool,
+    lamports: u64,
+    data: Vec<u8>,
+}
+
+#[derive(Arbitrary, Debug)]
+struct FuzzInput {
+    instruction_data: Vec<u8>,
+    accounts: Vec<FuzzAccount>,
+}
+
+/// Keys are drawn from a small pool of well known and colliding addresses,
+/// so equality checks between accounts pass often enough to reach deep paths.
+fn pool_key(index: u8) -> Pubkey {
+    match index % 16 {
+        0 => PROGRAM_ID,
+        1 => admin::id(),
+        2 => system_program::id(),
+        3 => spl_token::id(),
+        4 => spl_associated_token_account::id(),
+        5 => sysvar::rent::id(),
+        6 => sysvar::instructions::id(),
+        other => Pubkey::new_from_array([other; 32]),
+    }
+}
+
+fuzz_target!(|input: FuzzInput| {
+    static STUBS: Once = Once::new();
+    STUBS.call_once(|| {
+        set_syscall_stubs(Box::new(FuzzSyscallStubs));
+    });
+
+    let accounts = &input.accounts[..input.accounts.len().min(MAX_ACCOUNTS)];
+
+    let keys: Vec<Pubkey> = accounts
+        .iter()
+        .map(|account| pool_key(account.key))
+        .collect();
+    let owners: Vec<Pubkey> = accounts
+        .iter()
+        .map(|account| pool_key(account.owner))
+        .collect();
+    let mut lamports: Vec<u64> = accounts.iter().map(|account| account.lamports).collect();
+    let mut data: Vec<Vec<u8>> = accounts
+        .iter()
+        .map(|account| account.data.clone())
+        .collect();
+
+    let account_infos: Vec<AccountInfo> = accounts
+        .iter()
+        .zip(keys.iter())
+        .zip(owners.iter())
+        .zip(lamports.iter_mut())
+        .zip(data.iter_mut())
+        .map(|((((account, key), owner), lamports), data)| {
+            AccountInfo::new(
+                key,
+                account.is_signer,
+                account.is_writable,
+                lamports,
+                data,
+                owner,
+                false,
+                0,
+            )
+        })
+        .collect();
+
+    // Errors are expected for almost every input; only panics are findings.
+    let _ = Processor::unpack_and_process_instruction(
+        &PROGRAM_ID,
+        &account_infos,
+        &input.instruction_data,
+    );
+});
@@ -1,7 +1,9 @@
 use solana_program::{
-    account_info::AccountInfo, entrypoint, entrypoint::ProgramResult, msg, pubkey::Pubkey,
+    account_info::AccountInfo, entrypoint, entrypoint::ProgramResult, msg,
+    program_error::PrintProgramError, pubkey::Pubkey,
 };
 
+use crate::error::MetalityGameContractError;
 use crate::processor::Processor;
 
 entrypoint!(process_instruction);
@@ -13,7 +15,13 @@ fn process_instruction(
 ) -> ProgramResult {
     msg!("Metality: Entrypoint");
 
-    Processor::unpack_and_process_instruction(program_id, accounts, instruction_data)?;
+    if let Err(error) =
+        Processor::unpack_and_process_instruction(program_id, accounts, instruction_data)
+    {
+        error.print::<MetalityGameContractError>();
+
+        return Err(error);
+    }
 
     Ok(())
 }
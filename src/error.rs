@@ -1,7 +1,13 @@
-use solana_program::program_error::ProgramError;
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
 use thiserror::Error;
 
-#[derive(Error, Debug, Copy, Clone)]
+#[derive(Error, Debug, Copy, Clone, PartialEq, FromPrimitive)]
 pub enum MetalityGameContractError {
     #[error("Invalid Instruction")]
     InvalidInstruction,
@@ -35,6 +41,231 @@ pub enum MetalityGameContractError {
 
     #[error("Invalid State Account")]
     InvalidStateAccount,
+
+    #[error("Invalid lobby size")]
+    InvalidLobbySize,
+
+    #[error("Lobby is full")]
+    LobbyFull,
+
+    #[error("Not enough players")]
+    NotEnoughPlayers,
+
+    #[error("User not in lobby")]
+    NotLobbyEntrant,
+
+    #[error("Lobby creator cannot exit")]
+    CreatorCannotExit,
+
+    #[error("Invalid payout table")]
+    InvalidPayoutTable,
+
+    #[error("Invalid ranking")]
+    InvalidRanking,
+
+    #[error("Invalid tournament size")]
+    InvalidTournamentSize,
+
+    #[error("Tournament is full")]
+    TournamentFull,
+
+    #[error("Tournament match not ready")]
+    MatchNotReady,
+
+    #[error("Tournament match already created")]
+    MatchAlreadyCreated,
+
+    #[error("Game is not a match of this tournament")]
+    NotTournamentMatch,
+
+    #[error("Tournament champion undecided")]
+    ChampionUndecided,
+
+    #[error("Invalid player profile account")]
+    InvalidProfileAccount,
+
+    #[error("Invalid opponent rating range")]
+    InvalidRatingRange,
+
+    #[error("Rating outside the allowed range")]
+    RatingOutOfRange,
+
+    #[error("Matchmaking queue is full")]
+    QueueFull,
+
+    #[error("User not in queue")]
+    NotQueued,
+
+    #[error("Queued entries are not compatible")]
+    IncompatibleEntries,
+
+    #[error("Invalid side pool account")]
+    InvalidSidePool,
+
+    #[error("Side pool is locked")]
+    SidePoolLocked,
+
+    #[error("Side pool not settled")]
+    SidePoolNotSettled,
+
+    #[error("Invalid side")]
+    InvalidSide,
+
+    #[error("Nothing to claim")]
+    NothingToClaim,
+
+    #[error("No result oracle registered")]
+    OracleNotSet,
+
+    #[error("Invalid oracle attestation")]
+    InvalidOracleAttestation,
+
+    #[error("Invalid program data account")]
+    InvalidProgramDataAccount,
+
+    #[error("Winner not recorded")]
+    WinnerNotRecorded,
+
+    #[error("Winner must claim the reward")]
+    WinnerMustClaim,
+
+    #[error("No cancel proposal from the other player")]
+    NoCancelProposal,
+
+    #[error("Invalid join bond forfeit")]
+    InvalidBondForfeit,
+
+    #[error("Not a previous opponent")]
+    NotPreviousOpponent,
+
+    #[error("Opponent blocked by the creator")]
+    OpponentBlocked,
+
+    #[error("Invalid token program")]
+    InvalidTokenProgram,
+
+    #[error("Invalid system program")]
+    InvalidSystemProgram,
+
+    #[error("Invalid associated token account program")]
+    InvalidAssociatedTokenProgram,
+
+    #[error("Invalid rent sysvar")]
+    InvalidRentSysvar,
+
+    #[error("Token account has a delegate")]
+    TokenAccountDelegated,
+
+    #[error("Token account has a close authority")]
+    TokenAccountCloseAuthoritySet,
+
+    #[error("Token account is frozen")]
+    TokenAccountFrozen,
+
+    #[error("Mint is not a fixed supply NFT")]
+    InvalidNftMint,
+
+    #[error("Invalid NFT check level")]
+    InvalidNftCheckLevel,
+
+    #[error("State account not initialized")]
+    StateNotInitialized,
+
+    #[error("Game expired")]
+    GameExpired,
+
+    #[error("Game already started")]
+    GameAlreadyStarted,
+
+    #[error("Game not started")]
+    GameNotStarted,
+
+    #[error("Opponent already joined")]
+    OpponentAlreadyJoined,
+
+    #[error("Opponent not joined")]
+    OpponentNotJoined,
+
+    #[error("Winner already recorded")]
+    WinnerAlreadyRecorded,
+
+    #[error("Lobby expired")]
+    LobbyExpired,
+
+    #[error("Lobby already started")]
+    LobbyAlreadyStarted,
+
+    #[error("Lobby not started")]
+    LobbyNotStarted,
+
+    #[error("Tournament expired")]
+    TournamentExpired,
+
+    #[error("Tournament already started")]
+    TournamentAlreadyStarted,
+
+    #[error("User A does not match the game")]
+    UserAMismatch,
+
+    #[error("User B does not match the game")]
+    UserBMismatch,
+
+    #[error("Loser does not match the game")]
+    LoserMismatch,
+
+    #[error("Entrant does not match the recorded entry")]
+    EntrantMismatch,
+
+    #[error("Not the lobby creator")]
+    CreatorMismatch,
+
+    #[error("Not the tournament champion")]
+    ChampionMismatch,
+
+    #[error("Bettor does not match the side bet")]
+    BettorMismatch,
+
+    #[error("NFT token account does not match the recorded stake")]
+    NftAtaMismatch,
+
+    #[error("NFT mint does not match the recorded stake")]
+    MintMismatch,
+
+    #[error("Recipient token account is not the associated token account")]
+    RecipientAtaMismatch,
+
+    #[error("Side pool vault mismatch")]
+    VaultMismatch,
+
+    #[error("Side pool account mismatch")]
+    SidePoolMismatch,
+
+    #[error("Side pool already open for this pairing")]
+    SidePoolAlreadyOpen,
+
+    #[error("Side bet account mismatch")]
+    SideBetMismatch,
+
+    #[error("Account is not rent exempt")]
+    NotRentExempt,
+
+    #[error("Account index overflow")]
+    IndexOverflow,
+
+    #[error("Account seed too long")]
+    SeedTooLong,
+
+    #[error("Account already uses the current layout")]
+    NothingToMigrate,
+
+    #[error("Stakes not released yet")]
+    StakesLocked,
+
+    #[error("Stake already released")]
+    StakeAlreadyReleased,
+
+    #[error("TransferReward only records the winner, claim through ClaimReward")]
+    LegacyTransferReward,
 }
 
 impl From<MetalityGameContractError> for ProgramError {
@@ -42,3 +273,31 @@ impl From<MetalityGameContractError> for ProgramError {
         ProgramError::Custom(e as u32)
     }
 }
+
+impl<T> DecodeError<T> for MetalityGameContractError {
+    fn type_of() -> &'static str {
+        "MetalityGameContractError"
+    }
+}
+
+impl PrintProgramError for MetalityGameContractError {
+    fn print<E>(&self)
+    where
+        E: 'static + std::error::Error + DecodeError<E> + PrintProgramError + FromPrimitive,
+    {
+        msg!("Error: {:?} ({})", self, self);
+    }
+}
+
+impl MetalityGameContractError {
+    /// Every error as `(code, name, message)`, where `code` is the value
+    /// carried by `ProgramError::Custom`. Meant for clients decoding failed
+    /// transactions.
+    pub fn table() -> Vec<(u32, String, String)> {
+        (0..)
+            .map_while(|code| {
+                Self::from_u32(code).map(|error| (code, format!("{:?}", error), error.to_string()))
+            })
+            .collect()
+    }
+}
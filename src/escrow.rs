@@ -0,0 +1,129 @@
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    pubkey::Pubkey,
+};
+
+use spl_associated_token_account::instruction::create_associated_token_account;
+use spl_token;
+
+pub const ESCROW_SEED: &str = "metality_game_contract";
+
+/// PDA holding staked NFT accounts for a game or lobby state account.
+pub struct EscrowAuthority<'a, 'b> {
+    pub pda_account: &'b AccountInfo<'a>,
+    pub escrow_state_account: &'b AccountInfo<'a>,
+    pub bump_seeds: u8,
+    pub token_program_account: &'b AccountInfo<'a>,
+}
+
+impl<'a, 'b> EscrowAuthority<'a, 'b> {
+    pub fn find_pda(escrow_state: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[ESCROW_SEED.as_bytes(), escrow_state.as_ref()], program_id)
+    }
+
+    /// Hands ownership of a staked token account to `owner`, normally the
+    /// player who staked it.
+    pub fn release_stake(&self, stake_ata: &AccountInfo<'a>, owner: &Pubkey) -> ProgramResult {
+        invoke_signed(
+            &spl_token::instruction::set_authority(
+                &spl_token::ID,
+                stake_ata.key,
+                Some(owner),
+                spl_token::instruction::AuthorityType::AccountOwner,
+                self.pda_account.key,
+                &[],
+            )?,
+            &[
+                stake_ata.clone(),
+                self.pda_account.clone(),
+                self.token_program_account.clone(),
+            ],
+            &[&[
+                ESCROW_SEED.as_bytes(),
+                self.escrow_state_account.key.as_ref(),
+                &[self.bump_seeds],
+            ]],
+        )
+    }
+
+    /// Moves the staked NFT into `destination_ata`, then returns the emptied
+    /// token account to its original `owner`.
+    pub fn award_stake(
+        &self,
+        stake_ata: &AccountInfo<'a>,
+        owner: &Pubkey,
+        destination_ata: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        self.transfer(stake_ata, destination_ata, 1)?;
+
+        self.release_stake(stake_ata, owner)
+    }
+
+    /// Transfers `amount` tokens out of a token account owned by the PDA.
+    pub fn transfer(
+        &self,
+        source_ata: &AccountInfo<'a>,
+        destination_ata: &AccountInfo<'a>,
+        amount: u64,
+    ) -> ProgramResult {
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                &spl_token::ID,
+                source_ata.key,
+                destination_ata.key,
+                self.pda_account.key,
+                &[],
+                amount,
+            )?,
+            &[
+                source_ata.clone(),
+                destination_ata.clone(),
+                self.pda_account.clone(),
+                self.token_program_account.clone(),
+            ],
+            &[&[
+                ESCROW_SEED.as_bytes(),
+                self.escrow_state_account.key.as_ref(),
+                &[self.bump_seeds],
+            ]],
+        )
+    }
+}
+
+/// Accounts needed to create a recipient's associated token account on payout.
+pub struct AtaCreator<'a, 'b> {
+    pub payer: &'b AccountInfo<'a>,
+    pub system_program_account: &'b AccountInfo<'a>,
+    pub token_program_account: &'b AccountInfo<'a>,
+    pub rent_sysvar_account: &'b AccountInfo<'a>,
+    pub associated_token_account_program: &'b AccountInfo<'a>,
+}
+
+impl<'a, 'b> AtaCreator<'a, 'b> {
+    pub fn create_if_missing(
+        &self,
+        ata: &AccountInfo<'a>,
+        wallet: &AccountInfo<'a>,
+        mint: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        if !ata.data_is_empty() {
+            return Ok(());
+        }
+
+        invoke(
+            &create_associated_token_account(self.payer.key, wallet.key, mint.key),
+            &[
+                self.payer.clone(),
+                ata.clone(),
+                wallet.clone(),
+                mint.clone(),
+                self.system_program_account.clone(),
+                self.token_program_account.clone(),
+                self.rent_sysvar_account.clone(),
+                self.associated_token_account_program.clone(),
+            ],
+        )
+    }
+}
@@ -1,35 +1,291 @@
-use solana_program::program_error::ProgramError;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
 
 use crate::error::MetalityGameContractError;
+use crate::state::MAX_LOBBY_PLAYERS;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum MetalityGameContractInstruction {
     InitializeGameProgramData,
-    InitializeGame,
+    InitializeGame {
+        min_opponent_rating: u32,
+        max_opponent_rating: u32,
+    },
     CancelGame,
     JoinGame,
+    /// Admin records the winner of a started game, taking the admin, program
+    /// data, winner and game state accounts. Nothing is paid out: the reward
+    /// is then claimed through `ClaimReward`. The old layout that paid out
+    /// directly (admin followed by the payout accounts) is rejected with
+    /// `LegacyTransferReward`, so those clients must switch to recording the
+    /// winner here and sending `ClaimReward` with the same payout accounts.
     TransferReward,
     DrawOrCancelGame,
     SetGameStarted,
     UserBExitGame,
+    InitializeLobby {
+        max_players: u8,
+        payout_table: [u8; MAX_LOBBY_PLAYERS],
+    },
+    JoinLobby,
+    ExitLobby,
+    CancelLobby,
+    SetLobbyStarted,
+    SettleLobby {
+        ranking: [u8; MAX_LOBBY_PLAYERS],
+        ranking_len: u8,
+    },
+    InitializeTournament {
+        capacity: u8,
+    },
+    RegisterTournamentEntrant,
+    CancelTournament,
+    CreateTournamentMatch {
+        match_index: u8,
+    },
+    SettleTournamentMatch,
+    PayoutTournament,
+    InitializeQueue,
+    Enqueue {
+        min_opponent_rating: u32,
+        max_opponent_rating: u32,
+    },
+    Dequeue,
+    MatchQueued {
+        first_entry: u8,
+        second_entry: u8,
+    },
+    InitializeSidePool,
+    /// Bets on one player of a game, taking the bettor, their token account,
+    /// the side pool, the pool's game state, the vault, the bet account and
+    /// the system and token programs. Bets close once the winner is recorded.
+    PlaceSideBet {
+        side: u8,
+        amount: u64,
+    },
+    ClaimSideBet,
+    SetOracle {
+        oracle: Pubkey,
+    },
+    /// Records the winner reported by the registered oracle, taking the
+    /// payer, program data, instructions sysvar, winner and game state
+    /// accounts. The instruction right before it must be an ed25519
+    /// verification of `oracle::result_message` signed by the oracle. Like
+    /// `TransferReward` this only records the winner, the payout follows
+    /// through `ClaimReward`.
+    SubmitSignedResult,
+    /// Same as `TransferReward`, under its own tag.
+    RecordWinner,
+    /// Pays a recorded winner out: the payer followed by the accounts the old
+    /// `TransferReward` took after the admin, plus both player profiles.
+    ClaimReward,
+    SetClaimPreference {
+        self_claim: bool,
+    },
+    ProposeMutualCancel,
+    AcceptMutualCancel,
+    /// A player of a started game concedes, taking the player, winner and game
+    /// state accounts. This only records the opponent as the winner with a
+    /// forfeit outcome: nothing is paid out until `ClaimReward` is sent, as
+    /// after `TransferReward`.
+    Resign,
+    SetJoinBond {
+        lamports: u64,
+        forfeit_bps: u16,
+    },
+    KickOpponent,
+    SetOpponentBlocked {
+        opponent: Pubkey,
+        blocked: bool,
+    },
+    SetNftCheckLevel {
+        level: u8,
+    },
+    MigrateAccount,
+    ReleaseLobbyStake,
+    ReleaseTournamentStake,
 }
 
 impl MetalityGameContractInstruction {
     pub fn unpack_instruction_data(ins_data: &[u8]) -> Result<Self, ProgramError> {
-        let (ins_no, _data) = ins_data
+        let (ins_no, data) = ins_data
             .split_first()
             .ok_or(MetalityGameContractError::InvalidInstruction)?;
 
+        if let Some(payload_len) = Self::payload_len(*ins_no) {
+            if data.len() != payload_len {
+                return Err(MetalityGameContractError::InvalidInstruction.into());
+            }
+        }
+
         Ok(match ins_no {
             0 => Self::InitializeGameProgramData,
-            1 => Self::InitializeGame,
+            1 => match data.len() {
+                0 => Self::InitializeGame {
+                    min_opponent_rating: 0,
+                    max_opponent_rating: u32::MAX,
+                },
+                8 => Self::InitializeGame {
+                    min_opponent_rating: Self::unpack_u32(data, 0)?,
+                    max_opponent_rating: Self::unpack_u32(data, 4)?,
+                },
+                _ => return Err(MetalityGameContractError::InvalidInstruction.into()),
+            },
             2 => Self::CancelGame,
             3 => Self::JoinGame,
             4 => Self::TransferReward,
             5 => Self::DrawOrCancelGame,
             6 => Self::SetGameStarted,
             7 => Self::UserBExitGame,
+            8 => {
+                let (max_players, payout_table) = data
+                    .split_first()
+                    .ok_or(MetalityGameContractError::InvalidInstruction)?;
+
+                Self::InitializeLobby {
+                    max_players: *max_players,
+                    payout_table: payout_table
+                        .get(..MAX_LOBBY_PLAYERS)
+                        .and_then(|slice| slice.try_into().ok())
+                        .ok_or(MetalityGameContractError::InvalidInstruction)?,
+                }
+            }
+            9 => Self::JoinLobby,
+            10 => Self::ExitLobby,
+            11 => Self::CancelLobby,
+            12 => Self::SetLobbyStarted,
+            13 => {
+                if data.is_empty() || data.len() > MAX_LOBBY_PLAYERS {
+                    return Err(MetalityGameContractError::InvalidInstruction.into());
+                }
+
+                let mut ranking = [0u8; MAX_LOBBY_PLAYERS];
+                ranking[..data.len()].copy_from_slice(data);
+
+                Self::SettleLobby {
+                    ranking,
+                    ranking_len: data.len() as u8,
+                }
+            }
+            14 => Self::InitializeTournament {
+                capacity: *data
+                    .first()
+                    .ok_or(MetalityGameContractError::InvalidInstruction)?,
+            },
+            15 => Self::RegisterTournamentEntrant,
+            16 => Self::CancelTournament,
+            17 => Self::CreateTournamentMatch {
+                match_index: *data
+                    .first()
+                    .ok_or(MetalityGameContractError::InvalidInstruction)?,
+            },
+            18 => Self::SettleTournamentMatch,
+            19 => Self::PayoutTournament,
+            20 => Self::InitializeQueue,
+            21 => Self::Enqueue {
+                min_opponent_rating: Self::unpack_u32(data, 0)?,
+                max_opponent_rating: Self::unpack_u32(data, 4)?,
+            },
+            22 => Self::Dequeue,
+            23 => match data {
+                [first_entry, second_entry, ..] => Self::MatchQueued {
+                    first_entry: *first_entry,
+                    second_entry: *second_entry,
+                },
+                _ => return Err(MetalityGameContractError::InvalidInstruction.into()),
+            },
+            24 => Self::InitializeSidePool,
+            25 => {
+                let (side, amount) = data
+                    .split_first()
+                    .ok_or(MetalityGameContractError::InvalidInstruction)?;
+
+                Self::PlaceSideBet {
+                    side: *side,
+                    amount: Self::unpack_u64(amount, 0)?,
+                }
+            }
+            26 => Self::ClaimSideBet,
+            27 => Self::SetOracle {
+                oracle: Self::unpack_pubkey(data, 0)?,
+            },
+            28 => Self::SubmitSignedResult,
+            29 => Self::RecordWinner,
+            30 => Self::ClaimReward,
+            31 => Self::SetClaimPreference {
+                self_claim: match data.first() {
+                    Some(0) => false,
+                    Some(1) => true,
+                    _ => return Err(MetalityGameContractError::InvalidInstruction.into()),
+                },
+            },
+            32 => Self::ProposeMutualCancel,
+            33 => Self::AcceptMutualCancel,
+            34 => Self::Resign,
+            35 => Self::SetJoinBond {
+                lamports: Self::unpack_u64(data, 0)?,
+                forfeit_bps: data
+                    .get(8..10)
+                    .and_then(|bytes| bytes.try_into().ok())
+                    .map(u16::from_le_bytes)
+                    .ok_or(MetalityGameContractError::InvalidInstruction)?,
+            },
+            36 => Self::KickOpponent,
+            37 => Self::SetOpponentBlocked {
+                opponent: Self::unpack_pubkey(data, 0)?,
+                blocked: match data.get(32) {
+                    Some(0) => false,
+                    Some(1) => true,
+                    _ => return Err(MetalityGameContractError::InvalidInstruction.into()),
+                },
+            },
+            38 => Self::SetNftCheckLevel {
+                level: *data
+                    .first()
+                    .ok_or(MetalityGameContractError::InvalidInstruction)?,
+            },
+            39 => Self::MigrateAccount,
+            40 => Self::ReleaseLobbyStake,
+            41 => Self::ReleaseTournamentStake,
             _ => return Err(MetalityGameContractError::InvalidInstruction.into()),
         })
     }
+
+    /// Number of bytes that must follow the tag of each instruction. `None`
+    /// marks the variable length payloads of `InitializeGame` and
+    /// `SettleLobby`, which check their own length.
+    fn payload_len(ins_no: u8) -> Option<usize> {
+        match ins_no {
+            1 | 13 => None,
+            8 => Some(1 + MAX_LOBBY_PLAYERS),
+            14 | 17 | 31 | 38 => Some(1),
+            23 => Some(2),
+            21 => Some(8),
+            25 => Some(9),
+            35 => Some(10),
+            27 => Some(32),
+            37 => Some(33),
+            _ => Some(0),
+        }
+    }
+
+    fn unpack_u32(data: &[u8], offset: usize) -> Result<u32, ProgramError> {
+        data.get(offset..offset + 4)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(u32::from_le_bytes)
+            .ok_or_else(|| MetalityGameContractError::InvalidInstruction.into())
+    }
+
+    fn unpack_u64(data: &[u8], offset: usize) -> Result<u64, ProgramError> {
+        data.get(offset..offset + 8)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or_else(|| MetalityGameContractError::InvalidInstruction.into())
+    }
+
+    fn unpack_pubkey(data: &[u8], offset: usize) -> Result<Pubkey, ProgramError> {
+        data.get(offset..offset + 32)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(Pubkey::new_from_array)
+            .ok_or_else(|| MetalityGameContractError::InvalidInstruction.into())
+    }
 }
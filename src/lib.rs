@@ -2,7 +2,14 @@
 pub mod entrypoint;
 
 pub mod error;
+pub mod escrow;
 pub mod instruction;
+pub mod metaplex;
+pub mod oracle;
 pub mod processor;
+pub mod profile;
+pub mod rating;
+pub mod side_pool;
 pub mod state;
+pub mod utils;
 pub mod validations;
@@ -0,0 +1,23 @@
+use solana_program::pubkey::Pubkey;
+
+pub mod mpl_token_metadata {
+    solana_program::declare_id!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+}
+
+pub const METADATA_SEED: &str = "metadata";
+
+pub const EDITION_SEED: &str = "edition";
+
+/// Metaplex master edition PDA of `mint`. Printing a master edition hands the
+/// mint authority to this account, which caps the supply at one.
+pub fn find_master_edition_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            METADATA_SEED.as_bytes(),
+            mpl_token_metadata::ID.as_ref(),
+            mint.as_ref(),
+            EDITION_SEED.as_bytes(),
+        ],
+        &mpl_token_metadata::ID,
+    )
+}
@@ -0,0 +1,106 @@
+use solana_program::{
+    account_info::AccountInfo, ed25519_program, entrypoint::ProgramResult,
+    program_error::ProgramError, pubkey::Pubkey, sysvar::instructions,
+};
+
+use crate::error::MetalityGameContractError;
+
+pub const RESULT_MESSAGE_LEN: usize = 64;
+
+const SIGNATURE_OFFSETS_START: usize = 2;
+
+const SIGNATURE_OFFSETS_LEN: usize = 14;
+
+const PUBKEY_LEN: usize = 32;
+
+/// Marks an ed25519 offset as pointing into the verify instruction itself.
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Message the oracle signs to report `winner` as the winner of `game_state`.
+///
+/// It carries no nonce: a game records a single winner, so an attestation
+/// can never be accepted twice.
+pub fn result_message(game_state: &Pubkey, winner: &Pubkey) -> [u8; RESULT_MESSAGE_LEN] {
+    let mut message = [0u8; RESULT_MESSAGE_LEN];
+
+    message[..32].copy_from_slice(game_state.as_ref());
+    message[32..].copy_from_slice(winner.as_ref());
+
+    message
+}
+
+/// Signer and message of an ed25519 program instruction carrying exactly one
+/// signature, with the key and message stored in the instruction itself.
+///
+/// The ed25519 program has already checked the signature by the time this
+/// program runs, so only the layout needs validating here.
+pub fn parse_ed25519_instruction(data: &[u8]) -> Result<(Pubkey, &[u8]), ProgramError> {
+    let invalid = || ProgramError::from(MetalityGameContractError::InvalidOracleAttestation);
+
+    if data.first() != Some(&1) {
+        return Err(invalid());
+    }
+
+    let offsets = data
+        .get(SIGNATURE_OFFSETS_START..SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN)
+        .ok_or_else(invalid)?;
+
+    let read_u16 = |index: usize| u16::from_le_bytes([offsets[index * 2], offsets[index * 2 + 1]]);
+
+    let signature_instruction_index = read_u16(1);
+    let public_key_offset = read_u16(2) as usize;
+    let public_key_instruction_index = read_u16(3);
+    let message_data_offset = read_u16(4) as usize;
+    let message_data_size = read_u16(5) as usize;
+    let message_instruction_index = read_u16(6);
+
+    if signature_instruction_index != CURRENT_INSTRUCTION
+        || public_key_instruction_index != CURRENT_INSTRUCTION
+        || message_instruction_index != CURRENT_INSTRUCTION
+    {
+        return Err(invalid());
+    }
+
+    let signer = data
+        .get(public_key_offset..public_key_offset + PUBKEY_LEN)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(Pubkey::new_from_array)
+        .ok_or_else(invalid)?;
+
+    let message = data
+        .get(message_data_offset..message_data_offset + message_data_size)
+        .ok_or_else(invalid)?;
+
+    Ok((signer, message))
+}
+
+/// Checks that the instruction right before the current one is an ed25519
+/// verification of `expected_message` signed by `oracle`.
+pub fn verify_attestation(
+    instructions_sysvar_account: &AccountInfo,
+    oracle: &Pubkey,
+    expected_message: &[u8],
+) -> ProgramResult {
+    let current_index = instructions::load_current_index_checked(instructions_sysvar_account)?;
+
+    let verify_index = current_index
+        .checked_sub(1)
+        .ok_or(MetalityGameContractError::InvalidOracleAttestation)?;
+
+    let verify_instruction = instructions::load_instruction_at_checked(
+        verify_index as usize,
+        instructions_sysvar_account,
+    )?;
+
+    if verify_instruction.program_id != ed25519_program::id() {
+        return Err(MetalityGameContractError::InvalidOracleAttestation.into());
+    }
+
+    let (signer, message) = parse_ed25519_instruction(&verify_instruction.data)?;
+
+    if signer != *oracle || message != expected_message {
+        return Err(MetalityGameContractError::InvalidOracleAttestation.into());
+    }
+
+    Ok(())
+}
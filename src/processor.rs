@@ -3,9 +3,9 @@ use solana_program::{
     entrypoint::ProgramResult,
     msg,
     program::{invoke, invoke_signed},
+    program_error::ProgramError,
     program_pack::Pack,
     pubkey::Pubkey,
-    rent::Rent,
     system_instruction,
 };
 
@@ -14,12 +14,118 @@ use spl_associated_token_account::{
 };
 use spl_token;
 
+use crate::error::MetalityGameContractError;
+use crate::escrow::{AtaCreator, EscrowAuthority};
 use crate::instruction::MetalityGameContractInstruction;
-use crate::state::{MetalityGameContractState, MetalityGameProgramDataState};
+use crate::oracle;
+use crate::profile::{self, ProfileLoader};
+use crate::rating::{self, Score};
+use crate::side_pool::{self, SIDE_BET_SEED, SIDE_POOL_SEED};
+use crate::state::{
+    MetalityGameContractState, MetalityGameProgramDataState, MetalityLobbyEntrant,
+    MetalityLobbyState, MetalityQueueEntry, MetalityQueueState, MetalitySideBetState,
+    MetalitySidePoolState, MetalityTournamentState, BASIS_POINTS, GAME_OUTCOME_CANCELLED,
+    GAME_OUTCOME_DRAW, GAME_OUTCOME_FORFEIT, GAME_OUTCOME_WON, GAME_STATE_SEED_PREFIX,
+    LOBBY_SEED_PREFIX, MAX_LOBBY_PLAYERS, MAX_QUEUE_ENTRIES, MAX_TOURNAMENT_MATCHES,
+    MIN_LOBBY_PLAYERS, NFT_CHECK_STRICT, NO_ENTRANT, POOL_OUTCOME_REFUND, PROGRAM_DATA_SEED,
+    QUEUE_SEED_PREFIX, SIDE_USER_A, SIDE_USER_B, TOURNAMENT_SEED_PREFIX,
+};
+use crate::utils::{create_pda_account, create_seeded_account, resize_account};
 use crate::validations::Validator;
 
 pub struct Processor;
 
+/// Accounts needed to pay out a decided game, in instruction order.
+struct RewardAccounts<'a, 'b> {
+    winner: &'a AccountInfo<'b>,
+    loser: &'a AccountInfo<'b>,
+    won_nft: &'a AccountInfo<'b>,
+    won_nft_mint: &'a AccountInfo<'b>,
+    owned_nft: &'a AccountInfo<'b>,
+    owned_nft_mint: &'a AccountInfo<'b>,
+    winner_won_nft_ata: &'a AccountInfo<'b>,
+    pda_account: &'a AccountInfo<'b>,
+    game_state_account: &'a AccountInfo<'b>,
+    system_program_account: &'a AccountInfo<'b>,
+    token_program_account: &'a AccountInfo<'b>,
+    rent_sysvar_account: &'a AccountInfo<'b>,
+    associated_token_account_program: &'a AccountInfo<'b>,
+    winner_profile_account: &'a AccountInfo<'b>,
+    loser_profile_account: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> RewardAccounts<'a, 'b> {
+    fn next<I: Iterator<Item = &'a AccountInfo<'b>>>(
+        account_info_iter: &mut I,
+    ) -> Result<Self, ProgramError> {
+        Ok(RewardAccounts {
+            winner: next_account_info(account_info_iter)?,
+            loser: next_account_info(account_info_iter)?,
+            won_nft: next_account_info(account_info_iter)?,
+            won_nft_mint: next_account_info(account_info_iter)?,
+            owned_nft: next_account_info(account_info_iter)?,
+            owned_nft_mint: next_account_info(account_info_iter)?,
+            winner_won_nft_ata: next_account_info(account_info_iter)?,
+            pda_account: next_account_info(account_info_iter)?,
+            game_state_account: next_account_info(account_info_iter)?,
+            system_program_account: next_account_info(account_info_iter)?,
+            token_program_account: next_account_info(account_info_iter)?,
+            rent_sysvar_account: next_account_info(account_info_iter)?,
+            associated_token_account_program: next_account_info(account_info_iter)?,
+            winner_profile_account: next_account_info(account_info_iter)?,
+            loser_profile_account: next_account_info(account_info_iter)?,
+        })
+    }
+}
+
+/// Accounts booking a decided game on both players' records.
+struct OutcomeAccounts<'a, 'b> {
+    winner: &'a AccountInfo<'b>,
+    loser: &'a AccountInfo<'b>,
+    game_state_account: &'a AccountInfo<'b>,
+    system_program_account: &'a AccountInfo<'b>,
+    winner_profile_account: &'a AccountInfo<'b>,
+    loser_profile_account: &'a AccountInfo<'b>,
+}
+
+/// Accounts releasing one entrant's stake from a lobby or tournament escrow,
+/// in instruction order.
+struct StakeReleaseAccounts<'a, 'b> {
+    payer: &'a AccountInfo<'b>,
+    pda_account: &'a AccountInfo<'b>,
+    escrow_state_account: &'a AccountInfo<'b>,
+    user: &'a AccountInfo<'b>,
+    nft_ata: &'a AccountInfo<'b>,
+    nft_mint: &'a AccountInfo<'b>,
+    recipient: &'a AccountInfo<'b>,
+    recipient_ata: &'a AccountInfo<'b>,
+    system_program_account: &'a AccountInfo<'b>,
+    token_program_account: &'a AccountInfo<'b>,
+    rent_sysvar_account: &'a AccountInfo<'b>,
+    associated_token_account_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> StakeReleaseAccounts<'a, 'b> {
+    fn next<I: Iterator<Item = &'a AccountInfo<'b>>>(
+        account_info_iter: &mut I,
+    ) -> Result<Self, ProgramError> {
+        Ok(StakeReleaseAccounts {
+            payer: next_account_info(account_info_iter)?,
+            pda_account: next_account_info(account_info_iter)?,
+            escrow_state_account: next_account_info(account_info_iter)?,
+            user: next_account_info(account_info_iter)?,
+            nft_ata: next_account_info(account_info_iter)?,
+            nft_mint: next_account_info(account_info_iter)?,
+            recipient: next_account_info(account_info_iter)?,
+            recipient_ata: next_account_info(account_info_iter)?,
+            system_program_account: next_account_info(account_info_iter)?,
+            token_program_account: next_account_info(account_info_iter)?,
+            rent_sysvar_account: next_account_info(account_info_iter)?,
+            associated_token_account_program: next_account_info(account_info_iter)?,
+        })
+    }
+}
+
 impl Processor {
     pub fn unpack_and_process_instruction(
         program_id: &Pubkey,
@@ -32,9 +138,17 @@ impl Processor {
                 Self::process_initialize_game_program_data(accounts, program_id)?;
             }
 
-            MetalityGameContractInstruction::InitializeGame => {
+            MetalityGameContractInstruction::InitializeGame {
+                min_opponent_rating,
+                max_opponent_rating,
+            } => {
                 msg!("Instruction: InitializeGame");
-                Self::process_initialize_game(accounts, program_id)?;
+                Self::process_initialize_game(
+                    accounts,
+                    program_id,
+                    min_opponent_rating,
+                    max_opponent_rating,
+                )?;
             }
 
             MetalityGameContractInstruction::CancelGame => {
@@ -66,6 +180,201 @@ impl Processor {
                 msg!("Instruction: UserBExitGame");
                 Self::process_user_b_exit_game(accounts, program_id)?;
             }
+
+            MetalityGameContractInstruction::InitializeLobby {
+                max_players,
+                payout_table,
+            } => {
+                msg!("Instruction: InitializeLobby");
+                Self::process_initialize_lobby(accounts, program_id, max_players, payout_table)?;
+            }
+
+            MetalityGameContractInstruction::JoinLobby => {
+                msg!("Instruction: JoinLobby");
+                Self::process_join_lobby(accounts, program_id)?;
+            }
+
+            MetalityGameContractInstruction::ExitLobby => {
+                msg!("Instruction: ExitLobby");
+                Self::process_exit_lobby(accounts, program_id)?;
+            }
+
+            MetalityGameContractInstruction::CancelLobby => {
+                msg!("Instruction: CancelLobby");
+                Self::process_cancel_lobby(accounts, program_id)?;
+            }
+
+            MetalityGameContractInstruction::SetLobbyStarted => {
+                msg!("Instruction: SetLobbyStarted (Admin)");
+                Self::process_set_lobby_started(accounts, program_id)?;
+            }
+
+            MetalityGameContractInstruction::SettleLobby {
+                ranking,
+                ranking_len,
+            } => {
+                msg!("Instruction: SettleLobby (Admin)");
+                Self::process_settle_lobby(accounts, program_id, &ranking[..ranking_len as usize])?;
+            }
+
+            MetalityGameContractInstruction::InitializeTournament { capacity } => {
+                msg!("Instruction: InitializeTournament (Admin)");
+                Self::process_initialize_tournament(accounts, program_id, capacity)?;
+            }
+
+            MetalityGameContractInstruction::RegisterTournamentEntrant => {
+                msg!("Instruction: RegisterTournamentEntrant");
+                Self::process_register_tournament_entrant(accounts, program_id)?;
+            }
+
+            MetalityGameContractInstruction::CancelTournament => {
+                msg!("Instruction: CancelTournament (Admin)");
+                Self::process_cancel_tournament(accounts, program_id)?;
+            }
+
+            MetalityGameContractInstruction::CreateTournamentMatch { match_index } => {
+                msg!("Instruction: CreateTournamentMatch (Admin)");
+                Self::process_create_tournament_match(accounts, program_id, match_index as usize)?;
+            }
+
+            MetalityGameContractInstruction::SettleTournamentMatch => {
+                msg!("Instruction: SettleTournamentMatch (Admin)");
+                Self::process_settle_tournament_match(accounts, program_id)?;
+            }
+
+            MetalityGameContractInstruction::PayoutTournament => {
+                msg!("Instruction: PayoutTournament (Admin)");
+                Self::process_payout_tournament(accounts, program_id)?;
+            }
+
+            MetalityGameContractInstruction::InitializeQueue => {
+                msg!("Instruction: InitializeQueue (Admin)");
+                Self::process_initialize_queue(accounts, program_id)?;
+            }
+
+            MetalityGameContractInstruction::Enqueue {
+                min_opponent_rating,
+                max_opponent_rating,
+            } => {
+                msg!("Instruction: Enqueue");
+                Self::process_enqueue(
+                    accounts,
+                    program_id,
+                    min_opponent_rating,
+                    max_opponent_rating,
+                )?;
+            }
+
+            MetalityGameContractInstruction::Dequeue => {
+                msg!("Instruction: Dequeue");
+                Self::process_dequeue(accounts, program_id)?;
+            }
+
+            MetalityGameContractInstruction::MatchQueued {
+                first_entry,
+                second_entry,
+            } => {
+                msg!("Instruction: MatchQueued");
+                Self::process_match_queued(
+                    accounts,
+                    program_id,
+                    first_entry as usize,
+                    second_entry as usize,
+                )?;
+            }
+
+            MetalityGameContractInstruction::InitializeSidePool => {
+                msg!("Instruction: InitializeSidePool");
+                Self::process_initialize_side_pool(accounts, program_id)?;
+            }
+
+            MetalityGameContractInstruction::PlaceSideBet { side, amount } => {
+                msg!("Instruction: PlaceSideBet");
+                Self::process_place_side_bet(accounts, program_id, side, amount)?;
+            }
+
+            MetalityGameContractInstruction::ClaimSideBet => {
+                msg!("Instruction: ClaimSideBet");
+                Self::process_claim_side_bet(accounts, program_id)?;
+            }
+
+            MetalityGameContractInstruction::SetOracle { oracle } => {
+                msg!("Instruction: SetOracle (Admin)");
+                Self::process_set_oracle(accounts, program_id, oracle)?;
+            }
+
+            MetalityGameContractInstruction::SubmitSignedResult => {
+                msg!("Instruction: SubmitSignedResult");
+                Self::process_submit_signed_result(accounts, program_id)?;
+            }
+
+            MetalityGameContractInstruction::RecordWinner => {
+                msg!("Instruction: RecordWinner (Admin)");
+                Self::process_record_winner(accounts, program_id)?;
+            }
+
+            MetalityGameContractInstruction::ClaimReward => {
+                msg!("Instruction: ClaimReward");
+                Self::process_claim_reward(accounts, program_id)?;
+            }
+
+            MetalityGameContractInstruction::SetClaimPreference { self_claim } => {
+                msg!("Instruction: SetClaimPreference");
+                Self::process_set_claim_preference(accounts, program_id, self_claim)?;
+            }
+
+            MetalityGameContractInstruction::ProposeMutualCancel => {
+                msg!("Instruction: ProposeMutualCancel");
+                Self::process_propose_mutual_cancel(accounts, program_id)?;
+            }
+
+            MetalityGameContractInstruction::AcceptMutualCancel => {
+                msg!("Instruction: AcceptMutualCancel");
+                Self::process_accept_mutual_cancel(accounts, program_id)?;
+            }
+
+            MetalityGameContractInstruction::Resign => {
+                msg!("Instruction: Resign");
+                Self::process_resign(accounts, program_id)?;
+            }
+
+            MetalityGameContractInstruction::SetJoinBond {
+                lamports,
+                forfeit_bps,
+            } => {
+                msg!("Instruction: SetJoinBond (Admin)");
+                Self::process_set_join_bond(accounts, program_id, lamports, forfeit_bps)?;
+            }
+
+            MetalityGameContractInstruction::KickOpponent => {
+                msg!("Instruction: KickOpponent");
+                Self::process_kick_opponent(accounts, program_id)?;
+            }
+
+            MetalityGameContractInstruction::SetOpponentBlocked { opponent, blocked } => {
+                msg!("Instruction: SetOpponentBlocked");
+                Self::process_set_opponent_blocked(accounts, program_id, &opponent, blocked)?;
+            }
+
+            MetalityGameContractInstruction::SetNftCheckLevel { level } => {
+                msg!("Instruction: SetNftCheckLevel (Admin)");
+                Self::process_set_nft_check_level(accounts, program_id, level)?;
+            }
+
+            MetalityGameContractInstruction::MigrateAccount => {
+                msg!("Instruction: MigrateAccount");
+                Self::process_migrate_account(accounts, program_id)?;
+            }
+
+            MetalityGameContractInstruction::ReleaseLobbyStake => {
+                msg!("Instruction: ReleaseLobbyStake");
+                Self::process_release_lobby_stake(accounts, program_id)?;
+            }
+
+            MetalityGameContractInstruction::ReleaseTournamentStake => {
+                msg!("Instruction: ReleaseTournamentStake");
+                Self::process_release_tournament_stake(accounts, program_id)?;
+            }
         }
 
         Ok(())
@@ -83,25 +392,16 @@ impl Processor {
 
         let system_program_account = next_account_info(account_info_iter)?;
 
-        Validator::validate_admin(admin_account)?;
+        Validator::validate_deployer(admin_account)?;
+        Validator::validate_system_program(system_program_account)?;
 
-        let create_game_program_data_state_ix = system_instruction::create_account_with_seed(
-            admin_account.key,
-            game_program_data_account.key,
-            admin_account.key,
-            "Game Contract Main",
-            Rent::default().minimum_balance(MetalityGameProgramDataState::LEN),
-            MetalityGameProgramDataState::LEN as u64,
+        create_seeded_account(
+            admin_account,
+            game_program_data_account,
+            system_program_account,
+            PROGRAM_DATA_SEED,
+            MetalityGameProgramDataState::LEN,
             program_id,
-        );
-
-        invoke(
-            &create_game_program_data_state_ix,
-            &[
-                admin_account.clone(),
-                game_program_data_account.clone(),
-                system_program_account.clone(),
-            ],
         )?;
 
         let mut game_program_data_unpacked = MetalityGameProgramDataState::unpack_unchecked(
@@ -109,6 +409,11 @@ impl Processor {
         )?;
 
         game_program_data_unpacked.index = 0;
+        game_program_data_unpacked.oracle = Pubkey::default();
+        game_program_data_unpacked.join_bond_lamports = 0;
+        game_program_data_unpacked.bond_forfeit_bps = 0;
+        game_program_data_unpacked.nft_check_level = NFT_CHECK_STRICT;
+        game_program_data_unpacked.admin = *admin_account.key;
         game_program_data_unpacked.is_initialized = true;
 
         MetalityGameProgramDataState::pack(
@@ -119,7 +424,12 @@ impl Processor {
         Ok(())
     }
 
-    pub fn process_initialize_game(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+    pub fn process_initialize_game(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        min_opponent_rating: u32,
+        max_opponent_rating: u32,
+    ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
         let user_a = next_account_info(account_info_iter)?;
@@ -138,9 +448,8 @@ impl Processor {
 
         let system_program_account = next_account_info(account_info_iter)?;
 
-        let mut game_program_data_unpacked = MetalityGameProgramDataState::unpack_unchecked(
-            &game_program_data_account.try_borrow_data()?,
-        )?;
+        let mut game_program_data_unpacked =
+            MetalityGameProgramDataState::unpack(&game_program_data_account.try_borrow_data()?)?;
 
         msg!("Index: {:?}", game_program_data_unpacked.index);
 
@@ -152,30 +461,32 @@ impl Processor {
             program_id,
         );
 
-        let seed = format!("Metality Game State {}", game_program_data_unpacked.index);
+        let seed = game_program_data_unpacked.indexed_seed(GAME_STATE_SEED_PREFIX)?;
 
         Validator::validate_is_signer(user_a)?;
+        Validator::validate_program_data_account(game_program_data_account, *program_id)?;
         Validator::validate_token_owner(nft_ata, user_a)?;
         Validator::validate_token_ata(nft_ata, nft_mint)?;
-        Validator::validate_equality(*pda_account.key, pda)?;
+        Validator::validate_nft_mint(nft_mint, game_program_data_unpacked.nft_check_level)?;
+        Validator::validate_equality(
+            *pda_account.key,
+            pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_token_program(token_program_account)?;
+        Validator::validate_system_program(system_program_account)?;
 
-        let create_game_state_with_seed_ix = system_instruction::create_account_with_seed(
-            user_a.key,
-            game_state_account.key,
-            user_a.key,
+        if min_opponent_rating > max_opponent_rating {
+            return Err(MetalityGameContractError::InvalidRatingRange.into());
+        }
+
+        create_seeded_account(
+            user_a,
+            game_state_account,
+            system_program_account,
             &seed,
-            Rent::default().minimum_balance(MetalityGameContractState::LEN),
-            MetalityGameContractState::LEN as u64,
+            MetalityGameContractState::LEN,
             program_id,
-        );
-
-        invoke(
-            &create_game_state_with_seed_ix,
-            &[
-                user_a.clone(),
-                game_state_account.clone(),
-                system_program_account.clone(),
-            ],
         )?;
 
         let mut game_state_unpacked =
@@ -206,13 +517,15 @@ impl Processor {
         game_state_unpacked.pda_account = *pda_account.key;
         game_state_unpacked.expired = false;
         game_state_unpacked.game_started = false;
+        game_state_unpacked.min_opponent_rating = min_opponent_rating;
+        game_state_unpacked.max_opponent_rating = max_opponent_rating;
 
         MetalityGameContractState::pack(
             game_state_unpacked,
             &mut game_state_account.try_borrow_mut_data()?,
         )?;
 
-        game_program_data_unpacked.index += 1;
+        game_program_data_unpacked.advance_index()?;
 
         MetalityGameProgramDataState::pack(
             game_program_data_unpacked,
@@ -243,6 +556,12 @@ impl Processor {
 
         let token_program_account = next_account_info(account_info_iter)?;
 
+        let a_profile_account = next_account_info(account_info_iter)?;
+
+        let b_profile_account = next_account_info(account_info_iter)?;
+
+        let system_program_account = next_account_info(account_info_iter)?;
+
         let seeds = [
             "metality_game_contract".as_bytes(),
             game_state_account.key.as_ref(),
@@ -255,16 +574,50 @@ impl Processor {
 
         Validator::validate_is_signer(user_a)?;
         Validator::validate_state_account(game_state_account, *program_id)?;
-        Validator::validate_bool(game_state_unpacked.is_initialized, true)?;
-        Validator::validate_bool(game_state_unpacked.expired, false)?;
-        Validator::validate_bool(game_state_unpacked.game_started, false)?;
-        Validator::validate_equality(game_state_unpacked.user_a, *user_a.key)?;
-        Validator::validate_equality(*pda_account.key, pda)?;
-        Validator::validate_equality(pda, game_state_unpacked.pda_account)?;
-        Validator::validate_equality(*a_nft_ata.key, game_state_unpacked.a_nft_ata)?;
-        Validator::validate_equality(*a_nft_mint.key, game_state_unpacked.a_nft_mint)?;
+        Validator::validate_bool(
+            game_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.expired,
+            false,
+            MetalityGameContractError::GameExpired,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.game_started,
+            false,
+            MetalityGameContractError::GameAlreadyStarted,
+        )?;
+        Validator::validate_equality(
+            game_state_unpacked.user_a,
+            *user_a.key,
+            MetalityGameContractError::UserAMismatch,
+        )?;
+        Validator::validate_equality(
+            *pda_account.key,
+            pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_equality(
+            pda,
+            game_state_unpacked.pda_account,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_equality(
+            *a_nft_ata.key,
+            game_state_unpacked.a_nft_ata,
+            MetalityGameContractError::NftAtaMismatch,
+        )?;
+        Validator::validate_equality(
+            *a_nft_mint.key,
+            game_state_unpacked.a_nft_mint,
+            MetalityGameContractError::MintMismatch,
+        )?;
         Validator::validate_token_ata(a_nft_ata, a_nft_mint)?;
         Validator::validate_token_owner(a_nft_ata, pda_account)?;
+        Validator::validate_token_program(token_program_account)?;
+        Validator::validate_system_program(system_program_account)?;
 
         let mut set_authority_user_ins = spl_token::instruction::set_authority(
             &spl_token::ID,
@@ -290,10 +643,26 @@ impl Processor {
         )?;
 
         if game_state_unpacked.user_b_joined {
-            Validator::validate_bool(game_state_unpacked.user_b_joined, true)?;
-            Validator::validate_equality(game_state_unpacked.user_b, *user_b.key)?;
-            Validator::validate_equality(*b_nft_ata.key, game_state_unpacked.b_nft_ata)?;
-            Validator::validate_equality(*b_nft_mint.key, game_state_unpacked.b_nft_mint)?;
+            Validator::validate_bool(
+                game_state_unpacked.user_b_joined,
+                true,
+                MetalityGameContractError::OpponentNotJoined,
+            )?;
+            Validator::validate_equality(
+                game_state_unpacked.user_b,
+                *user_b.key,
+                MetalityGameContractError::UserBMismatch,
+            )?;
+            Validator::validate_equality(
+                *b_nft_ata.key,
+                game_state_unpacked.b_nft_ata,
+                MetalityGameContractError::NftAtaMismatch,
+            )?;
+            Validator::validate_equality(
+                *b_nft_mint.key,
+                game_state_unpacked.b_nft_mint,
+                MetalityGameContractError::MintMismatch,
+            )?;
             Validator::validate_token_ata(b_nft_ata, b_nft_mint)?;
             Validator::validate_token_owner(b_nft_ata, pda_account)?;
 
@@ -321,7 +690,33 @@ impl Processor {
             )?;
         }
 
+        let profile_loader = ProfileLoader {
+            payer: user_a,
+            system_program_account,
+            program_id,
+        };
+
+        let mut a_profile = profile_loader.load(a_profile_account, user_a.key)?;
+        a_profile.record_cancel();
+        ProfileLoader::store(a_profile_account, a_profile)?;
+
+        if game_state_unpacked.user_b_joined {
+            let mut b_profile = profile_loader.load(b_profile_account, user_b.key)?;
+            b_profile.record_cancel();
+            ProfileLoader::store(b_profile_account, b_profile)?;
+        }
+
+        Self::release_join_bond(game_state_account, &mut game_state_unpacked, user_b, None)?;
+
+        Self::update_side_pool(
+            account_info_iter,
+            &game_state_unpacked,
+            program_id,
+            Some(POOL_OUTCOME_REFUND),
+        )?;
+
         game_state_unpacked.expired = true;
+        game_state_unpacked.outcome = GAME_OUTCOME_CANCELLED;
 
         MetalityGameContractState::pack(
             game_state_unpacked,
@@ -346,6 +741,10 @@ impl Processor {
 
         let token_program_account = next_account_info(account_info_iter)?;
 
+        let game_program_data_account = next_account_info(account_info_iter)?;
+
+        let system_program_account = next_account_info(account_info_iter)?;
+
         let (pda, _bump_seeds) = Pubkey::find_program_address(
             &[
                 "metality_game_contract".as_bytes(),
@@ -359,15 +758,71 @@ impl Processor {
 
         Validator::validate_is_signer(user_b)?;
         Validator::validate_state_account(game_state_account, *program_id)?;
-        Validator::validate_bool(game_state_unpacked.is_initialized, true)?;
-        Validator::validate_bool(game_state_unpacked.user_b_joined, false)?;
+        Validator::validate_bool(
+            game_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.user_b_joined,
+            false,
+            MetalityGameContractError::OpponentAlreadyJoined,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.expired,
+            false,
+            MetalityGameContractError::GameExpired,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.game_started,
+            false,
+            MetalityGameContractError::GameAlreadyStarted,
+        )?;
         Validator::validate_token_owner(nft_ata, user_b)?;
         Validator::validate_token_ata(nft_ata, nft_mint)?;
-        Validator::validate_equality(*pda_account.key, pda)?;
-        Validator::validate_equality(*pda_account.key, game_state_unpacked.pda_account)?;
+        Validator::validate_equality(
+            *pda_account.key,
+            pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_equality(
+            *pda_account.key,
+            game_state_unpacked.pda_account,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
         Validator::validate_same_resource(game_state_unpacked.user_a, *user_b.key)?;
         Validator::validate_same_resource(game_state_unpacked.a_nft_ata, *nft_ata.key)?;
         Validator::validate_same_resource(game_state_unpacked.a_nft_mint, *nft_mint.key)?;
+        Validator::validate_token_program(token_program_account)?;
+        Validator::validate_system_program(system_program_account)?;
+
+        if game_state_unpacked.is_blocked(user_b.key) {
+            return Err(MetalityGameContractError::OpponentBlocked.into());
+        }
+
+        Validator::validate_program_data_account(game_program_data_account, *program_id)?;
+
+        let game_program_data_unpacked =
+            MetalityGameProgramDataState::unpack(&game_program_data_account.try_borrow_data()?)?;
+
+        Validator::validate_nft_mint(nft_mint, game_program_data_unpacked.nft_check_level)?;
+
+        // Bounded games also take the joiner's profile, a missing profile
+        // counts as the default rating.
+        if game_state_unpacked.is_rating_bounded() {
+            let user_b_profile_account = next_account_info(account_info_iter)?;
+
+            let user_b_rating =
+                profile::read_rating(user_b_profile_account, user_b.key, program_id)?;
+
+            msg!("Joiner rating: {:?}", user_b_rating);
+
+            Validator::validate_rating_range(
+                user_b_rating,
+                game_state_unpacked.min_opponent_rating,
+                game_state_unpacked.max_opponent_rating,
+            )?;
+        }
 
         let set_authority_pda_ins = spl_token::instruction::set_authority(
             &spl_token::ID,
@@ -387,6 +842,25 @@ impl Processor {
             ],
         )?;
 
+        // The bond sits on the game state account on top of its rent, so it
+        // can be paid back out without a signed transfer.
+        if game_program_data_unpacked.join_bond_lamports > 0 {
+            invoke(
+                &system_instruction::transfer(
+                    user_b.key,
+                    game_state_account.key,
+                    game_program_data_unpacked.join_bond_lamports,
+                ),
+                &[
+                    user_b.clone(),
+                    game_state_account.clone(),
+                    system_program_account.clone(),
+                ],
+            )?;
+        }
+
+        game_state_unpacked.join_bond = game_program_data_unpacked.join_bond_lamports;
+        game_state_unpacked.bond_forfeit_bps = game_program_data_unpacked.bond_forfeit_bps;
         game_state_unpacked.user_b = *user_b.key;
         game_state_unpacked.b_nft_ata = *nft_ata.key;
         game_state_unpacked.b_nft_mint = *nft_mint.key;
@@ -400,323 +874,320 @@ impl Processor {
         Ok(())
     }
 
-    pub fn process_transfer_reward(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+    pub fn process_set_oracle(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        oracle: Pubkey,
+    ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
         let admin = next_account_info(account_info_iter)?;
 
-        let winner = next_account_info(account_info_iter)?;
+        let game_program_data_account = next_account_info(account_info_iter)?;
 
-        let loser = next_account_info(account_info_iter)?;
+        Validator::validate_admin(admin, game_program_data_account, *program_id)?;
 
-        let won_nft = next_account_info(account_info_iter)?;
+        let mut game_program_data_unpacked =
+            MetalityGameProgramDataState::unpack(&game_program_data_account.try_borrow_data()?)?;
 
-        let won_nft_mint = next_account_info(account_info_iter)?;
+        game_program_data_unpacked.oracle = oracle;
 
-        let owned_nft = next_account_info(account_info_iter)?;
+        MetalityGameProgramDataState::pack(
+            game_program_data_unpacked,
+            &mut game_program_data_account.try_borrow_mut_data()?,
+        )?;
 
-        let owned_nft_mint = next_account_info(account_info_iter)?;
+        Ok(())
+    }
 
-        let winner_won_nft_ata = next_account_info(account_info_iter)?;
+    pub fn process_set_join_bond(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        lamports: u64,
+        forfeit_bps: u16,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
 
-        let pda_account = next_account_info(account_info_iter)?;
+        let admin = next_account_info(account_info_iter)?;
 
-        let game_state_account = next_account_info(account_info_iter)?;
+        let game_program_data_account = next_account_info(account_info_iter)?;
 
-        let system_program_account = next_account_info(account_info_iter)?;
+        Validator::validate_admin(admin, game_program_data_account, *program_id)?;
 
-        let token_program_account = next_account_info(account_info_iter)?;
+        if forfeit_bps > BASIS_POINTS {
+            return Err(MetalityGameContractError::InvalidBondForfeit.into());
+        }
 
-        let rent_sysvar_account = next_account_info(account_info_iter)?;
+        let mut game_program_data_unpacked =
+            MetalityGameProgramDataState::unpack(&game_program_data_account.try_borrow_data()?)?;
 
-        let associated_token_account_program = next_account_info(account_info_iter)?;
+        game_program_data_unpacked.join_bond_lamports = lamports;
+        game_program_data_unpacked.bond_forfeit_bps = forfeit_bps;
 
-        let (pda, bump_seeds) = Pubkey::find_program_address(
-            &[
-                "metality_game_contract".as_bytes(),
-                game_state_account.key.as_ref(),
-            ],
-            program_id,
-        );
+        MetalityGameProgramDataState::pack(
+            game_program_data_unpacked,
+            &mut game_program_data_account.try_borrow_mut_data()?,
+        )?;
 
-        let mut game_state_unpacked =
-            MetalityGameContractState::unpack_unchecked(&game_state_account.try_borrow_data()?)?;
+        Ok(())
+    }
 
-        let winner_won_nft_ata_pubkey = get_associated_token_address(winner.key, won_nft_mint.key);
+    pub fn process_set_nft_check_level(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        level: u8,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
 
-        Validator::validate_admin(admin)?;
-        // Validator::validate_is_signer(winner)?;
-        Validator::validate_state_account(game_state_account, *program_id)?;
-        Validator::validate_winner(game_state_unpacked, *winner.key)?;
-        Validator::validate_bool(game_state_unpacked.is_initialized, true)?;
-        Validator::validate_bool(game_state_unpacked.user_b_joined, true)?;
-        Validator::validate_bool(game_state_unpacked.expired, false)?;
-        Validator::validate_bool(game_state_unpacked.game_started, true)?;
-        Validator::validate_token_owner(won_nft, pda_account)?;
-        Validator::validate_token_owner(owned_nft, pda_account)?;
-        Validator::validate_token_ata(won_nft, won_nft_mint)?;
-        Validator::validate_token_ata(owned_nft, owned_nft_mint)?;
-        Validator::validate_equality(*pda_account.key, pda)?;
-        Validator::validate_equality(*pda_account.key, game_state_unpacked.pda_account)?;
-        Validator::validate_equality(winner_won_nft_ata_pubkey, *winner_won_nft_ata.key)?;
+        let admin = next_account_info(account_info_iter)?;
 
-        if winner_won_nft_ata.data_is_empty() {
-            invoke(
-                &create_associated_token_account(admin.key, winner.key, won_nft_mint.key),
-                &[
-                    admin.clone(),
-                    winner_won_nft_ata.clone(),
-                    winner.clone(),
-                    won_nft_mint.clone(),
-                    system_program_account.clone(),
-                    token_program_account.clone(),
-                    rent_sysvar_account.clone(),
-                    associated_token_account_program.clone(),
-                ],
-            )?;
+        let game_program_data_account = next_account_info(account_info_iter)?;
+
+        Validator::validate_admin(admin, game_program_data_account, *program_id)?;
+
+        if level > NFT_CHECK_STRICT {
+            return Err(MetalityGameContractError::InvalidNftCheckLevel.into());
         }
 
-        invoke_signed(
-            &spl_token::instruction::set_authority(
-                &spl_token::ID,
-                owned_nft.key,
-                Some(winner.key),
-                spl_token::instruction::AuthorityType::AccountOwner,
-                pda_account.key,
-                &[],
-            )?,
-            &[
-                owned_nft.clone(),
-                pda_account.clone(),
-                token_program_account.clone(),
-            ],
-            &[&[
-                "metality_game_contract".as_bytes(),
-                game_state_account.key.as_ref(),
-                &[bump_seeds],
-            ]],
+        let mut game_program_data_unpacked =
+            MetalityGameProgramDataState::unpack(&game_program_data_account.try_borrow_data()?)?;
+
+        game_program_data_unpacked.nft_check_level = level;
+
+        MetalityGameProgramDataState::pack(
+            game_program_data_unpacked,
+            &mut game_program_data_account.try_borrow_mut_data()?,
         )?;
 
-        invoke_signed(
-            &spl_token::instruction::transfer(
-                &spl_token::ID,
-                won_nft.key,
-                winner_won_nft_ata.key,
-                pda_account.key,
-                &[],
-                1,
-            )?,
-            &[
-                won_nft.clone(),
-                winner_won_nft_ata.clone(),
-                pda_account.clone(),
-            ],
-            &[&[
-                "metality_game_contract".as_bytes(),
-                game_state_account.key.as_ref(),
-                &[bump_seeds],
-            ]],
-        )?;
+        Ok(())
+    }
 
-        invoke_signed(
-            &spl_token::instruction::set_authority(
-                &spl_token::ID,
-                won_nft.key,
-                Some(loser.key),
-                spl_token::instruction::AuthorityType::AccountOwner,
-                pda_account.key,
-                &[],
-            )?,
-            &[
-                won_nft.clone(),
-                pda_account.clone(),
-                token_program_account.clone(),
-            ],
-            &[&[
-                "metality_game_contract".as_bytes(),
-                game_state_account.key.as_ref(),
-                &[bump_seeds],
-            ]],
-        )?;
+    /// Rewrites a game or the program data account still in its legacy
+    /// layout into the current one, growing it in place so escrowed NFTs stay
+    /// with the game's PDA. Anyone may pay for the migration.
+    pub fn process_migrate_account(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
 
-        game_state_unpacked.expired = true;
+        let payer = next_account_info(account_info_iter)?;
 
-        MetalityGameContractState::pack(
-            game_state_unpacked,
-            &mut game_state_account.try_borrow_mut_data()?,
-        )?;
+        let account = next_account_info(account_info_iter)?;
+
+        let system_program_account = next_account_info(account_info_iter)?;
+
+        Validator::validate_is_signer(payer)?;
+
+        Validator::validate_system_program(system_program_account)?;
+
+        Validator::validate_state_account(account, *program_id)?;
+
+        match account.data_len() {
+            MetalityGameContractState::LEGACY_LEN => {
+                let game_state_unpacked =
+                    MetalityGameContractState::unpack_legacy(&account.try_borrow_data()?)?;
+
+                resize_account(
+                    payer,
+                    account,
+                    system_program_account,
+                    MetalityGameContractState::LEN,
+                )?;
+
+                MetalityGameContractState::pack(
+                    game_state_unpacked,
+                    &mut account.try_borrow_mut_data()?,
+                )?;
+            }
+            MetalityGameProgramDataState::LEGACY_LEN => {
+                Validator::validate_program_data_account(account, *program_id)?;
+
+                // The legacy program data was created by the deployer, who
+                // held admin rights before they were recorded.
+                let game_program_data_unpacked = MetalityGameProgramDataState::unpack_legacy(
+                    &account.try_borrow_data()?,
+                    crate::validations::admin::id(),
+                )?;
+
+                resize_account(
+                    payer,
+                    account,
+                    system_program_account,
+                    MetalityGameProgramDataState::LEN,
+                )?;
+
+                MetalityGameProgramDataState::pack(
+                    game_program_data_unpacked,
+                    &mut account.try_borrow_mut_data()?,
+                )?;
+            }
+            _ => return Err(MetalityGameContractError::NothingToMigrate.into()),
+        }
+
+        msg!("Migrated: {:?}", account.key);
 
         Ok(())
     }
 
-    pub fn process_draw_or_cancel_game(
+    pub fn process_submit_signed_result(
         accounts: &[AccountInfo],
         program_id: &Pubkey,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
-        let admin = next_account_info(account_info_iter)?;
+        let payer = next_account_info(account_info_iter)?;
 
-        let user_a = next_account_info(account_info_iter)?;
+        let game_program_data_account = next_account_info(account_info_iter)?;
 
-        let user_b = next_account_info(account_info_iter)?;
+        let instructions_sysvar_account = next_account_info(account_info_iter)?;
 
-        let a_nft_ata = next_account_info(account_info_iter)?;
+        let winner = next_account_info(account_info_iter)?;
 
-        let a_nft_mint = next_account_info(account_info_iter)?;
+        let game_state_account = next_account_info(account_info_iter)?;
 
-        let b_nft_ata = next_account_info(account_info_iter)?;
+        Validator::validate_is_signer(payer)?;
+        Validator::validate_program_data_account(game_program_data_account, *program_id)?;
 
-        let b_nft_mint = next_account_info(account_info_iter)?;
+        let game_program_data_unpacked =
+            MetalityGameProgramDataState::unpack(&game_program_data_account.try_borrow_data()?)?;
 
-        let pda_account = next_account_info(account_info_iter)?;
+        if !game_program_data_unpacked.has_oracle() {
+            return Err(MetalityGameContractError::OracleNotSet.into());
+        }
 
-        let game_state_account = next_account_info(account_info_iter)?;
+        oracle::verify_attestation(
+            instructions_sysvar_account,
+            &game_program_data_unpacked.oracle,
+            &oracle::result_message(game_state_account.key, winner.key),
+        )?;
 
-        let token_program_account = next_account_info(account_info_iter)?;
+        Self::record_winner(game_state_account, winner, program_id, GAME_OUTCOME_WON)
+    }
 
-        let (pda, bump_seeds) = Pubkey::find_program_address(
-            &[
-                "metality_game_contract".as_bytes(),
-                game_state_account.key.as_ref(),
-            ],
-            program_id,
-        );
+    /// TransferReward used to pay out in one go, taking the admin and the
+    /// payout accounts. Callers still sending that layout are told to claim
+    /// instead of having their accounts misread as a winner to record.
+    pub fn process_transfer_reward(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        if accounts.len() != 4 {
+            return Err(MetalityGameContractError::LegacyTransferReward.into());
+        }
 
-        let mut game_state_unpacked =
-            MetalityGameContractState::unpack_unchecked(&game_state_account.try_borrow_data()?)?;
+        Self::process_record_winner(accounts, program_id)
+    }
 
-        Validator::validate_admin(admin)?;
-        Validator::validate_state_account(game_state_account, *program_id)?;
-        Validator::validate_bool(game_state_unpacked.is_initialized, true)?;
-        Validator::validate_bool(game_state_unpacked.expired, false)?;
-        Validator::validate_token_owner(a_nft_ata, pda_account)?;
-        Validator::validate_token_ata(a_nft_ata, a_nft_mint)?;
-        Validator::validate_equality(*user_a.key, game_state_unpacked.user_a)?;
-        Validator::validate_equality(*a_nft_ata.key, game_state_unpacked.a_nft_ata)?;
-        Validator::validate_equality(*a_nft_mint.key, game_state_unpacked.a_nft_mint)?;
-        Validator::validate_equality(*pda_account.key, pda)?;
-        Validator::validate_equality(*pda_account.key, game_state_unpacked.pda_account)?;
+    pub fn process_record_winner(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
 
-        let mut set_authority_user_ins = spl_token::instruction::set_authority(
-            &spl_token::ID,
-            a_nft_ata.key,
-            Some(user_a.key),
-            spl_token::instruction::AuthorityType::AccountOwner,
-            pda_account.key,
-            &[],
-        )?;
+        let admin = next_account_info(account_info_iter)?;
 
-        invoke_signed(
-            &set_authority_user_ins,
-            &[
-                a_nft_ata.clone(),
-                pda_account.clone(),
-                token_program_account.clone(),
-            ],
-            &[&[
-                "metality_game_contract".as_bytes(),
-                game_state_account.key.as_ref(),
-                &[bump_seeds],
-            ]],
-        )?;
+        let game_program_data_account = next_account_info(account_info_iter)?;
 
-        if game_state_unpacked.user_b_joined {
-            Validator::validate_bool(game_state_unpacked.user_b_joined, true)?;
-            Validator::validate_token_owner(b_nft_ata, pda_account)?;
-            Validator::validate_token_ata(b_nft_ata, b_nft_mint)?;
-            Validator::validate_equality(*user_b.key, game_state_unpacked.user_b)?;
-            Validator::validate_equality(*b_nft_ata.key, game_state_unpacked.b_nft_ata)?;
-            Validator::validate_equality(*b_nft_mint.key, game_state_unpacked.b_nft_mint)?;
+        let winner = next_account_info(account_info_iter)?;
 
-            set_authority_user_ins = spl_token::instruction::set_authority(
-                &spl_token::ID,
-                b_nft_ata.key,
-                Some(user_b.key),
-                spl_token::instruction::AuthorityType::AccountOwner,
-                pda_account.key,
-                &[],
-            )?;
+        let game_state_account = next_account_info(account_info_iter)?;
 
-            invoke_signed(
-                &set_authority_user_ins,
-                &[
-                    b_nft_ata.clone(),
-                    pda_account.clone(),
-                    token_program_account.clone(),
-                ],
-                &[&[
-                    "metality_game_contract".as_bytes(),
-                    game_state_account.key.as_ref(),
-                    &[bump_seeds],
-                ]],
-            )?;
-        }
+        Validator::validate_admin(admin, game_program_data_account, *program_id)?;
 
-        game_state_unpacked.expired = true;
+        Self::record_winner(game_state_account, winner, program_id, GAME_OUTCOME_WON)
+    }
 
-        MetalityGameContractState::pack(
-            game_state_unpacked,
-            &mut game_state_account.try_borrow_mut_data()?,
-        )?;
+    pub fn process_claim_reward(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
 
-        Ok(())
+        let payer = next_account_info(account_info_iter)?;
+
+        let reward_accounts = RewardAccounts::next(account_info_iter)?;
+
+        Self::settle_reward(payer, &reward_accounts, account_info_iter, program_id)
     }
 
-    pub fn process_set_game_started(
+    pub fn process_set_claim_preference(
         accounts: &[AccountInfo],
         program_id: &Pubkey,
+        self_claim: bool,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
-        let admin = next_account_info(account_info_iter)?;
+        let player = next_account_info(account_info_iter)?;
 
-        let user_a = next_account_info(account_info_iter)?;
+        let profile_account = next_account_info(account_info_iter)?;
 
-        let user_b = next_account_info(account_info_iter)?;
+        let system_program_account = next_account_info(account_info_iter)?;
 
-        let a_nft_ata = next_account_info(account_info_iter)?;
+        Validator::validate_is_signer(player)?;
+        Validator::validate_system_program(system_program_account)?;
 
-        let a_nft_mint = next_account_info(account_info_iter)?;
+        let profile_loader = ProfileLoader {
+            payer: player,
+            system_program_account,
+            program_id,
+        };
 
-        let b_nft_ata = next_account_info(account_info_iter)?;
+        let mut profile = profile_loader.load(profile_account, player.key)?;
 
-        let b_nft_mint = next_account_info(account_info_iter)?;
+        profile.self_claim = self_claim;
 
-        let pda_account = next_account_info(account_info_iter)?;
+        ProfileLoader::store(profile_account, profile)
+    }
+
+    /// Records a forfeit in favor of the resigning player's opponent. The
+    /// payout is left to ClaimReward, like any other recorded winner.
+    pub fn process_resign(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let player = next_account_info(account_info_iter)?;
+
+        let winner = next_account_info(account_info_iter)?;
 
         let game_state_account = next_account_info(account_info_iter)?;
 
-        let (pda, _bump_seeds) = Pubkey::find_program_address(
-            &[
-                "metality_game_contract".as_bytes(),
-                game_state_account.key.as_ref(),
-            ],
-            program_id,
-        );
+        let game_state_unpacked =
+            MetalityGameContractState::unpack_unchecked(&game_state_account.try_borrow_data()?)?;
+
+        Validator::validate_is_signer(player)?;
+        Validator::validate_opponent(game_state_unpacked, *player.key, *winner.key)?;
+
+        msg!("Forfeit: {:?} resigned, {:?} wins", player.key, winner.key);
+
+        Self::record_winner(game_state_account, winner, program_id, GAME_OUTCOME_FORFEIT)
+    }
+
+    pub fn process_propose_mutual_cancel(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let player = next_account_info(account_info_iter)?;
+
+        let game_state_account = next_account_info(account_info_iter)?;
 
         let mut game_state_unpacked =
             MetalityGameContractState::unpack_unchecked(&game_state_account.try_borrow_data()?)?;
 
-        Validator::validate_admin(admin)?;
+        Validator::validate_is_signer(player)?;
         Validator::validate_state_account(game_state_account, *program_id)?;
-        Validator::validate_bool(game_state_unpacked.is_initialized, true)?;
-        Validator::validate_bool(game_state_unpacked.user_b_joined, true)?;
-        Validator::validate_bool(game_state_unpacked.expired, false)?;
-        Validator::validate_bool(game_state_unpacked.game_started, false)?;
-        Validator::validate_equality(*user_a.key, game_state_unpacked.user_a)?;
-        Validator::validate_equality(*user_b.key, game_state_unpacked.user_b)?;
-        Validator::validate_equality(*a_nft_ata.key, game_state_unpacked.a_nft_ata)?;
-        Validator::validate_equality(*a_nft_mint.key, game_state_unpacked.a_nft_mint)?;
-        Validator::validate_equality(*b_nft_ata.key, game_state_unpacked.b_nft_ata)?;
-        Validator::validate_equality(*b_nft_mint.key, game_state_unpacked.b_nft_mint)?;
-        Validator::validate_equality(*pda_account.key, pda)?;
-        Validator::validate_token_owner(a_nft_ata, pda_account)?;
-        Validator::validate_token_owner(b_nft_ata, pda_account)?;
-        Validator::validate_token_ata(a_nft_ata, a_nft_mint)?;
-        Validator::validate_token_ata(b_nft_ata, b_nft_mint)?;
+        Validator::validate_bool(
+            game_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.user_b_joined,
+            true,
+            MetalityGameContractError::OpponentNotJoined,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.expired,
+            false,
+            MetalityGameContractError::GameExpired,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.has_recorded_winner(),
+            false,
+            MetalityGameContractError::WinnerAlreadyRecorded,
+        )?;
+        Validator::validate_winner(game_state_unpacked, *player.key)?;
 
-        game_state_unpacked.game_started = true;
+        game_state_unpacked.cancel_proposer = *player.key;
 
         MetalityGameContractState::pack(
             game_state_unpacked,
@@ -726,17 +1197,21 @@ impl Processor {
         Ok(())
     }
 
-    pub fn process_user_b_exit_game(
+    pub fn process_accept_mutual_cancel(
         accounts: &[AccountInfo],
         program_id: &Pubkey,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
+        let player = next_account_info(account_info_iter)?;
+
+        let user_a = next_account_info(account_info_iter)?;
+
         let user_b = next_account_info(account_info_iter)?;
 
-        let b_nft_ata = next_account_info(account_info_iter)?;
+        let a_nft_ata = next_account_info(account_info_iter)?;
 
-        let b_nft_mint = next_account_info(account_info_iter)?;
+        let b_nft_ata = next_account_info(account_info_iter)?;
 
         let pda_account = next_account_info(account_info_iter)?;
 
@@ -744,57 +1219,117 @@ impl Processor {
 
         let token_program_account = next_account_info(account_info_iter)?;
 
-        let (pda, bump_seeds) = Pubkey::find_program_address(
-            &[
-                "metality_game_contract".as_bytes(),
-                game_state_account.key.as_ref(),
-            ],
-            program_id,
-        );
+        let a_profile_account = next_account_info(account_info_iter)?;
+
+        let b_profile_account = next_account_info(account_info_iter)?;
+
+        let system_program_account = next_account_info(account_info_iter)?;
+
+        let (pda, bump_seeds) = EscrowAuthority::find_pda(game_state_account.key, program_id);
 
         let mut game_state_unpacked =
             MetalityGameContractState::unpack_unchecked(&game_state_account.try_borrow_data()?)?;
 
-        Validator::validate_is_signer(user_b)?;
+        Validator::validate_is_signer(player)?;
         Validator::validate_state_account(game_state_account, *program_id)?;
-        Validator::validate_bool(game_state_unpacked.is_initialized, true)?;
-        Validator::validate_bool(game_state_unpacked.user_b_joined, true)?;
-        Validator::validate_bool(game_state_unpacked.expired, false)?;
-        Validator::validate_bool(game_state_unpacked.game_started, false)?;
-        Validator::validate_equality(*user_b.key, game_state_unpacked.user_b)?;
-        Validator::validate_equality(*b_nft_ata.key, game_state_unpacked.b_nft_ata)?;
-        Validator::validate_equality(*b_nft_mint.key, game_state_unpacked.b_nft_mint)?;
-        Validator::validate_equality(*pda_account.key, pda)?;
-        Validator::validate_token_owner(b_nft_ata, pda_account)?;
-        Validator::validate_token_ata(b_nft_ata, b_nft_mint)?;
-
-        let set_authority_user_ins = spl_token::instruction::set_authority(
-            &spl_token::ID,
-            b_nft_ata.key,
-            Some(user_b.key),
-            spl_token::instruction::AuthorityType::AccountOwner,
-            pda_account.key,
-            &[],
+        Validator::validate_bool(
+            game_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.user_b_joined,
+            true,
+            MetalityGameContractError::OpponentNotJoined,
         )?;
+        Validator::validate_bool(
+            game_state_unpacked.expired,
+            false,
+            MetalityGameContractError::GameExpired,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.has_recorded_winner(),
+            false,
+            MetalityGameContractError::WinnerAlreadyRecorded,
+        )?;
+        Validator::validate_winner(game_state_unpacked, *player.key)?;
+        Validator::validate_equality(
+            *user_a.key,
+            game_state_unpacked.user_a,
+            MetalityGameContractError::UserAMismatch,
+        )?;
+        Validator::validate_equality(
+            *user_b.key,
+            game_state_unpacked.user_b,
+            MetalityGameContractError::UserBMismatch,
+        )?;
+        Validator::validate_equality(
+            *a_nft_ata.key,
+            game_state_unpacked.a_nft_ata,
+            MetalityGameContractError::NftAtaMismatch,
+        )?;
+        Validator::validate_equality(
+            *b_nft_ata.key,
+            game_state_unpacked.b_nft_ata,
+            MetalityGameContractError::NftAtaMismatch,
+        )?;
+        Validator::validate_equality(
+            *pda_account.key,
+            pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_equality(
+            *pda_account.key,
+            game_state_unpacked.pda_account,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_token_program(token_program_account)?;
+        Validator::validate_system_program(system_program_account)?;
+
+        // Both players have signed once the other one accepts the proposal.
+        if game_state_unpacked.cancel_proposer == Pubkey::default()
+            || game_state_unpacked.cancel_proposer == *player.key
+        {
+            return Err(MetalityGameContractError::NoCancelProposal.into());
+        }
 
-        invoke_signed(
-            &set_authority_user_ins,
-            &[
-                b_nft_ata.clone(),
-                pda_account.clone(),
-                token_program_account.clone(),
-            ],
-            &[&[
-                "metality_game_contract".as_bytes(),
-                game_state_account.key.as_ref(),
-                &[bump_seeds],
-            ]],
+        let escrow = EscrowAuthority {
+            pda_account,
+            escrow_state_account: game_state_account,
+            bump_seeds,
+            token_program_account,
+        };
+
+        escrow.release_stake(a_nft_ata, user_a.key)?;
+        escrow.release_stake(b_nft_ata, user_b.key)?;
+
+        let profile_loader = ProfileLoader {
+            payer: player,
+            system_program_account,
+            program_id,
+        };
+
+        let mut a_profile = profile_loader.load(a_profile_account, user_a.key)?;
+        a_profile.record_cancel();
+        ProfileLoader::store(a_profile_account, a_profile)?;
+
+        let mut b_profile = profile_loader.load(b_profile_account, user_b.key)?;
+        b_profile.record_cancel();
+        ProfileLoader::store(b_profile_account, b_profile)?;
+
+        Self::release_join_bond(game_state_account, &mut game_state_unpacked, user_b, None)?;
+
+        Self::update_side_pool(
+            account_info_iter,
+            &game_state_unpacked,
+            program_id,
+            Some(POOL_OUTCOME_REFUND),
         )?;
 
-        game_state_unpacked.user_b_joined = false;
-        game_state_unpacked.user_b = Pubkey::default();
-        game_state_unpacked.b_nft_ata = Pubkey::default();
-        game_state_unpacked.b_nft_mint = Pubkey::default();
+        msg!("Game cancelled by both players");
+
+        game_state_unpacked.expired = true;
+        game_state_unpacked.outcome = GAME_OUTCOME_CANCELLED;
 
         MetalityGameContractState::pack(
             game_state_unpacked,
@@ -803,4 +1338,2996 @@ impl Processor {
 
         Ok(())
     }
+
+    /// Records `winner` of a running game with `outcome`. Nothing moves until
+    /// the reward is claimed through ClaimReward.
+    fn record_winner(
+        game_state_account: &AccountInfo,
+        winner: &AccountInfo,
+        program_id: &Pubkey,
+        outcome: u8,
+    ) -> ProgramResult {
+        let mut game_state_unpacked =
+            MetalityGameContractState::unpack_unchecked(&game_state_account.try_borrow_data()?)?;
+
+        Validator::validate_state_account(game_state_account, *program_id)?;
+        Validator::validate_winner(game_state_unpacked, *winner.key)?;
+        Validator::validate_bool(
+            game_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.user_b_joined,
+            true,
+            MetalityGameContractError::OpponentNotJoined,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.expired,
+            false,
+            MetalityGameContractError::GameExpired,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.game_started,
+            true,
+            MetalityGameContractError::GameNotStarted,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.has_recorded_winner(),
+            false,
+            MetalityGameContractError::WinnerAlreadyRecorded,
+        )?;
+
+        game_state_unpacked.winner = *winner.key;
+        game_state_unpacked.outcome = outcome;
+
+        msg!("Recorded winner: {:?}", winner.key);
+
+        MetalityGameContractState::pack(
+            game_state_unpacked,
+            &mut game_state_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    /// Pays out a game to its recorded winner, with `payer` covering any
+    /// accounts that need creating along the way.
+    fn settle_reward<'a, 'b: 'a, I: Iterator<Item = &'a AccountInfo<'b>>>(
+        payer: &'a AccountInfo<'b>,
+        reward_accounts: &RewardAccounts<'a, 'b>,
+        account_info_iter: &mut I,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let RewardAccounts {
+            winner,
+            loser,
+            won_nft,
+            won_nft_mint,
+            owned_nft,
+            owned_nft_mint,
+            winner_won_nft_ata,
+            pda_account,
+            game_state_account,
+            system_program_account,
+            token_program_account,
+            rent_sysvar_account,
+            associated_token_account_program,
+            winner_profile_account,
+            loser_profile_account,
+        } = *reward_accounts;
+
+        let (pda, bump_seeds) = Pubkey::find_program_address(
+            &[
+                "metality_game_contract".as_bytes(),
+                game_state_account.key.as_ref(),
+            ],
+            program_id,
+        );
+
+        let mut game_state_unpacked =
+            MetalityGameContractState::unpack_unchecked(&game_state_account.try_borrow_data()?)?;
+
+        let winner_won_nft_ata_pubkey = get_associated_token_address(winner.key, won_nft_mint.key);
+
+        let loser_pubkey = if *winner.key == game_state_unpacked.user_a {
+            game_state_unpacked.user_b
+        } else {
+            game_state_unpacked.user_a
+        };
+
+        Validator::validate_state_account(game_state_account, *program_id)?;
+        Validator::validate_winner(game_state_unpacked, *winner.key)?;
+        Validator::validate_equality(
+            *loser.key,
+            loser_pubkey,
+            MetalityGameContractError::LoserMismatch,
+        )?;
+        Validator::validate_token_program(token_program_account)?;
+        Validator::validate_system_program(system_program_account)?;
+        Validator::validate_rent_sysvar(rent_sysvar_account)?;
+        Validator::validate_associated_token_program(associated_token_account_program)?;
+
+        Validator::validate_is_signer(payer)?;
+        Validator::validate_reward_claimant(
+            profile::read_self_claim(winner_profile_account, winner.key, program_id)?,
+            winner,
+            payer,
+        )?;
+
+        if !game_state_unpacked.has_recorded_winner() {
+            return Err(MetalityGameContractError::WinnerNotRecorded.into());
+        }
+
+        if game_state_unpacked.winner != *winner.key {
+            return Err(MetalityGameContractError::InvalidWinner.into());
+        }
+
+        Validator::validate_bool(
+            game_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.user_b_joined,
+            true,
+            MetalityGameContractError::OpponentNotJoined,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.expired,
+            false,
+            MetalityGameContractError::GameExpired,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.game_started,
+            true,
+            MetalityGameContractError::GameNotStarted,
+        )?;
+        Validator::validate_token_owner(won_nft, pda_account)?;
+        Validator::validate_token_owner(owned_nft, pda_account)?;
+        Validator::validate_token_ata(won_nft, won_nft_mint)?;
+        Validator::validate_token_ata(owned_nft, owned_nft_mint)?;
+        Validator::validate_equality(
+            *pda_account.key,
+            pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_equality(
+            *pda_account.key,
+            game_state_unpacked.pda_account,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_equality(
+            winner_won_nft_ata_pubkey,
+            *winner_won_nft_ata.key,
+            MetalityGameContractError::RecipientAtaMismatch,
+        )?;
+
+        if winner_won_nft_ata.data_is_empty() {
+            invoke(
+                &create_associated_token_account(payer.key, winner.key, won_nft_mint.key),
+                &[
+                    payer.clone(),
+                    winner_won_nft_ata.clone(),
+                    winner.clone(),
+                    won_nft_mint.clone(),
+                    system_program_account.clone(),
+                    token_program_account.clone(),
+                    rent_sysvar_account.clone(),
+                    associated_token_account_program.clone(),
+                ],
+            )?;
+        }
+
+        invoke_signed(
+            &spl_token::instruction::set_authority(
+                &spl_token::ID,
+                owned_nft.key,
+                Some(winner.key),
+                spl_token::instruction::AuthorityType::AccountOwner,
+                pda_account.key,
+                &[],
+            )?,
+            &[
+                owned_nft.clone(),
+                pda_account.clone(),
+                token_program_account.clone(),
+            ],
+            &[&[
+                "metality_game_contract".as_bytes(),
+                game_state_account.key.as_ref(),
+                &[bump_seeds],
+            ]],
+        )?;
+
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                &spl_token::ID,
+                won_nft.key,
+                winner_won_nft_ata.key,
+                pda_account.key,
+                &[],
+                1,
+            )?,
+            &[
+                won_nft.clone(),
+                winner_won_nft_ata.clone(),
+                pda_account.clone(),
+            ],
+            &[&[
+                "metality_game_contract".as_bytes(),
+                game_state_account.key.as_ref(),
+                &[bump_seeds],
+            ]],
+        )?;
+
+        invoke_signed(
+            &spl_token::instruction::set_authority(
+                &spl_token::ID,
+                won_nft.key,
+                Some(loser.key),
+                spl_token::instruction::AuthorityType::AccountOwner,
+                pda_account.key,
+                &[],
+            )?,
+            &[
+                won_nft.clone(),
+                pda_account.clone(),
+                token_program_account.clone(),
+            ],
+            &[&[
+                "metality_game_contract".as_bytes(),
+                game_state_account.key.as_ref(),
+                &[bump_seeds],
+            ]],
+        )?;
+
+        let outcome_accounts = OutcomeAccounts {
+            winner,
+            loser,
+            game_state_account,
+            system_program_account,
+            winner_profile_account,
+            loser_profile_account,
+        };
+
+        Self::conclude_win(
+            &outcome_accounts,
+            payer,
+            &mut game_state_unpacked,
+            account_info_iter,
+            program_id,
+        )
+    }
+
+    /// Books a won game once its stakes are dealt with: updates both profiles
+    /// and ratings, returns user B's join bond, settles the side pool and
+    /// expires the game. `payer` covers any profile created on the way.
+    fn conclude_win<'a, 'b: 'a, I: Iterator<Item = &'a AccountInfo<'b>>>(
+        outcome_accounts: &OutcomeAccounts<'a, 'b>,
+        payer: &'a AccountInfo<'b>,
+        game_state: &mut MetalityGameContractState,
+        account_info_iter: &mut I,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let OutcomeAccounts {
+            winner,
+            loser,
+            game_state_account,
+            system_program_account,
+            winner_profile_account,
+            loser_profile_account,
+        } = *outcome_accounts;
+
+        let profile_loader = ProfileLoader {
+            payer,
+            system_program_account,
+            program_id,
+        };
+
+        let mut winner_profile = profile_loader.load(winner_profile_account, winner.key)?;
+        let mut loser_profile = profile_loader.load(loser_profile_account, loser.key)?;
+
+        winner_profile.record_win();
+        loser_profile.record_loss();
+
+        (winner_profile.rating, loser_profile.rating) =
+            rating::elo_update(winner_profile.rating, loser_profile.rating, Score::Win);
+
+        msg!(
+            "Ratings: winner {:?}, loser {:?}",
+            winner_profile.rating,
+            loser_profile.rating
+        );
+
+        ProfileLoader::store(winner_profile_account, winner_profile)?;
+        ProfileLoader::store(loser_profile_account, loser_profile)?;
+
+        let winning_side = if *winner.key == game_state.user_a {
+            SIDE_USER_A
+        } else {
+            SIDE_USER_B
+        };
+
+        let user_b = if *winner.key == game_state.user_b {
+            winner
+        } else {
+            loser
+        };
+
+        Self::release_join_bond(game_state_account, game_state, user_b, None)?;
+
+        Self::update_side_pool(
+            account_info_iter,
+            game_state,
+            program_id,
+            Some(winning_side),
+        )?;
+
+        game_state.expired = true;
+
+        MetalityGameContractState::pack(
+            *game_state,
+            &mut game_state_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_draw_or_cancel_game(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let admin = next_account_info(account_info_iter)?;
+
+        let game_program_data_account = next_account_info(account_info_iter)?;
+
+        let user_a = next_account_info(account_info_iter)?;
+
+        let user_b = next_account_info(account_info_iter)?;
+
+        let a_nft_ata = next_account_info(account_info_iter)?;
+
+        let a_nft_mint = next_account_info(account_info_iter)?;
+
+        let b_nft_ata = next_account_info(account_info_iter)?;
+
+        let b_nft_mint = next_account_info(account_info_iter)?;
+
+        let pda_account = next_account_info(account_info_iter)?;
+
+        let game_state_account = next_account_info(account_info_iter)?;
+
+        let token_program_account = next_account_info(account_info_iter)?;
+
+        let a_profile_account = next_account_info(account_info_iter)?;
+
+        let b_profile_account = next_account_info(account_info_iter)?;
+
+        let system_program_account = next_account_info(account_info_iter)?;
+
+        let (pda, bump_seeds) = Pubkey::find_program_address(
+            &[
+                "metality_game_contract".as_bytes(),
+                game_state_account.key.as_ref(),
+            ],
+            program_id,
+        );
+
+        let mut game_state_unpacked =
+            MetalityGameContractState::unpack_unchecked(&game_state_account.try_borrow_data()?)?;
+
+        Validator::validate_admin(admin, game_program_data_account, *program_id)?;
+        Validator::validate_state_account(game_state_account, *program_id)?;
+        Validator::validate_bool(
+            game_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.expired,
+            false,
+            MetalityGameContractError::GameExpired,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.has_recorded_winner(),
+            false,
+            MetalityGameContractError::WinnerAlreadyRecorded,
+        )?;
+        Validator::validate_token_owner(a_nft_ata, pda_account)?;
+        Validator::validate_token_ata(a_nft_ata, a_nft_mint)?;
+        Validator::validate_equality(
+            *user_a.key,
+            game_state_unpacked.user_a,
+            MetalityGameContractError::UserAMismatch,
+        )?;
+        Validator::validate_equality(
+            *a_nft_ata.key,
+            game_state_unpacked.a_nft_ata,
+            MetalityGameContractError::NftAtaMismatch,
+        )?;
+        Validator::validate_equality(
+            *a_nft_mint.key,
+            game_state_unpacked.a_nft_mint,
+            MetalityGameContractError::MintMismatch,
+        )?;
+        Validator::validate_equality(
+            *pda_account.key,
+            pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_equality(
+            *pda_account.key,
+            game_state_unpacked.pda_account,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_token_program(token_program_account)?;
+        Validator::validate_system_program(system_program_account)?;
+
+        let mut set_authority_user_ins = spl_token::instruction::set_authority(
+            &spl_token::ID,
+            a_nft_ata.key,
+            Some(user_a.key),
+            spl_token::instruction::AuthorityType::AccountOwner,
+            pda_account.key,
+            &[],
+        )?;
+
+        invoke_signed(
+            &set_authority_user_ins,
+            &[
+                a_nft_ata.clone(),
+                pda_account.clone(),
+                token_program_account.clone(),
+            ],
+            &[&[
+                "metality_game_contract".as_bytes(),
+                game_state_account.key.as_ref(),
+                &[bump_seeds],
+            ]],
+        )?;
+
+        if game_state_unpacked.user_b_joined {
+            Validator::validate_bool(
+                game_state_unpacked.user_b_joined,
+                true,
+                MetalityGameContractError::OpponentNotJoined,
+            )?;
+            Validator::validate_token_owner(b_nft_ata, pda_account)?;
+            Validator::validate_token_ata(b_nft_ata, b_nft_mint)?;
+            Validator::validate_equality(
+                *user_b.key,
+                game_state_unpacked.user_b,
+                MetalityGameContractError::UserBMismatch,
+            )?;
+            Validator::validate_equality(
+                *b_nft_ata.key,
+                game_state_unpacked.b_nft_ata,
+                MetalityGameContractError::NftAtaMismatch,
+            )?;
+            Validator::validate_equality(
+                *b_nft_mint.key,
+                game_state_unpacked.b_nft_mint,
+                MetalityGameContractError::MintMismatch,
+            )?;
+
+            set_authority_user_ins = spl_token::instruction::set_authority(
+                &spl_token::ID,
+                b_nft_ata.key,
+                Some(user_b.key),
+                spl_token::instruction::AuthorityType::AccountOwner,
+                pda_account.key,
+                &[],
+            )?;
+
+            invoke_signed(
+                &set_authority_user_ins,
+                &[
+                    b_nft_ata.clone(),
+                    pda_account.clone(),
+                    token_program_account.clone(),
+                ],
+                &[&[
+                    "metality_game_contract".as_bytes(),
+                    game_state_account.key.as_ref(),
+                    &[bump_seeds],
+                ]],
+            )?;
+        }
+
+        // A started game ending here is a draw, otherwise it never got played.
+        let profile_loader = ProfileLoader {
+            payer: admin,
+            system_program_account,
+            program_id,
+        };
+
+        let mut a_profile = profile_loader.load(a_profile_account, user_a.key)?;
+
+        if game_state_unpacked.user_b_joined {
+            let mut b_profile = profile_loader.load(b_profile_account, user_b.key)?;
+
+            if game_state_unpacked.game_started {
+                a_profile.record_draw();
+                b_profile.record_draw();
+
+                (a_profile.rating, b_profile.rating) =
+                    rating::elo_update(a_profile.rating, b_profile.rating, Score::Draw);
+
+                msg!(
+                    "Ratings: user a {:?}, user b {:?}",
+                    a_profile.rating,
+                    b_profile.rating
+                );
+            } else {
+                a_profile.record_cancel();
+                b_profile.record_cancel();
+            }
+
+            ProfileLoader::store(b_profile_account, b_profile)?;
+        } else {
+            a_profile.record_cancel();
+        }
+
+        ProfileLoader::store(a_profile_account, a_profile)?;
+
+        Self::release_join_bond(game_state_account, &mut game_state_unpacked, user_b, None)?;
+
+        Self::update_side_pool(
+            account_info_iter,
+            &game_state_unpacked,
+            program_id,
+            Some(POOL_OUTCOME_REFUND),
+        )?;
+
+        game_state_unpacked.expired = true;
+        game_state_unpacked.outcome =
+            if game_state_unpacked.user_b_joined && game_state_unpacked.game_started {
+                GAME_OUTCOME_DRAW
+            } else {
+                GAME_OUTCOME_CANCELLED
+            };
+
+        MetalityGameContractState::pack(
+            game_state_unpacked,
+            &mut game_state_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_set_game_started(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let admin = next_account_info(account_info_iter)?;
+
+        let game_program_data_account = next_account_info(account_info_iter)?;
+
+        let user_a = next_account_info(account_info_iter)?;
+
+        let user_b = next_account_info(account_info_iter)?;
+
+        let a_nft_ata = next_account_info(account_info_iter)?;
+
+        let a_nft_mint = next_account_info(account_info_iter)?;
+
+        let b_nft_ata = next_account_info(account_info_iter)?;
+
+        let b_nft_mint = next_account_info(account_info_iter)?;
+
+        let pda_account = next_account_info(account_info_iter)?;
+
+        let game_state_account = next_account_info(account_info_iter)?;
+
+        let (pda, _bump_seeds) = Pubkey::find_program_address(
+            &[
+                "metality_game_contract".as_bytes(),
+                game_state_account.key.as_ref(),
+            ],
+            program_id,
+        );
+
+        let mut game_state_unpacked =
+            MetalityGameContractState::unpack_unchecked(&game_state_account.try_borrow_data()?)?;
+
+        Validator::validate_admin(admin, game_program_data_account, *program_id)?;
+        Validator::validate_state_account(game_state_account, *program_id)?;
+        Validator::validate_bool(
+            game_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.user_b_joined,
+            true,
+            MetalityGameContractError::OpponentNotJoined,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.expired,
+            false,
+            MetalityGameContractError::GameExpired,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.game_started,
+            false,
+            MetalityGameContractError::GameAlreadyStarted,
+        )?;
+        Validator::validate_equality(
+            *user_a.key,
+            game_state_unpacked.user_a,
+            MetalityGameContractError::UserAMismatch,
+        )?;
+        Validator::validate_equality(
+            *user_b.key,
+            game_state_unpacked.user_b,
+            MetalityGameContractError::UserBMismatch,
+        )?;
+        Validator::validate_equality(
+            *a_nft_ata.key,
+            game_state_unpacked.a_nft_ata,
+            MetalityGameContractError::NftAtaMismatch,
+        )?;
+        Validator::validate_equality(
+            *a_nft_mint.key,
+            game_state_unpacked.a_nft_mint,
+            MetalityGameContractError::MintMismatch,
+        )?;
+        Validator::validate_equality(
+            *b_nft_ata.key,
+            game_state_unpacked.b_nft_ata,
+            MetalityGameContractError::NftAtaMismatch,
+        )?;
+        Validator::validate_equality(
+            *b_nft_mint.key,
+            game_state_unpacked.b_nft_mint,
+            MetalityGameContractError::MintMismatch,
+        )?;
+        Validator::validate_equality(
+            *pda_account.key,
+            pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_token_owner(a_nft_ata, pda_account)?;
+        Validator::validate_token_owner(b_nft_ata, pda_account)?;
+        Validator::validate_token_ata(a_nft_ata, a_nft_mint)?;
+        Validator::validate_token_ata(b_nft_ata, b_nft_mint)?;
+
+        Self::update_side_pool(account_info_iter, &game_state_unpacked, program_id, None)?;
+
+        game_state_unpacked.game_started = true;
+
+        MetalityGameContractState::pack(
+            game_state_unpacked,
+            &mut game_state_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_user_b_exit_game(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let user_b = next_account_info(account_info_iter)?;
+
+        let b_nft_ata = next_account_info(account_info_iter)?;
+
+        let b_nft_mint = next_account_info(account_info_iter)?;
+
+        let pda_account = next_account_info(account_info_iter)?;
+
+        let game_state_account = next_account_info(account_info_iter)?;
+
+        let token_program_account = next_account_info(account_info_iter)?;
+
+        let (pda, bump_seeds) = Pubkey::find_program_address(
+            &[
+                "metality_game_contract".as_bytes(),
+                game_state_account.key.as_ref(),
+            ],
+            program_id,
+        );
+
+        let mut game_state_unpacked =
+            MetalityGameContractState::unpack_unchecked(&game_state_account.try_borrow_data()?)?;
+
+        Validator::validate_is_signer(user_b)?;
+        Validator::validate_state_account(game_state_account, *program_id)?;
+        Validator::validate_bool(
+            game_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.user_b_joined,
+            true,
+            MetalityGameContractError::OpponentNotJoined,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.expired,
+            false,
+            MetalityGameContractError::GameExpired,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.game_started,
+            false,
+            MetalityGameContractError::GameAlreadyStarted,
+        )?;
+        Validator::validate_equality(
+            *user_b.key,
+            game_state_unpacked.user_b,
+            MetalityGameContractError::UserBMismatch,
+        )?;
+        Validator::validate_equality(
+            *b_nft_ata.key,
+            game_state_unpacked.b_nft_ata,
+            MetalityGameContractError::NftAtaMismatch,
+        )?;
+        Validator::validate_equality(
+            *b_nft_mint.key,
+            game_state_unpacked.b_nft_mint,
+            MetalityGameContractError::MintMismatch,
+        )?;
+        Validator::validate_equality(
+            *pda_account.key,
+            pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_token_owner(b_nft_ata, pda_account)?;
+        Validator::validate_token_ata(b_nft_ata, b_nft_mint)?;
+        Validator::validate_token_program(token_program_account)?;
+
+        let set_authority_user_ins = spl_token::instruction::set_authority(
+            &spl_token::ID,
+            b_nft_ata.key,
+            Some(user_b.key),
+            spl_token::instruction::AuthorityType::AccountOwner,
+            pda_account.key,
+            &[],
+        )?;
+
+        invoke_signed(
+            &set_authority_user_ins,
+            &[
+                b_nft_ata.clone(),
+                pda_account.clone(),
+                token_program_account.clone(),
+            ],
+            &[&[
+                "metality_game_contract".as_bytes(),
+                game_state_account.key.as_ref(),
+                &[bump_seeds],
+            ]],
+        )?;
+
+        // Leaving before the game starts costs user B part of the join bond.
+        if game_state_unpacked.join_bond > 0 {
+            let user_a = next_account_info(account_info_iter)?;
+
+            Validator::validate_equality(
+                *user_a.key,
+                game_state_unpacked.user_a,
+                MetalityGameContractError::UserAMismatch,
+            )?;
+
+            Self::release_join_bond(
+                game_state_account,
+                &mut game_state_unpacked,
+                user_b,
+                Some(user_a),
+            )?;
+        }
+
+        Self::reopen_game(account_info_iter, &mut game_state_unpacked, program_id)?;
+
+        MetalityGameContractState::pack(
+            game_state_unpacked,
+            &mut game_state_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_kick_opponent(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let user_a = next_account_info(account_info_iter)?;
+
+        let user_b = next_account_info(account_info_iter)?;
+
+        let b_nft_ata = next_account_info(account_info_iter)?;
+
+        let b_nft_mint = next_account_info(account_info_iter)?;
+
+        let pda_account = next_account_info(account_info_iter)?;
+
+        let game_state_account = next_account_info(account_info_iter)?;
+
+        let token_program_account = next_account_info(account_info_iter)?;
+
+        let (pda, bump_seeds) = EscrowAuthority::find_pda(game_state_account.key, program_id);
+
+        let mut game_state_unpacked =
+            MetalityGameContractState::unpack_unchecked(&game_state_account.try_borrow_data()?)?;
+
+        Validator::validate_is_signer(user_a)?;
+        Validator::validate_state_account(game_state_account, *program_id)?;
+        Validator::validate_bool(
+            game_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.user_b_joined,
+            true,
+            MetalityGameContractError::OpponentNotJoined,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.expired,
+            false,
+            MetalityGameContractError::GameExpired,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.game_started,
+            false,
+            MetalityGameContractError::GameAlreadyStarted,
+        )?;
+        Validator::validate_equality(
+            *user_a.key,
+            game_state_unpacked.user_a,
+            MetalityGameContractError::UserAMismatch,
+        )?;
+        Validator::validate_equality(
+            *user_b.key,
+            game_state_unpacked.user_b,
+            MetalityGameContractError::UserBMismatch,
+        )?;
+        Validator::validate_equality(
+            *b_nft_ata.key,
+            game_state_unpacked.b_nft_ata,
+            MetalityGameContractError::NftAtaMismatch,
+        )?;
+        Validator::validate_equality(
+            *b_nft_mint.key,
+            game_state_unpacked.b_nft_mint,
+            MetalityGameContractError::MintMismatch,
+        )?;
+        Validator::validate_equality(
+            *pda_account.key,
+            pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_token_owner(b_nft_ata, pda_account)?;
+        Validator::validate_token_ata(b_nft_ata, b_nft_mint)?;
+        Validator::validate_token_program(token_program_account)?;
+
+        EscrowAuthority {
+            pda_account,
+            escrow_state_account: game_state_account,
+            bump_seeds,
+            token_program_account,
+        }
+        .release_stake(b_nft_ata, user_b.key)?;
+
+        // A kicked opponent didn't choose to leave, so the bond comes back in full.
+        Self::release_join_bond(game_state_account, &mut game_state_unpacked, user_b, None)?;
+
+        msg!("Kicked opponent: {:?}", user_b.key);
+
+        Self::reopen_game(account_info_iter, &mut game_state_unpacked, program_id)?;
+
+        MetalityGameContractState::pack(
+            game_state_unpacked,
+            &mut game_state_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_set_opponent_blocked(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        opponent: &Pubkey,
+        blocked: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let user_a = next_account_info(account_info_iter)?;
+
+        let game_state_account = next_account_info(account_info_iter)?;
+
+        let mut game_state_unpacked =
+            MetalityGameContractState::unpack_unchecked(&game_state_account.try_borrow_data()?)?;
+
+        Validator::validate_is_signer(user_a)?;
+        Validator::validate_state_account(game_state_account, *program_id)?;
+        Validator::validate_bool(
+            game_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.expired,
+            false,
+            MetalityGameContractError::GameExpired,
+        )?;
+        Validator::validate_equality(
+            *user_a.key,
+            game_state_unpacked.user_a,
+            MetalityGameContractError::UserAMismatch,
+        )?;
+
+        game_state_unpacked.set_blocked(opponent, blocked)?;
+
+        MetalityGameContractState::pack(
+            game_state_unpacked,
+            &mut game_state_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_initialize_lobby(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        max_players: u8,
+        payout_table: [u8; MAX_LOBBY_PLAYERS],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let creator = next_account_info(account_info_iter)?;
+
+        let nft_ata = next_account_info(account_info_iter)?;
+
+        let nft_mint = next_account_info(account_info_iter)?;
+
+        let pda_account = next_account_info(account_info_iter)?;
+
+        let lobby_state_account = next_account_info(account_info_iter)?;
+
+        let game_program_data_account = next_account_info(account_info_iter)?;
+
+        let token_program_account = next_account_info(account_info_iter)?;
+
+        let system_program_account = next_account_info(account_info_iter)?;
+
+        if !(MIN_LOBBY_PLAYERS..=MAX_LOBBY_PLAYERS).contains(&(max_players as usize)) {
+            return Err(MetalityGameContractError::InvalidLobbySize.into());
+        }
+
+        MetalityLobbyState::validate_payout_table(&payout_table, max_players)?;
+
+        let mut game_program_data_unpacked =
+            MetalityGameProgramDataState::unpack(&game_program_data_account.try_borrow_data()?)?;
+
+        msg!("Index: {:?}", game_program_data_unpacked.index);
+
+        let (pda, _bump_seeds) = EscrowAuthority::find_pda(lobby_state_account.key, program_id);
+
+        let seed = game_program_data_unpacked.indexed_seed(LOBBY_SEED_PREFIX)?;
+
+        Validator::validate_is_signer(creator)?;
+        Validator::validate_program_data_account(game_program_data_account, *program_id)?;
+        Validator::validate_token_owner(nft_ata, creator)?;
+        Validator::validate_token_ata(nft_ata, nft_mint)?;
+        Validator::validate_nft_mint(nft_mint, game_program_data_unpacked.nft_check_level)?;
+        Validator::validate_equality(
+            *pda_account.key,
+            pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_token_program(token_program_account)?;
+        Validator::validate_system_program(system_program_account)?;
+
+        create_seeded_account(
+            creator,
+            lobby_state_account,
+            system_program_account,
+            &seed,
+            MetalityLobbyState::LEN,
+            program_id,
+        )?;
+
+        let mut lobby_state_unpacked =
+            MetalityLobbyState::unpack_unchecked(&lobby_state_account.try_borrow_data()?)?;
+
+        invoke(
+            &spl_token::instruction::set_authority(
+                &spl_token::ID,
+                nft_ata.key,
+                Some(pda_account.key),
+                spl_token::instruction::AuthorityType::AccountOwner,
+                creator.key,
+                &[creator.key],
+            )?,
+            &[
+                nft_ata.clone(),
+                creator.clone(),
+                token_program_account.clone(),
+            ],
+        )?;
+
+        lobby_state_unpacked.is_initialized = true;
+        lobby_state_unpacked.creator = *creator.key;
+        lobby_state_unpacked.pda_account = *pda_account.key;
+        lobby_state_unpacked.max_players = max_players;
+        lobby_state_unpacked.player_count = 1;
+        lobby_state_unpacked.expired = false;
+        lobby_state_unpacked.game_started = false;
+        lobby_state_unpacked.payout_table = payout_table;
+        lobby_state_unpacked.entrants[0] = MetalityLobbyEntrant {
+            user: *creator.key,
+            nft_ata: *nft_ata.key,
+            nft_mint: *nft_mint.key,
+        };
+        lobby_state_unpacked.recipients = [0; MAX_LOBBY_PLAYERS];
+        lobby_state_unpacked.released = 0;
+
+        MetalityLobbyState::pack(
+            lobby_state_unpacked,
+            &mut lobby_state_account.try_borrow_mut_data()?,
+        )?;
+
+        game_program_data_unpacked.advance_index()?;
+
+        MetalityGameProgramDataState::pack(
+            game_program_data_unpacked,
+            &mut game_program_data_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_join_lobby(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let user = next_account_info(account_info_iter)?;
+
+        let nft_ata = next_account_info(account_info_iter)?;
+
+        let nft_mint = next_account_info(account_info_iter)?;
+
+        let pda_account = next_account_info(account_info_iter)?;
+
+        let lobby_state_account = next_account_info(account_info_iter)?;
+
+        let token_program_account = next_account_info(account_info_iter)?;
+
+        let game_program_data_account = next_account_info(account_info_iter)?;
+
+        let (pda, _bump_seeds) = EscrowAuthority::find_pda(lobby_state_account.key, program_id);
+
+        let mut lobby_state_unpacked =
+            MetalityLobbyState::unpack_unchecked(&lobby_state_account.try_borrow_data()?)?;
+
+        Validator::validate_is_signer(user)?;
+        Validator::validate_state_account(lobby_state_account, *program_id)?;
+        Validator::validate_bool(
+            lobby_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            lobby_state_unpacked.expired,
+            false,
+            MetalityGameContractError::LobbyExpired,
+        )?;
+        Validator::validate_bool(
+            lobby_state_unpacked.game_started,
+            false,
+            MetalityGameContractError::LobbyAlreadyStarted,
+        )?;
+        Validator::validate_token_owner(nft_ata, user)?;
+        Validator::validate_token_ata(nft_ata, nft_mint)?;
+        Validator::validate_equality(
+            *pda_account.key,
+            pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_equality(
+            *pda_account.key,
+            lobby_state_unpacked.pda_account,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_token_program(token_program_account)?;
+        Validator::validate_program_data_account(game_program_data_account, *program_id)?;
+
+        let game_program_data_unpacked =
+            MetalityGameProgramDataState::unpack(&game_program_data_account.try_borrow_data()?)?;
+
+        Validator::validate_nft_mint(nft_mint, game_program_data_unpacked.nft_check_level)?;
+
+        if lobby_state_unpacked.player_count >= lobby_state_unpacked.max_players {
+            return Err(MetalityGameContractError::LobbyFull.into());
+        }
+
+        let player_count = lobby_state_unpacked.player_count as usize;
+
+        for entrant in &lobby_state_unpacked.entrants[..player_count] {
+            Validator::validate_same_resource(entrant.user, *user.key)?;
+            Validator::validate_same_resource(entrant.nft_ata, *nft_ata.key)?;
+            Validator::validate_same_resource(entrant.nft_mint, *nft_mint.key)?;
+        }
+
+        invoke(
+            &spl_token::instruction::set_authority(
+                &spl_token::ID,
+                nft_ata.key,
+                Some(pda_account.key),
+                spl_token::instruction::AuthorityType::AccountOwner,
+                user.key,
+                &[user.key],
+            )?,
+            &[nft_ata.clone(), user.clone(), token_program_account.clone()],
+        )?;
+
+        lobby_state_unpacked.entrants[player_count] = MetalityLobbyEntrant {
+            user: *user.key,
+            nft_ata: *nft_ata.key,
+            nft_mint: *nft_mint.key,
+        };
+        lobby_state_unpacked.player_count += 1;
+
+        MetalityLobbyState::pack(
+            lobby_state_unpacked,
+            &mut lobby_state_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_exit_lobby(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let user = next_account_info(account_info_iter)?;
+
+        let nft_ata = next_account_info(account_info_iter)?;
+
+        let nft_mint = next_account_info(account_info_iter)?;
+
+        let pda_account = next_account_info(account_info_iter)?;
+
+        let lobby_state_account = next_account_info(account_info_iter)?;
+
+        let token_program_account = next_account_info(account_info_iter)?;
+
+        let (pda, bump_seeds) = EscrowAuthority::find_pda(lobby_state_account.key, program_id);
+
+        let mut lobby_state_unpacked =
+            MetalityLobbyState::unpack_unchecked(&lobby_state_account.try_borrow_data()?)?;
+
+        Validator::validate_is_signer(user)?;
+        Validator::validate_state_account(lobby_state_account, *program_id)?;
+        Validator::validate_bool(
+            lobby_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            lobby_state_unpacked.expired,
+            false,
+            MetalityGameContractError::LobbyExpired,
+        )?;
+        Validator::validate_bool(
+            lobby_state_unpacked.game_started,
+            false,
+            MetalityGameContractError::LobbyAlreadyStarted,
+        )?;
+        Validator::validate_equality(
+            *pda_account.key,
+            pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_token_program(token_program_account)?;
+
+        if lobby_state_unpacked.creator == *user.key {
+            return Err(MetalityGameContractError::CreatorCannotExit.into());
+        }
+
+        let entrant_index = lobby_state_unpacked
+            .entrant_index(user.key)
+            .ok_or(MetalityGameContractError::NotLobbyEntrant)?;
+
+        let entrant = lobby_state_unpacked.entrants[entrant_index];
+
+        Validator::validate_equality(
+            *nft_ata.key,
+            entrant.nft_ata,
+            MetalityGameContractError::NftAtaMismatch,
+        )?;
+        Validator::validate_equality(
+            *nft_mint.key,
+            entrant.nft_mint,
+            MetalityGameContractError::MintMismatch,
+        )?;
+        Validator::validate_token_owner(nft_ata, pda_account)?;
+        Validator::validate_token_ata(nft_ata, nft_mint)?;
+
+        let escrow = EscrowAuthority {
+            pda_account,
+            escrow_state_account: lobby_state_account,
+            bump_seeds,
+            token_program_account,
+        };
+
+        escrow.release_stake(nft_ata, user.key)?;
+
+        let player_count = lobby_state_unpacked.player_count as usize;
+
+        lobby_state_unpacked
+            .entrants
+            .copy_within(entrant_index + 1..player_count, entrant_index);
+        lobby_state_unpacked.entrants[player_count - 1] = MetalityLobbyEntrant::default();
+        lobby_state_unpacked.player_count -= 1;
+
+        MetalityLobbyState::pack(
+            lobby_state_unpacked,
+            &mut lobby_state_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    /// Closes the lobby and hands every stake back to its owner. Stakes are
+    /// returned one entrant at a time through `ReleaseLobbyStake`.
+    pub fn process_cancel_lobby(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let authority = next_account_info(account_info_iter)?;
+
+        let game_program_data_account = next_account_info(account_info_iter)?;
+
+        let lobby_state_account = next_account_info(account_info_iter)?;
+
+        let mut lobby_state_unpacked =
+            MetalityLobbyState::unpack_unchecked(&lobby_state_account.try_borrow_data()?)?;
+
+        Validator::validate_program_data_account(game_program_data_account, *program_id)?;
+
+        let game_program_data_unpacked =
+            MetalityGameProgramDataState::unpack(&game_program_data_account.try_borrow_data()?)?;
+
+        // The creator may only cancel an open lobby, the admin may also
+        // unwind one that already started.
+        if *authority.key == game_program_data_unpacked.admin {
+            Validator::validate_admin(authority, game_program_data_account, *program_id)?;
+        } else {
+            Validator::validate_is_signer(authority)?;
+            Validator::validate_equality(
+                *authority.key,
+                lobby_state_unpacked.creator,
+                MetalityGameContractError::CreatorMismatch,
+            )?;
+            Validator::validate_bool(
+                lobby_state_unpacked.game_started,
+                false,
+                MetalityGameContractError::LobbyAlreadyStarted,
+            )?;
+        }
+
+        Validator::validate_state_account(lobby_state_account, *program_id)?;
+        Validator::validate_bool(
+            lobby_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            lobby_state_unpacked.expired,
+            false,
+            MetalityGameContractError::LobbyExpired,
+        )?;
+
+        lobby_state_unpacked.recipients = MetalityLobbyEntrant::own_stakes();
+        lobby_state_unpacked.expired = true;
+
+        MetalityLobbyState::pack(
+            lobby_state_unpacked,
+            &mut lobby_state_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    /// Every stake was checked into the lobby PDA on join and only this
+    /// program can move it, so starting needs no per-entrant accounts.
+    pub fn process_set_lobby_started(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let admin = next_account_info(account_info_iter)?;
+
+        let game_program_data_account = next_account_info(account_info_iter)?;
+
+        let lobby_state_account = next_account_info(account_info_iter)?;
+
+        let mut lobby_state_unpacked =
+            MetalityLobbyState::unpack_unchecked(&lobby_state_account.try_borrow_data()?)?;
+
+        Validator::validate_admin(admin, game_program_data_account, *program_id)?;
+        Validator::validate_state_account(lobby_state_account, *program_id)?;
+        Validator::validate_bool(
+            lobby_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            lobby_state_unpacked.expired,
+            false,
+            MetalityGameContractError::LobbyExpired,
+        )?;
+        Validator::validate_bool(
+            lobby_state_unpacked.game_started,
+            false,
+            MetalityGameContractError::LobbyAlreadyStarted,
+        )?;
+
+        let player_count = lobby_state_unpacked.player_count as usize;
+
+        // A ranked payout table is sized for a full lobby.
+        if player_count < MIN_LOBBY_PLAYERS
+            || (!lobby_state_unpacked.is_winner_takes_all()
+                && player_count != lobby_state_unpacked.max_players as usize)
+        {
+            return Err(MetalityGameContractError::NotEnoughPlayers.into());
+        }
+
+        lobby_state_unpacked.game_started = true;
+
+        MetalityLobbyState::pack(
+            lobby_state_unpacked,
+            &mut lobby_state_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    /// Records who receives each stake for `ranking` and closes the lobby.
+    /// Stakes are paid out one entrant at a time through `ReleaseLobbyStake`.
+    pub fn process_settle_lobby(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        ranking: &[u8],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let admin = next_account_info(account_info_iter)?;
+
+        let game_program_data_account = next_account_info(account_info_iter)?;
+
+        let lobby_state_account = next_account_info(account_info_iter)?;
+
+        let mut lobby_state_unpacked =
+            MetalityLobbyState::unpack_unchecked(&lobby_state_account.try_borrow_data()?)?;
+
+        Validator::validate_admin(admin, game_program_data_account, *program_id)?;
+        Validator::validate_state_account(lobby_state_account, *program_id)?;
+        Validator::validate_bool(
+            lobby_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            lobby_state_unpacked.expired,
+            false,
+            MetalityGameContractError::LobbyExpired,
+        )?;
+        Validator::validate_bool(
+            lobby_state_unpacked.game_started,
+            true,
+            MetalityGameContractError::LobbyNotStarted,
+        )?;
+
+        lobby_state_unpacked.recipients = lobby_state_unpacked.allocate_stakes(ranking)?;
+        lobby_state_unpacked.expired = true;
+
+        MetalityLobbyState::pack(
+            lobby_state_unpacked,
+            &mut lobby_state_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    /// Pays out one entrant's stake once the lobby is settled or cancelled.
+    /// Anyone may crank it; the payer funds a missing recipient ATA.
+    pub fn process_release_lobby_stake(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let release_accounts = StakeReleaseAccounts::next(account_info_iter)?;
+
+        let lobby_state_account = release_accounts.escrow_state_account;
+
+        let mut lobby_state_unpacked =
+            MetalityLobbyState::unpack_unchecked(&lobby_state_account.try_borrow_data()?)?;
+
+        Validator::validate_state_account(lobby_state_account, *program_id)?;
+        Validator::validate_bool(
+            lobby_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            lobby_state_unpacked.expired,
+            true,
+            MetalityGameContractError::StakesLocked,
+        )?;
+        Validator::validate_equality(
+            *release_accounts.pda_account.key,
+            lobby_state_unpacked.pda_account,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+
+        let player_count = lobby_state_unpacked.player_count as usize;
+
+        Self::release_entrant_stake(
+            &release_accounts,
+            program_id,
+            &lobby_state_unpacked.entrants[..player_count],
+            &lobby_state_unpacked.recipients,
+            &mut lobby_state_unpacked.released,
+        )?;
+
+        MetalityLobbyState::pack(
+            lobby_state_unpacked,
+            &mut lobby_state_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    /// Moves the stake of the entrant named by `release_accounts` to the
+    /// entrant `recipients` assigns it to and marks it in `released`.
+    fn release_entrant_stake(
+        release_accounts: &StakeReleaseAccounts,
+        program_id: &Pubkey,
+        entrants: &[MetalityLobbyEntrant],
+        recipients: &[u8; MAX_LOBBY_PLAYERS],
+        released: &mut u16,
+    ) -> ProgramResult {
+        let StakeReleaseAccounts {
+            payer,
+            pda_account,
+            escrow_state_account,
+            user,
+            nft_ata,
+            nft_mint,
+            recipient,
+            recipient_ata,
+            system_program_account,
+            token_program_account,
+            rent_sysvar_account,
+            associated_token_account_program,
+        } = *release_accounts;
+
+        let (pda, bump_seeds) = EscrowAuthority::find_pda(escrow_state_account.key, program_id);
+
+        Validator::validate_is_signer(payer)?;
+        Validator::validate_equality(
+            *pda_account.key,
+            pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_token_program(token_program_account)?;
+        Validator::validate_system_program(system_program_account)?;
+        Validator::validate_rent_sysvar(rent_sysvar_account)?;
+        Validator::validate_associated_token_program(associated_token_account_program)?;
+
+        let entrant_index = entrants
+            .iter()
+            .position(|entrant| entrant.user == *user.key)
+            .ok_or(MetalityGameContractError::EntrantMismatch)?;
+
+        if *released & (1 << entrant_index) != 0 {
+            return Err(MetalityGameContractError::StakeAlreadyReleased.into());
+        }
+
+        let entrant = entrants[entrant_index];
+
+        Validator::validate_equality(
+            *nft_ata.key,
+            entrant.nft_ata,
+            MetalityGameContractError::NftAtaMismatch,
+        )?;
+        Validator::validate_equality(
+            *nft_mint.key,
+            entrant.nft_mint,
+            MetalityGameContractError::MintMismatch,
+        )?;
+        Validator::validate_token_owner(nft_ata, pda_account)?;
+        Validator::validate_token_ata(nft_ata, nft_mint)?;
+
+        let recipient_index = recipients[entrant_index] as usize;
+
+        let recipient_entrant = entrants
+            .get(recipient_index)
+            .ok_or(MetalityGameContractError::EntrantMismatch)?;
+
+        Validator::validate_equality(
+            *recipient.key,
+            recipient_entrant.user,
+            MetalityGameContractError::EntrantMismatch,
+        )?;
+
+        let escrow = EscrowAuthority {
+            pda_account,
+            escrow_state_account,
+            bump_seeds,
+            token_program_account,
+        };
+
+        if recipient_index == entrant_index {
+            escrow.release_stake(nft_ata, user.key)?;
+        } else {
+            Validator::validate_equality(
+                get_associated_token_address(recipient.key, nft_mint.key),
+                *recipient_ata.key,
+                MetalityGameContractError::RecipientAtaMismatch,
+            )?;
+
+            let ata_creator = AtaCreator {
+                payer,
+                system_program_account,
+                token_program_account,
+                rent_sysvar_account,
+                associated_token_account_program,
+            };
+
+            ata_creator.create_if_missing(recipient_ata, recipient, nft_mint)?;
+
+            escrow.award_stake(nft_ata, user.key, recipient_ata)?;
+        }
+
+        *released |= 1 << entrant_index;
+
+        msg!(
+            "Released stake of entrant {:?} to entrant {:?}",
+            entrant_index,
+            recipient_index
+        );
+
+        Ok(())
+    }
+
+    pub fn process_initialize_tournament(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        capacity: u8,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let admin = next_account_info(account_info_iter)?;
+
+        let pda_account = next_account_info(account_info_iter)?;
+
+        let tournament_state_account = next_account_info(account_info_iter)?;
+
+        let game_program_data_account = next_account_info(account_info_iter)?;
+
+        let system_program_account = next_account_info(account_info_iter)?;
+
+        let mut game_program_data_unpacked =
+            MetalityGameProgramDataState::unpack(&game_program_data_account.try_borrow_data()?)?;
+
+        msg!("Index: {:?}", game_program_data_unpacked.index);
+
+        let (pda, _bump_seeds) =
+            EscrowAuthority::find_pda(tournament_state_account.key, program_id);
+
+        let seed = game_program_data_unpacked.indexed_seed(TOURNAMENT_SEED_PREFIX)?;
+
+        Validator::validate_admin(admin, game_program_data_account, *program_id)?;
+        Validator::validate_equality(
+            *pda_account.key,
+            pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_system_program(system_program_account)?;
+
+        if !MetalityTournamentState::is_valid_capacity(capacity) {
+            return Err(MetalityGameContractError::InvalidTournamentSize.into());
+        }
+
+        create_seeded_account(
+            admin,
+            tournament_state_account,
+            system_program_account,
+            &seed,
+            MetalityTournamentState::LEN,
+            program_id,
+        )?;
+
+        let mut tournament_state_unpacked = MetalityTournamentState::unpack_unchecked(
+            &tournament_state_account.try_borrow_data()?,
+        )?;
+
+        tournament_state_unpacked.is_initialized = true;
+        tournament_state_unpacked.pda_account = *pda_account.key;
+        tournament_state_unpacked.capacity = capacity;
+        tournament_state_unpacked.entrant_count = 0;
+        tournament_state_unpacked.started = false;
+        tournament_state_unpacked.expired = false;
+        tournament_state_unpacked.champion = NO_ENTRANT;
+        tournament_state_unpacked.match_games = [Pubkey::default(); MAX_TOURNAMENT_MATCHES];
+        tournament_state_unpacked.match_winners = [NO_ENTRANT; MAX_TOURNAMENT_MATCHES];
+        tournament_state_unpacked.recipients = [0; MAX_LOBBY_PLAYERS];
+        tournament_state_unpacked.released = 0;
+
+        MetalityTournamentState::pack(
+            tournament_state_unpacked,
+            &mut tournament_state_account.try_borrow_mut_data()?,
+        )?;
+
+        game_program_data_unpacked.advance_index()?;
+
+        MetalityGameProgramDataState::pack(
+            game_program_data_unpacked,
+            &mut game_program_data_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_register_tournament_entrant(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let user = next_account_info(account_info_iter)?;
+
+        let nft_ata = next_account_info(account_info_iter)?;
+
+        let nft_mint = next_account_info(account_info_iter)?;
+
+        let pda_account = next_account_info(account_info_iter)?;
+
+        let tournament_state_account = next_account_info(account_info_iter)?;
+
+        let token_program_account = next_account_info(account_info_iter)?;
+
+        let game_program_data_account = next_account_info(account_info_iter)?;
+
+        let (pda, _bump_seeds) =
+            EscrowAuthority::find_pda(tournament_state_account.key, program_id);
+
+        let mut tournament_state_unpacked = MetalityTournamentState::unpack_unchecked(
+            &tournament_state_account.try_borrow_data()?,
+        )?;
+
+        Validator::validate_is_signer(user)?;
+        Validator::validate_state_account(tournament_state_account, *program_id)?;
+        Validator::validate_bool(
+            tournament_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            tournament_state_unpacked.expired,
+            false,
+            MetalityGameContractError::TournamentExpired,
+        )?;
+        Validator::validate_bool(
+            tournament_state_unpacked.started,
+            false,
+            MetalityGameContractError::TournamentAlreadyStarted,
+        )?;
+        Validator::validate_token_owner(nft_ata, user)?;
+        Validator::validate_token_ata(nft_ata, nft_mint)?;
+        Validator::validate_equality(
+            *pda_account.key,
+            pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_equality(
+            *pda_account.key,
+            tournament_state_unpacked.pda_account,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_token_program(token_program_account)?;
+        Validator::validate_program_data_account(game_program_data_account, *program_id)?;
+
+        let game_program_data_unpacked =
+            MetalityGameProgramDataState::unpack(&game_program_data_account.try_borrow_data()?)?;
+
+        Validator::validate_nft_mint(nft_mint, game_program_data_unpacked.nft_check_level)?;
+
+        if tournament_state_unpacked.entrant_count >= tournament_state_unpacked.capacity {
+            return Err(MetalityGameContractError::TournamentFull.into());
+        }
+
+        let entrant_count = tournament_state_unpacked.entrant_count as usize;
+
+        for entrant in &tournament_state_unpacked.entrants[..entrant_count] {
+            Validator::validate_same_resource(entrant.user, *user.key)?;
+            Validator::validate_same_resource(entrant.nft_ata, *nft_ata.key)?;
+            Validator::validate_same_resource(entrant.nft_mint, *nft_mint.key)?;
+        }
+
+        invoke(
+            &spl_token::instruction::set_authority(
+                &spl_token::ID,
+                nft_ata.key,
+                Some(pda_account.key),
+                spl_token::instruction::AuthorityType::AccountOwner,
+                user.key,
+                &[user.key],
+            )?,
+            &[nft_ata.clone(), user.clone(), token_program_account.clone()],
+        )?;
+
+        tournament_state_unpacked.entrants[entrant_count] = MetalityLobbyEntrant {
+            user: *user.key,
+            nft_ata: *nft_ata.key,
+            nft_mint: *nft_mint.key,
+        };
+        tournament_state_unpacked.entrant_count += 1;
+
+        MetalityTournamentState::pack(
+            tournament_state_unpacked,
+            &mut tournament_state_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    /// Closes a tournament before its first match and hands every stake back
+    /// to its owner through `ReleaseTournamentStake`.
+    pub fn process_cancel_tournament(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let admin = next_account_info(account_info_iter)?;
+
+        let game_program_data_account = next_account_info(account_info_iter)?;
+
+        let tournament_state_account = next_account_info(account_info_iter)?;
+
+        let mut tournament_state_unpacked = MetalityTournamentState::unpack_unchecked(
+            &tournament_state_account.try_borrow_data()?,
+        )?;
+
+        Validator::validate_admin(admin, game_program_data_account, *program_id)?;
+        Validator::validate_state_account(tournament_state_account, *program_id)?;
+        Validator::validate_bool(
+            tournament_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            tournament_state_unpacked.expired,
+            false,
+            MetalityGameContractError::TournamentExpired,
+        )?;
+        Validator::validate_bool(
+            tournament_state_unpacked.started,
+            false,
+            MetalityGameContractError::TournamentAlreadyStarted,
+        )?;
+
+        tournament_state_unpacked.recipients = MetalityLobbyEntrant::own_stakes();
+        tournament_state_unpacked.expired = true;
+
+        MetalityTournamentState::pack(
+            tournament_state_unpacked,
+            &mut tournament_state_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_create_tournament_match(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        match_index: usize,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let admin = next_account_info(account_info_iter)?;
+
+        let pda_account = next_account_info(account_info_iter)?;
+
+        let tournament_state_account = next_account_info(account_info_iter)?;
+
+        let game_state_account = next_account_info(account_info_iter)?;
+
+        let game_program_data_account = next_account_info(account_info_iter)?;
+
+        let system_program_account = next_account_info(account_info_iter)?;
+
+        let (pda, _bump_seeds) =
+            EscrowAuthority::find_pda(tournament_state_account.key, program_id);
+
+        let mut tournament_state_unpacked = MetalityTournamentState::unpack_unchecked(
+            &tournament_state_account.try_borrow_data()?,
+        )?;
+
+        let mut game_program_data_unpacked =
+            MetalityGameProgramDataState::unpack(&game_program_data_account.try_borrow_data()?)?;
+
+        msg!("Index: {:?}", game_program_data_unpacked.index);
+
+        let seed = game_program_data_unpacked.indexed_seed(GAME_STATE_SEED_PREFIX)?;
+
+        Validator::validate_admin(admin, game_program_data_account, *program_id)?;
+        Validator::validate_state_account(tournament_state_account, *program_id)?;
+        Validator::validate_bool(
+            tournament_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            tournament_state_unpacked.expired,
+            false,
+            MetalityGameContractError::TournamentExpired,
+        )?;
+        Validator::validate_equality(
+            *pda_account.key,
+            pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_equality(
+            *pda_account.key,
+            tournament_state_unpacked.pda_account,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_system_program(system_program_account)?;
+
+        if tournament_state_unpacked.entrant_count != tournament_state_unpacked.capacity {
+            return Err(MetalityGameContractError::NotEnoughPlayers.into());
+        }
+
+        let (entrant_a, entrant_b) = tournament_state_unpacked
+            .match_participants(match_index)
+            .ok_or(MetalityGameContractError::MatchNotReady)?;
+
+        if tournament_state_unpacked.match_games[match_index] != Pubkey::default() {
+            return Err(MetalityGameContractError::MatchAlreadyCreated.into());
+        }
+
+        create_seeded_account(
+            admin,
+            game_state_account,
+            system_program_account,
+            &seed,
+            MetalityGameContractState::LEN,
+            program_id,
+        )?;
+
+        let mut game_state_unpacked =
+            MetalityGameContractState::unpack_unchecked(&game_state_account.try_borrow_data()?)?;
+
+        let entrant_a = tournament_state_unpacked.entrants[entrant_a as usize];
+        let entrant_b = tournament_state_unpacked.entrants[entrant_b as usize];
+
+        // Stakes stay with the tournament PDA, so the match can only be
+        // settled through the tournament and never through ClaimReward.
+        game_state_unpacked.is_initialized = true;
+        game_state_unpacked.user_a = entrant_a.user;
+        game_state_unpacked.a_nft_ata = entrant_a.nft_ata;
+        game_state_unpacked.a_nft_mint = entrant_a.nft_mint;
+        game_state_unpacked.user_b = entrant_b.user;
+        game_state_unpacked.user_b_joined = true;
+        game_state_unpacked.b_nft_ata = entrant_b.nft_ata;
+        game_state_unpacked.b_nft_mint = entrant_b.nft_mint;
+        game_state_unpacked.pda_account = *pda_account.key;
+        game_state_unpacked.expired = false;
+        game_state_unpacked.game_started = true;
+        game_state_unpacked.min_opponent_rating = 0;
+        game_state_unpacked.max_opponent_rating = u32::MAX;
+
+        MetalityGameContractState::pack(
+            game_state_unpacked,
+            &mut game_state_account.try_borrow_mut_data()?,
+        )?;
+
+        tournament_state_unpacked.started = true;
+        tournament_state_unpacked.match_games[match_index] = *game_state_account.key;
+
+        MetalityTournamentState::pack(
+            tournament_state_unpacked,
+            &mut tournament_state_account.try_borrow_mut_data()?,
+        )?;
+
+        game_program_data_unpacked.advance_index()?;
+
+        MetalityGameProgramDataState::pack(
+            game_program_data_unpacked,
+            &mut game_program_data_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    /// Decides a bracket match. The winner is recorded and booked like any
+    /// other game, while both stakes stay with the tournament escrow.
+    /// A winner already recorded through `Resign` or an oracle result is kept.
+    pub fn process_settle_tournament_match(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let admin = next_account_info(account_info_iter)?;
+
+        let game_program_data_account = next_account_info(account_info_iter)?;
+
+        let winner = next_account_info(account_info_iter)?;
+
+        let tournament_state_account = next_account_info(account_info_iter)?;
+
+        let game_state_account = next_account_info(account_info_iter)?;
+
+        let loser = next_account_info(account_info_iter)?;
+
+        let system_program_account = next_account_info(account_info_iter)?;
+
+        let winner_profile_account = next_account_info(account_info_iter)?;
+
+        let loser_profile_account = next_account_info(account_info_iter)?;
+
+        let mut tournament_state_unpacked = MetalityTournamentState::unpack_unchecked(
+            &tournament_state_account.try_borrow_data()?,
+        )?;
+
+        let game_state_unpacked =
+            MetalityGameContractState::unpack_unchecked(&game_state_account.try_borrow_data()?)?;
+
+        Validator::validate_admin(admin, game_program_data_account, *program_id)?;
+        Validator::validate_state_account(tournament_state_account, *program_id)?;
+        Validator::validate_state_account(game_state_account, *program_id)?;
+        Validator::validate_system_program(system_program_account)?;
+        Validator::validate_bool(
+            tournament_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            tournament_state_unpacked.expired,
+            false,
+            MetalityGameContractError::TournamentExpired,
+        )?;
+        Validator::validate_equality(
+            game_state_unpacked.pda_account,
+            tournament_state_unpacked.pda_account,
+            MetalityGameContractError::NotTournamentMatch,
+        )?;
+
+        let match_index = tournament_state_unpacked
+            .match_index(game_state_account.key)
+            .ok_or(MetalityGameContractError::NotTournamentMatch)?;
+
+        let (entrant_a, entrant_b) = tournament_state_unpacked
+            .match_participants(match_index)
+            .ok_or(MetalityGameContractError::MatchNotReady)?;
+
+        if !game_state_unpacked.has_recorded_winner() {
+            Self::record_winner(game_state_account, winner, program_id, GAME_OUTCOME_WON)?;
+        }
+
+        let mut game_state_unpacked =
+            MetalityGameContractState::unpack_unchecked(&game_state_account.try_borrow_data()?)?;
+
+        Validator::validate_bool(
+            game_state_unpacked.expired,
+            false,
+            MetalityGameContractError::GameExpired,
+        )?;
+        Validator::validate_equality(
+            *winner.key,
+            game_state_unpacked.winner,
+            MetalityGameContractError::InvalidWinner,
+        )?;
+        Validator::validate_opponent(game_state_unpacked, *winner.key, *loser.key)
+            .map_err(|_| MetalityGameContractError::LoserMismatch)?;
+
+        let match_winner = if *winner.key == game_state_unpacked.user_a {
+            entrant_a
+        } else {
+            entrant_b
+        };
+
+        msg!("Match {:?} won by entrant {:?}", match_index, match_winner);
+
+        let outcome_accounts = OutcomeAccounts {
+            winner,
+            loser,
+            game_state_account,
+            system_program_account,
+            winner_profile_account,
+            loser_profile_account,
+        };
+
+        Self::conclude_win(
+            &outcome_accounts,
+            admin,
+            &mut game_state_unpacked,
+            account_info_iter,
+            program_id,
+        )?;
+
+        tournament_state_unpacked.match_winners[match_index] = match_winner;
+
+        if match_index == tournament_state_unpacked.final_match() {
+            tournament_state_unpacked.champion = match_winner;
+        }
+
+        MetalityTournamentState::pack(
+            tournament_state_unpacked,
+            &mut tournament_state_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    /// Awards every stake to the champion and closes the tournament. Stakes
+    /// are paid out one entrant at a time through `ReleaseTournamentStake`.
+    pub fn process_payout_tournament(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let admin = next_account_info(account_info_iter)?;
+
+        let game_program_data_account = next_account_info(account_info_iter)?;
+
+        let tournament_state_account = next_account_info(account_info_iter)?;
+
+        let mut tournament_state_unpacked = MetalityTournamentState::unpack_unchecked(
+            &tournament_state_account.try_borrow_data()?,
+        )?;
+
+        Validator::validate_admin(admin, game_program_data_account, *program_id)?;
+        Validator::validate_state_account(tournament_state_account, *program_id)?;
+        Validator::validate_bool(
+            tournament_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            tournament_state_unpacked.expired,
+            false,
+            MetalityGameContractError::TournamentExpired,
+        )?;
+
+        if tournament_state_unpacked.champion == NO_ENTRANT {
+            return Err(MetalityGameContractError::ChampionUndecided.into());
+        }
+
+        tournament_state_unpacked.recipients =
+            [tournament_state_unpacked.champion; MAX_LOBBY_PLAYERS];
+        tournament_state_unpacked.expired = true;
+
+        MetalityTournamentState::pack(
+            tournament_state_unpacked,
+            &mut tournament_state_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    /// Pays out one entrant's stake once the tournament is paid out or
+    /// cancelled. Anyone may crank it; the payer funds a missing recipient ATA.
+    pub fn process_release_tournament_stake(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let release_accounts = StakeReleaseAccounts::next(account_info_iter)?;
+
+        let tournament_state_account = release_accounts.escrow_state_account;
+
+        let mut tournament_state_unpacked = MetalityTournamentState::unpack_unchecked(
+            &tournament_state_account.try_borrow_data()?,
+        )?;
+
+        Validator::validate_state_account(tournament_state_account, *program_id)?;
+        Validator::validate_bool(
+            tournament_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            tournament_state_unpacked.expired,
+            true,
+            MetalityGameContractError::StakesLocked,
+        )?;
+        Validator::validate_equality(
+            *release_accounts.pda_account.key,
+            tournament_state_unpacked.pda_account,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+
+        let entrant_count = tournament_state_unpacked.entrant_count as usize;
+
+        Self::release_entrant_stake(
+            &release_accounts,
+            program_id,
+            &tournament_state_unpacked.entrants[..entrant_count],
+            &tournament_state_unpacked.recipients,
+            &mut tournament_state_unpacked.released,
+        )?;
+
+        MetalityTournamentState::pack(
+            tournament_state_unpacked,
+            &mut tournament_state_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_initialize_queue(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let admin = next_account_info(account_info_iter)?;
+
+        let pda_account = next_account_info(account_info_iter)?;
+
+        let queue_state_account = next_account_info(account_info_iter)?;
+
+        let game_program_data_account = next_account_info(account_info_iter)?;
+
+        let system_program_account = next_account_info(account_info_iter)?;
+
+        let mut game_program_data_unpacked =
+            MetalityGameProgramDataState::unpack(&game_program_data_account.try_borrow_data()?)?;
+
+        msg!("Index: {:?}", game_program_data_unpacked.index);
+
+        let (pda, _bump_seeds) = EscrowAuthority::find_pda(queue_state_account.key, program_id);
+
+        let seed = game_program_data_unpacked.indexed_seed(QUEUE_SEED_PREFIX)?;
+
+        Validator::validate_admin(admin, game_program_data_account, *program_id)?;
+        Validator::validate_equality(
+            *pda_account.key,
+            pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_system_program(system_program_account)?;
+
+        create_seeded_account(
+            admin,
+            queue_state_account,
+            system_program_account,
+            &seed,
+            MetalityQueueState::LEN,
+            program_id,
+        )?;
+
+        let mut queue_state_unpacked =
+            MetalityQueueState::unpack_unchecked(&queue_state_account.try_borrow_data()?)?;
+
+        queue_state_unpacked.is_initialized = true;
+        queue_state_unpacked.pda_account = *pda_account.key;
+        queue_state_unpacked.entry_count = 0;
+
+        MetalityQueueState::pack(
+            queue_state_unpacked,
+            &mut queue_state_account.try_borrow_mut_data()?,
+        )?;
+
+        game_program_data_unpacked.advance_index()?;
+
+        MetalityGameProgramDataState::pack(
+            game_program_data_unpacked,
+            &mut game_program_data_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_enqueue(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        min_opponent_rating: u32,
+        max_opponent_rating: u32,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let user = next_account_info(account_info_iter)?;
+
+        let nft_ata = next_account_info(account_info_iter)?;
+
+        let nft_mint = next_account_info(account_info_iter)?;
+
+        let pda_account = next_account_info(account_info_iter)?;
+
+        let queue_state_account = next_account_info(account_info_iter)?;
+
+        let user_profile_account = next_account_info(account_info_iter)?;
+
+        let token_program_account = next_account_info(account_info_iter)?;
+
+        let game_program_data_account = next_account_info(account_info_iter)?;
+
+        let (pda, _bump_seeds) = EscrowAuthority::find_pda(queue_state_account.key, program_id);
+
+        let mut queue_state_unpacked =
+            MetalityQueueState::unpack_unchecked(&queue_state_account.try_borrow_data()?)?;
+
+        Validator::validate_is_signer(user)?;
+        Validator::validate_state_account(queue_state_account, *program_id)?;
+        Validator::validate_bool(
+            queue_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_token_owner(nft_ata, user)?;
+        Validator::validate_token_ata(nft_ata, nft_mint)?;
+        Validator::validate_equality(
+            *pda_account.key,
+            pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_equality(
+            *pda_account.key,
+            queue_state_unpacked.pda_account,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_token_program(token_program_account)?;
+        Validator::validate_program_data_account(game_program_data_account, *program_id)?;
+
+        let game_program_data_unpacked =
+            MetalityGameProgramDataState::unpack(&game_program_data_account.try_borrow_data()?)?;
+
+        Validator::validate_nft_mint(nft_mint, game_program_data_unpacked.nft_check_level)?;
+
+        if min_opponent_rating > max_opponent_rating {
+            return Err(MetalityGameContractError::InvalidRatingRange.into());
+        }
+
+        let entry_count = queue_state_unpacked.entry_count as usize;
+
+        if entry_count >= MAX_QUEUE_ENTRIES {
+            return Err(MetalityGameContractError::QueueFull.into());
+        }
+
+        for entry in &queue_state_unpacked.entries[..entry_count] {
+            Validator::validate_same_resource(entry.user, *user.key)?;
+            Validator::validate_same_resource(entry.nft_ata, *nft_ata.key)?;
+            Validator::validate_same_resource(entry.nft_mint, *nft_mint.key)?;
+        }
+
+        let rating = profile::read_rating(user_profile_account, user.key, program_id)?;
+
+        invoke(
+            &spl_token::instruction::set_authority(
+                &spl_token::ID,
+                nft_ata.key,
+                Some(pda_account.key),
+                spl_token::instruction::AuthorityType::AccountOwner,
+                user.key,
+                &[user.key],
+            )?,
+            &[nft_ata.clone(), user.clone(), token_program_account.clone()],
+        )?;
+
+        queue_state_unpacked.entries[entry_count] = MetalityQueueEntry {
+            user: *user.key,
+            nft_ata: *nft_ata.key,
+            nft_mint: *nft_mint.key,
+            rating,
+            min_opponent_rating,
+            max_opponent_rating,
+        };
+        queue_state_unpacked.entry_count += 1;
+
+        MetalityQueueState::pack(
+            queue_state_unpacked,
+            &mut queue_state_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_dequeue(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let user = next_account_info(account_info_iter)?;
+
+        let nft_ata = next_account_info(account_info_iter)?;
+
+        let nft_mint = next_account_info(account_info_iter)?;
+
+        let pda_account = next_account_info(account_info_iter)?;
+
+        let queue_state_account = next_account_info(account_info_iter)?;
+
+        let token_program_account = next_account_info(account_info_iter)?;
+
+        let (pda, bump_seeds) = EscrowAuthority::find_pda(queue_state_account.key, program_id);
+
+        let mut queue_state_unpacked =
+            MetalityQueueState::unpack_unchecked(&queue_state_account.try_borrow_data()?)?;
+
+        Validator::validate_is_signer(user)?;
+        Validator::validate_state_account(queue_state_account, *program_id)?;
+        Validator::validate_bool(
+            queue_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_equality(
+            *pda_account.key,
+            pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_token_program(token_program_account)?;
+
+        let entry_index = queue_state_unpacked
+            .entry_index(user.key)
+            .ok_or(MetalityGameContractError::NotQueued)?;
+
+        let entry = queue_state_unpacked.entries[entry_index];
+
+        Validator::validate_equality(
+            *nft_ata.key,
+            entry.nft_ata,
+            MetalityGameContractError::NftAtaMismatch,
+        )?;
+        Validator::validate_equality(
+            *nft_mint.key,
+            entry.nft_mint,
+            MetalityGameContractError::MintMismatch,
+        )?;
+        Validator::validate_token_owner(nft_ata, pda_account)?;
+        Validator::validate_token_ata(nft_ata, nft_mint)?;
+
+        let escrow = EscrowAuthority {
+            pda_account,
+            escrow_state_account: queue_state_account,
+            bump_seeds,
+            token_program_account,
+        };
+
+        escrow.release_stake(nft_ata, user.key)?;
+
+        queue_state_unpacked.remove_entry(entry_index);
+
+        MetalityQueueState::pack(
+            queue_state_unpacked,
+            &mut queue_state_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_match_queued(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        first_entry: usize,
+        second_entry: usize,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let payer = next_account_info(account_info_iter)?;
+
+        let queue_pda_account = next_account_info(account_info_iter)?;
+
+        let queue_state_account = next_account_info(account_info_iter)?;
+
+        let first_nft_ata = next_account_info(account_info_iter)?;
+
+        let second_nft_ata = next_account_info(account_info_iter)?;
+
+        let pda_account = next_account_info(account_info_iter)?;
+
+        let game_state_account = next_account_info(account_info_iter)?;
+
+        let game_program_data_account = next_account_info(account_info_iter)?;
+
+        let token_program_account = next_account_info(account_info_iter)?;
+
+        let system_program_account = next_account_info(account_info_iter)?;
+
+        let (queue_pda, queue_bump_seeds) =
+            EscrowAuthority::find_pda(queue_state_account.key, program_id);
+
+        let (pda, _bump_seeds) = EscrowAuthority::find_pda(game_state_account.key, program_id);
+
+        let mut queue_state_unpacked =
+            MetalityQueueState::unpack_unchecked(&queue_state_account.try_borrow_data()?)?;
+
+        let mut game_program_data_unpacked =
+            MetalityGameProgramDataState::unpack(&game_program_data_account.try_borrow_data()?)?;
+
+        msg!("Index: {:?}", game_program_data_unpacked.index);
+
+        let seed = game_program_data_unpacked.indexed_seed(GAME_STATE_SEED_PREFIX)?;
+
+        Validator::validate_is_signer(payer)?;
+        Validator::validate_program_data_account(game_program_data_account, *program_id)?;
+        Validator::validate_state_account(queue_state_account, *program_id)?;
+        Validator::validate_bool(
+            queue_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_equality(
+            *queue_pda_account.key,
+            queue_pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_equality(
+            *pda_account.key,
+            pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_token_program(token_program_account)?;
+        Validator::validate_system_program(system_program_account)?;
+
+        let entry_count = queue_state_unpacked.entry_count as usize;
+
+        if first_entry == second_entry || first_entry >= entry_count || second_entry >= entry_count
+        {
+            return Err(MetalityGameContractError::NotQueued.into());
+        }
+
+        let first = queue_state_unpacked.entries[first_entry];
+        let second = queue_state_unpacked.entries[second_entry];
+
+        if !first.accepts(&second) || !second.accepts(&first) {
+            return Err(MetalityGameContractError::IncompatibleEntries.into());
+        }
+
+        Validator::validate_equality(
+            *first_nft_ata.key,
+            first.nft_ata,
+            MetalityGameContractError::NftAtaMismatch,
+        )?;
+        Validator::validate_equality(
+            *second_nft_ata.key,
+            second.nft_ata,
+            MetalityGameContractError::NftAtaMismatch,
+        )?;
+        Validator::validate_token_owner(first_nft_ata, queue_pda_account)?;
+        Validator::validate_token_owner(second_nft_ata, queue_pda_account)?;
+
+        create_seeded_account(
+            payer,
+            game_state_account,
+            system_program_account,
+            &seed,
+            MetalityGameContractState::LEN,
+            program_id,
+        )?;
+
+        // Both stakes move straight from queue custody to the new game's PDA.
+        let queue_escrow = EscrowAuthority {
+            pda_account: queue_pda_account,
+            escrow_state_account: queue_state_account,
+            bump_seeds: queue_bump_seeds,
+            token_program_account,
+        };
+
+        queue_escrow.release_stake(first_nft_ata, pda_account.key)?;
+        queue_escrow.release_stake(second_nft_ata, pda_account.key)?;
+
+        let mut game_state_unpacked =
+            MetalityGameContractState::unpack_unchecked(&game_state_account.try_borrow_data()?)?;
+
+        game_state_unpacked.is_initialized = true;
+        game_state_unpacked.user_a = first.user;
+        game_state_unpacked.a_nft_ata = first.nft_ata;
+        game_state_unpacked.a_nft_mint = first.nft_mint;
+        game_state_unpacked.user_b = second.user;
+        game_state_unpacked.user_b_joined = true;
+        game_state_unpacked.b_nft_ata = second.nft_ata;
+        game_state_unpacked.b_nft_mint = second.nft_mint;
+        game_state_unpacked.pda_account = *pda_account.key;
+        game_state_unpacked.expired = false;
+        game_state_unpacked.game_started = true;
+        game_state_unpacked.min_opponent_rating = first.min_opponent_rating;
+        game_state_unpacked.max_opponent_rating = first.max_opponent_rating;
+
+        MetalityGameContractState::pack(
+            game_state_unpacked,
+            &mut game_state_account.try_borrow_mut_data()?,
+        )?;
+
+        queue_state_unpacked.remove_entry(first_entry.max(second_entry));
+        queue_state_unpacked.remove_entry(first_entry.min(second_entry));
+
+        MetalityQueueState::pack(
+            queue_state_unpacked,
+            &mut queue_state_account.try_borrow_mut_data()?,
+        )?;
+
+        game_program_data_unpacked.advance_index()?;
+
+        MetalityGameProgramDataState::pack(
+            game_program_data_unpacked,
+            &mut game_program_data_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_initialize_side_pool(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let payer = next_account_info(account_info_iter)?;
+
+        let game_state_account = next_account_info(account_info_iter)?;
+
+        let side_pool_account = next_account_info(account_info_iter)?;
+
+        let pool_pda_account = next_account_info(account_info_iter)?;
+
+        let vault_account = next_account_info(account_info_iter)?;
+
+        let token_mint = next_account_info(account_info_iter)?;
+
+        let system_program_account = next_account_info(account_info_iter)?;
+
+        let token_program_account = next_account_info(account_info_iter)?;
+
+        let rent_sysvar_account = next_account_info(account_info_iter)?;
+
+        let associated_token_account_program = next_account_info(account_info_iter)?;
+
+        let mut game_state_unpacked =
+            MetalityGameContractState::unpack_unchecked(&game_state_account.try_borrow_data()?)?;
+
+        let (side_pool_address, side_pool_bump_seeds) = side_pool::find_side_pool_address(
+            game_state_account.key,
+            &game_state_unpacked.user_b,
+            program_id,
+        );
+
+        let (pool_pda, _bump_seeds) = EscrowAuthority::find_pda(side_pool_account.key, program_id);
+
+        Validator::validate_is_signer(payer)?;
+        Validator::validate_state_account(game_state_account, *program_id)?;
+        Validator::validate_bool(
+            game_state_unpacked.is_initialized,
+            true,
+            MetalityGameContractError::StateNotInitialized,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.user_b_joined,
+            true,
+            MetalityGameContractError::OpponentNotJoined,
+        )?;
+        Validator::validate_bool(
+            game_state_unpacked.expired,
+            false,
+            MetalityGameContractError::GameExpired,
+        )?;
+        // Queue and tournament games are created already started, so a pool
+        // may open until the winner is known. SetGameStarted locks it early
+        // for games that go through that instruction.
+        Validator::validate_bool(
+            game_state_unpacked.has_recorded_winner(),
+            false,
+            MetalityGameContractError::WinnerAlreadyRecorded,
+        )?;
+        Validator::validate_equality(
+            game_state_unpacked.side_pool,
+            Pubkey::default(),
+            MetalityGameContractError::SidePoolAlreadyOpen,
+        )?;
+        Validator::validate_equality(
+            *side_pool_account.key,
+            side_pool_address,
+            MetalityGameContractError::SidePoolMismatch,
+        )?;
+        Validator::validate_equality(
+            *pool_pda_account.key,
+            pool_pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_equality(
+            *vault_account.key,
+            get_associated_token_address(&pool_pda, token_mint.key),
+            MetalityGameContractError::VaultMismatch,
+        )?;
+        Validator::validate_token_program(token_program_account)?;
+        Validator::validate_system_program(system_program_account)?;
+        Validator::validate_rent_sysvar(rent_sysvar_account)?;
+        Validator::validate_associated_token_program(associated_token_account_program)?;
+
+        create_pda_account(
+            payer,
+            side_pool_account,
+            system_program_account,
+            MetalitySidePoolState::LEN,
+            program_id,
+            &[
+                SIDE_POOL_SEED.as_bytes(),
+                game_state_account.key.as_ref(),
+                game_state_unpacked.user_b.as_ref(),
+                &[side_pool_bump_seeds],
+            ],
+        )?;
+
+        AtaCreator {
+            payer,
+            system_program_account,
+            token_program_account,
+            rent_sysvar_account,
+            associated_token_account_program,
+        }
+        .create_if_missing(vault_account, pool_pda_account, token_mint)?;
+
+        let side_pool_unpacked = MetalitySidePoolState {
+            is_initialized: true,
+            game_state: *game_state_account.key,
+            token_mint: *token_mint.key,
+            vault: *vault_account.key,
+            ..MetalitySidePoolState::default()
+        };
+
+        MetalitySidePoolState::pack(
+            side_pool_unpacked,
+            &mut side_pool_account.try_borrow_mut_data()?,
+        )?;
+
+        game_state_unpacked.side_pool = *side_pool_account.key;
+
+        MetalityGameContractState::pack(
+            game_state_unpacked,
+            &mut game_state_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_place_side_bet(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        side: u8,
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bettor = next_account_info(account_info_iter)?;
+
+        let bettor_token_account = next_account_info(account_info_iter)?;
+
+        let side_pool_account = next_account_info(account_info_iter)?;
+
+        let game_state_account = next_account_info(account_info_iter)?;
+
+        let vault_account = next_account_info(account_info_iter)?;
+
+        let side_bet_account = next_account_info(account_info_iter)?;
+
+        let system_program_account = next_account_info(account_info_iter)?;
+
+        let token_program_account = next_account_info(account_info_iter)?;
+
+        let mut side_pool_unpacked = Self::load_side_pool(side_pool_account, program_id)?;
+
+        let (side_bet_address, side_bet_bump_seeds) =
+            side_pool::find_side_bet_address(side_pool_account.key, bettor.key, program_id);
+
+        Validator::validate_is_signer(bettor)?;
+        Validator::validate_equality(
+            *game_state_account.key,
+            side_pool_unpacked.game_state,
+            MetalityGameContractError::InvalidSidePool,
+        )?;
+        Validator::validate_equality(
+            *vault_account.key,
+            side_pool_unpacked.vault,
+            MetalityGameContractError::VaultMismatch,
+        )?;
+        Validator::validate_equality(
+            *side_bet_account.key,
+            side_bet_address,
+            MetalityGameContractError::SideBetMismatch,
+        )?;
+        Validator::validate_token_program(token_program_account)?;
+        Validator::validate_system_program(system_program_account)?;
+
+        if side_pool_unpacked.locked || side_pool_unpacked.is_settled() {
+            return Err(MetalityGameContractError::SidePoolLocked.into());
+        }
+
+        let game_state_unpacked =
+            MetalityGameContractState::unpack(&game_state_account.try_borrow_data()?)?;
+
+        // The pool only settles once the reward is claimed, so bets must stop
+        // as soon as the winner is recorded.
+        Validator::validate_bool(
+            game_state_unpacked.has_recorded_winner(),
+            false,
+            MetalityGameContractError::WinnerAlreadyRecorded,
+        )?;
+
+        if side != SIDE_USER_A && side != SIDE_USER_B {
+            return Err(MetalityGameContractError::InvalidSide.into());
+        }
+
+        if amount == 0 {
+            return Err(MetalityGameContractError::InvalidInstruction.into());
+        }
+
+        let mut side_bet_unpacked = if side_bet_account.data_is_empty() {
+            create_pda_account(
+                bettor,
+                side_bet_account,
+                system_program_account,
+                MetalitySideBetState::LEN,
+                program_id,
+                &[
+                    SIDE_BET_SEED.as_bytes(),
+                    side_pool_account.key.as_ref(),
+                    bettor.key.as_ref(),
+                    &[side_bet_bump_seeds],
+                ],
+            )?;
+
+            MetalitySideBetState {
+                is_initialized: true,
+                side_pool: *side_pool_account.key,
+                bettor: *bettor.key,
+                side,
+                ..MetalitySideBetState::default()
+            }
+        } else {
+            Validator::validate_state_account(side_bet_account, *program_id)?;
+            MetalitySideBetState::unpack(&side_bet_account.try_borrow_data()?)?
+        };
+
+        // Topping up is fine, hedging across both sides is not.
+        if side_bet_unpacked.side != side {
+            return Err(MetalityGameContractError::InvalidSide.into());
+        }
+
+        invoke(
+            &spl_token::instruction::transfer(
+                &spl_token::ID,
+                bettor_token_account.key,
+                vault_account.key,
+                bettor.key,
+                &[],
+                amount,
+            )?,
+            &[
+                bettor_token_account.clone(),
+                vault_account.clone(),
+                bettor.clone(),
+                token_program_account.clone(),
+            ],
+        )?;
+
+        let side_total = if side == SIDE_USER_A {
+            &mut side_pool_unpacked.total_a
+        } else {
+            &mut side_pool_unpacked.total_b
+        };
+
+        *side_total = side_total
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        side_bet_unpacked.amount = side_bet_unpacked
+            .amount
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        MetalitySidePoolState::pack(
+            side_pool_unpacked,
+            &mut side_pool_account.try_borrow_mut_data()?,
+        )?;
+
+        MetalitySideBetState::pack(
+            side_bet_unpacked,
+            &mut side_bet_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_claim_side_bet(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bettor = next_account_info(account_info_iter)?;
+
+        let bettor_token_account = next_account_info(account_info_iter)?;
+
+        let side_pool_account = next_account_info(account_info_iter)?;
+
+        let pool_pda_account = next_account_info(account_info_iter)?;
+
+        let vault_account = next_account_info(account_info_iter)?;
+
+        let side_bet_account = next_account_info(account_info_iter)?;
+
+        let token_program_account = next_account_info(account_info_iter)?;
+
+        let side_pool_unpacked = Self::load_side_pool(side_pool_account, program_id)?;
+
+        let (pool_pda, bump_seeds) = EscrowAuthority::find_pda(side_pool_account.key, program_id);
+
+        Validator::validate_is_signer(bettor)?;
+        Validator::validate_state_account(side_bet_account, *program_id)?;
+        Validator::validate_equality(
+            *pool_pda_account.key,
+            pool_pda,
+            MetalityGameContractError::IncorrectPDA,
+        )?;
+        Validator::validate_equality(
+            *vault_account.key,
+            side_pool_unpacked.vault,
+            MetalityGameContractError::VaultMismatch,
+        )?;
+        Validator::validate_token_program(token_program_account)?;
+
+        if !side_pool_unpacked.is_settled() {
+            return Err(MetalityGameContractError::SidePoolNotSettled.into());
+        }
+
+        let mut side_bet_unpacked =
+            MetalitySideBetState::unpack(&side_bet_account.try_borrow_data()?)?;
+
+        Validator::validate_equality(
+            side_bet_unpacked.side_pool,
+            *side_pool_account.key,
+            MetalityGameContractError::SidePoolMismatch,
+        )?;
+        Validator::validate_equality(
+            side_bet_unpacked.bettor,
+            *bettor.key,
+            MetalityGameContractError::BettorMismatch,
+        )?;
+
+        let payout = side_pool_unpacked.payout(side_bet_unpacked.side, side_bet_unpacked.amount);
+
+        if side_bet_unpacked.claimed || payout == 0 {
+            return Err(MetalityGameContractError::NothingToClaim.into());
+        }
+
+        EscrowAuthority {
+            pda_account: pool_pda_account,
+            escrow_state_account: side_pool_account,
+            bump_seeds,
+            token_program_account,
+        }
+        .transfer(vault_account, bettor_token_account, payout)?;
+
+        msg!("Side bet payout: {:?}", payout);
+
+        side_bet_unpacked.claimed = true;
+
+        MetalitySideBetState::pack(
+            side_bet_unpacked,
+            &mut side_bet_account.try_borrow_mut_data()?,
+        )?;
+
+        Ok(())
+    }
+
+    /// Pays user B's join bond back out of the game state account, handing
+    /// the forfeited share to `forfeit_to` when one is given.
+    fn release_join_bond<'a>(
+        game_state_account: &AccountInfo<'a>,
+        game_state: &mut MetalityGameContractState,
+        user_b: &AccountInfo<'a>,
+        forfeit_to: Option<&AccountInfo<'a>>,
+    ) -> ProgramResult {
+        if game_state.join_bond == 0 {
+            return Ok(());
+        }
+
+        let forfeited = match forfeit_to {
+            Some(forfeit_to) => {
+                let forfeited = game_state.forfeited_bond();
+
+                **forfeit_to.try_borrow_mut_lamports()? += forfeited;
+
+                msg!("Join bond forfeited: {:?}", forfeited);
+
+                forfeited
+            }
+            None => 0,
+        };
+
+        **user_b.try_borrow_mut_lamports()? += game_state.join_bond - forfeited;
+        **game_state_account.try_borrow_mut_lamports()? -= game_state.join_bond;
+
+        game_state.join_bond = 0;
+
+        Ok(())
+    }
+
+    /// Clears user B from a game that hasn't started so someone else can join.
+    /// Bets were placed on this pairing, so they are refunded and a new pool
+    /// can be opened for the next one.
+    fn reopen_game<'a, 'b: 'a, I: Iterator<Item = &'a AccountInfo<'b>>>(
+        account_info_iter: &mut I,
+        game_state: &mut MetalityGameContractState,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        Self::update_side_pool(
+            account_info_iter,
+            game_state,
+            program_id,
+            Some(POOL_OUTCOME_REFUND),
+        )?;
+
+        game_state.record_previous_opponent(game_state.user_b);
+        game_state.exit_count = game_state.exit_count.saturating_add(1);
+
+        msg!(
+            "Game reopened: {:?} left, {:?} exits so far",
+            game_state.user_b,
+            game_state.exit_count
+        );
+
+        game_state.side_pool = Pubkey::default();
+        game_state.cancel_proposer = Pubkey::default();
+        game_state.user_b_joined = false;
+        game_state.user_b = Pubkey::default();
+        game_state.b_nft_ata = Pubkey::default();
+        game_state.b_nft_mint = Pubkey::default();
+
+        Ok(())
+    }
+
+    fn load_side_pool(
+        side_pool_account: &AccountInfo,
+        program_id: &Pubkey,
+    ) -> Result<MetalitySidePoolState, ProgramError> {
+        if side_pool_account.owner != program_id {
+            return Err(MetalityGameContractError::InvalidSidePool.into());
+        }
+
+        MetalitySidePoolState::unpack(&side_pool_account.try_borrow_data()?)
+    }
+
+    /// Locks or settles the side pool attached to `game_state`, if there is one.
+    /// The pool account is then expected as the next account in the list.
+    fn update_side_pool<'a, 'b: 'a, I: Iterator<Item = &'a AccountInfo<'b>>>(
+        account_info_iter: &mut I,
+        game_state: &MetalityGameContractState,
+        program_id: &Pubkey,
+        outcome: Option<u8>,
+    ) -> ProgramResult {
+        if game_state.side_pool == Pubkey::default() {
+            return Ok(());
+        }
+
+        let side_pool_account = next_account_info(account_info_iter)?;
+
+        if *side_pool_account.key != game_state.side_pool {
+            return Err(MetalityGameContractError::InvalidSidePool.into());
+        }
+
+        let mut side_pool_unpacked = Self::load_side_pool(side_pool_account, program_id)?;
+
+        match outcome {
+            Some(outcome) => side_pool_unpacked.outcome = outcome,
+            None => side_pool_unpacked.locked = true,
+        }
+
+        MetalitySidePoolState::pack(
+            side_pool_unpacked,
+            &mut side_pool_account.try_borrow_mut_data()?,
+        )
+    }
 }
@@ -0,0 +1,121 @@
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    program_pack::Pack, pubkey::Pubkey,
+};
+
+use crate::error::MetalityGameContractError;
+use crate::rating::DEFAULT_RATING;
+use crate::state::MetalityPlayerProfileState;
+use crate::utils::create_pda_account;
+
+pub const PROFILE_SEED: &str = "player_profile";
+
+pub fn find_profile_address(player: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROFILE_SEED.as_bytes(), player.as_ref()], program_id)
+}
+
+/// Current rating of `player`, without creating a missing profile.
+pub fn read_rating(
+    profile_account: &AccountInfo,
+    player: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<u32, ProgramError> {
+    Ok(read_profile(profile_account, player, program_id)?
+        .map_or(DEFAULT_RATING, |profile| profile.rating))
+}
+
+/// Whether `player` opted into claiming their own rewards, without creating a
+/// missing profile.
+pub fn read_self_claim(
+    profile_account: &AccountInfo,
+    player: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<bool, ProgramError> {
+    Ok(
+        read_profile(profile_account, player, program_id)?
+            .is_some_and(|profile| profile.self_claim),
+    )
+}
+
+fn read_profile(
+    profile_account: &AccountInfo,
+    player: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<Option<MetalityPlayerProfileState>, ProgramError> {
+    let (profile_address, _bump_seeds) = find_profile_address(player, program_id);
+
+    if *profile_account.key != profile_address {
+        return Err(MetalityGameContractError::InvalidProfileAccount.into());
+    }
+
+    if profile_account.data_is_empty() {
+        return Ok(None);
+    }
+
+    if profile_account.owner != program_id {
+        return Err(MetalityGameContractError::InvalidProfileAccount.into());
+    }
+
+    MetalityPlayerProfileState::unpack(&profile_account.try_borrow_data()?).map(Some)
+}
+
+/// Loads player profiles, creating the PDA on first use at `payer`'s expense.
+pub struct ProfileLoader<'a, 'b> {
+    pub payer: &'b AccountInfo<'a>,
+    pub system_program_account: &'b AccountInfo<'a>,
+    pub program_id: &'b Pubkey,
+}
+
+impl<'a, 'b> ProfileLoader<'a, 'b> {
+    pub fn load(
+        &self,
+        profile_account: &AccountInfo<'a>,
+        player: &Pubkey,
+    ) -> Result<MetalityPlayerProfileState, ProgramError> {
+        let (profile_address, bump_seeds) = find_profile_address(player, self.program_id);
+
+        if *profile_account.key != profile_address {
+            return Err(MetalityGameContractError::InvalidProfileAccount.into());
+        }
+
+        if profile_account.data_is_empty() {
+            self.create(profile_account, player, bump_seeds)?;
+
+            return Ok(MetalityPlayerProfileState {
+                is_initialized: true,
+                player: *player,
+                rating: DEFAULT_RATING,
+                ..MetalityPlayerProfileState::default()
+            });
+        }
+
+        if profile_account.owner != self.program_id {
+            return Err(MetalityGameContractError::InvalidProfileAccount.into());
+        }
+
+        MetalityPlayerProfileState::unpack(&profile_account.try_borrow_data()?)
+    }
+
+    pub fn store(
+        profile_account: &AccountInfo<'a>,
+        profile: MetalityPlayerProfileState,
+    ) -> ProgramResult {
+        MetalityPlayerProfileState::pack(profile, &mut profile_account.try_borrow_mut_data()?)
+    }
+
+    fn create(
+        &self,
+        profile_account: &AccountInfo<'a>,
+        player: &Pubkey,
+        bump_seeds: u8,
+    ) -> ProgramResult {
+        create_pda_account(
+            self.payer,
+            profile_account,
+            self.system_program_account,
+            MetalityPlayerProfileState::LEN,
+            self.program_id,
+            &[PROFILE_SEED.as_bytes(), player.as_ref(), &[bump_seeds]],
+        )
+    }
+}
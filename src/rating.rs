@@ -0,0 +1,76 @@
+//! Elo rating math in integer fixed point, scores are in basis points.
+
+pub const DEFAULT_RATING: u32 = 1200;
+
+pub const K_FACTOR: i64 = 32;
+
+pub const SCORE_SCALE: i64 = 10_000;
+
+/// Rating gaps beyond this are treated as this gap.
+pub const MAX_RATING_DIFFERENCE: i64 = 800;
+
+const EXPECTED_SCORE_STEP: i64 = 25;
+
+/// `1 / (1 + 10^(d / 400))` in basis points for `d = 0, 25, .., 800`.
+const EXPECTED_SCORE_TABLE: [i64; 33] = [
+    5000, 4641, 4285, 3937, 3599, 3275, 2966, 2675, 2403, 2150, 1917, 1704, 1510, 1334, 1177, 1035,
+    909, 797, 698, 610, 532, 464, 405, 352, 307, 267, 232, 201, 175, 152, 132, 114, 99,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Score {
+    Win,
+    Draw,
+    Loss,
+}
+
+impl Score {
+    pub fn basis_points(&self) -> i64 {
+        match self {
+            Score::Win => SCORE_SCALE,
+            Score::Draw => SCORE_SCALE / 2,
+            Score::Loss => 0,
+        }
+    }
+}
+
+/// Expected score of a `rating` player against `opponent_rating`, in basis
+/// points, linearly interpolated between table entries.
+pub fn expected_score(rating: u32, opponent_rating: u32) -> i64 {
+    let difference = (opponent_rating as i64 - rating as i64)
+        .clamp(-MAX_RATING_DIFFERENCE, MAX_RATING_DIFFERENCE);
+
+    let gap = difference.abs();
+    let index = (gap / EXPECTED_SCORE_STEP) as usize;
+    let remainder = gap % EXPECTED_SCORE_STEP;
+
+    let lower = EXPECTED_SCORE_TABLE[index];
+    let upper = EXPECTED_SCORE_TABLE[(index + 1).min(EXPECTED_SCORE_TABLE.len() - 1)];
+
+    let underdog_score = lower - (lower - upper) * remainder / EXPECTED_SCORE_STEP;
+
+    if difference >= 0 {
+        underdog_score
+    } else {
+        SCORE_SCALE - underdog_score
+    }
+}
+
+/// New ratings for players `a` and `b` after `a` scored `score_a`.
+///
+/// The adjustment is rounded half away from zero and applied symmetrically;
+/// ratings never drop below zero.
+pub fn elo_update(rating_a: u32, rating_b: u32, score_a: Score) -> (u32, u32) {
+    let numerator = K_FACTOR * (score_a.basis_points() - expected_score(rating_a, rating_b));
+
+    let adjustment = if numerator >= 0 {
+        (numerator + SCORE_SCALE / 2) / SCORE_SCALE
+    } else {
+        (numerator - SCORE_SCALE / 2) / SCORE_SCALE
+    };
+
+    let new_rating_a = (rating_a as i64 + adjustment).clamp(0, u32::MAX as i64);
+    let new_rating_b = (rating_b as i64 - adjustment).clamp(0, u32::MAX as i64);
+
+    (new_rating_a as u32, new_rating_b as u32)
+}
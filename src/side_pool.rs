@@ -0,0 +1,37 @@
+use solana_program::pubkey::Pubkey;
+
+pub const SIDE_POOL_SEED: &str = "side_pool";
+
+pub const SIDE_BET_SEED: &str = "side_bet";
+
+/// Spectator pool for the current pairing of a game. Keyed by user B as well,
+/// so a fresh pool can be opened when user B leaves and someone else joins.
+pub fn find_side_pool_address(
+    game_state: &Pubkey,
+    user_b: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            SIDE_POOL_SEED.as_bytes(),
+            game_state.as_ref(),
+            user_b.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+pub fn find_side_bet_address(
+    side_pool: &Pubkey,
+    bettor: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            SIDE_BET_SEED.as_bytes(),
+            side_pool.as_ref(),
+            bettor.as_ref(),
+        ],
+        program_id,
+    )
+}
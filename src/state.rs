@@ -1,11 +1,31 @@
 use solana_program::{
     program_error::ProgramError,
     program_pack::{IsInitialized, Pack, Sealed},
-    pubkey::Pubkey,
+    pubkey::{Pubkey, MAX_SEED_LEN},
 };
 
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
 
+use crate::error::MetalityGameContractError;
+
+pub const BASIS_POINTS: u16 = 10_000;
+
+pub const GAME_OUTCOME_PENDING: u8 = 0;
+
+pub const GAME_OUTCOME_WON: u8 = 1;
+
+pub const GAME_OUTCOME_DRAW: u8 = 2;
+
+pub const GAME_OUTCOME_CANCELLED: u8 = 3;
+
+/// Won because the opponent resigned.
+pub const GAME_OUTCOME_FORFEIT: u8 = 4;
+
+/// Former joiners remembered per game, `blocked_opponents` has one bit each.
+pub const MAX_PREVIOUS_OPPONENTS: usize = 8;
+
+const PREVIOUS_OPPONENTS_LEN: usize = 32 * MAX_PREVIOUS_OPPONENTS;
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct MetalityGameContractState {
     pub is_initialized: bool,
@@ -19,6 +39,100 @@ pub struct MetalityGameContractState {
     pub pda_account: Pubkey,
     pub expired: bool,
     pub game_started: bool,
+    pub min_opponent_rating: u32,
+    pub max_opponent_rating: u32,
+    pub side_pool: Pubkey,
+    pub winner: Pubkey,
+    pub cancel_proposer: Pubkey,
+    pub outcome: u8,
+    pub join_bond: u64,
+    pub bond_forfeit_bps: u16,
+    pub previous_opponents: [Pubkey; MAX_PREVIOUS_OPPONENTS],
+    pub blocked_opponents: u8,
+    pub next_previous_opponent: u8,
+    pub exit_count: u16,
+}
+
+impl MetalityGameContractState {
+    /// Size of game accounts created before rating ranges were added. That
+    /// layout is a prefix of the current one.
+    pub const LEGACY_LEN: usize = 228;
+
+    /// Reads a game stored in the legacy layout, defaulting every field added
+    /// since. Legacy games accepted any opponent rating.
+    pub fn unpack_legacy(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() != Self::LEGACY_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mut data = [0u8; Self::LEN];
+        data[..Self::LEGACY_LEN].copy_from_slice(src);
+
+        let mut state = Self::unpack_from_slice(&data)?;
+        state.max_opponent_rating = u32::MAX;
+
+        Ok(state)
+    }
+
+    pub fn has_recorded_winner(&self) -> bool {
+        self.winner != Pubkey::default()
+    }
+
+    /// Part of user B's join bond handed to user A when B leaves early.
+    pub fn forfeited_bond(&self) -> u64 {
+        (self.join_bond as u128 * self.bond_forfeit_bps as u128 / BASIS_POINTS as u128) as u64
+    }
+
+    pub fn is_rating_bounded(&self) -> bool {
+        self.min_opponent_rating != 0 || self.max_opponent_rating != u32::MAX
+    }
+
+    pub fn previous_opponent_index(&self, opponent: &Pubkey) -> Option<usize> {
+        if *opponent == Pubkey::default() {
+            return None;
+        }
+
+        self.previous_opponents
+            .iter()
+            .position(|previous_opponent| previous_opponent == opponent)
+    }
+
+    pub fn is_blocked(&self, opponent: &Pubkey) -> bool {
+        self.previous_opponent_index(opponent)
+            .is_some_and(|index| self.blocked_opponents & (1 << index) != 0)
+    }
+
+    /// Remembers a former joiner, overwriting the oldest unblocked entry once
+    /// the list is full. Blocked wallets are never evicted.
+    pub fn record_previous_opponent(&mut self, opponent: Pubkey) {
+        if self.previous_opponent_index(&opponent).is_some() {
+            return;
+        }
+
+        for offset in 0..MAX_PREVIOUS_OPPONENTS {
+            let index = (self.next_previous_opponent as usize + offset) % MAX_PREVIOUS_OPPONENTS;
+
+            if self.blocked_opponents & (1 << index) == 0 {
+                self.previous_opponents[index] = opponent;
+                self.next_previous_opponent = ((index + 1) % MAX_PREVIOUS_OPPONENTS) as u8;
+                return;
+            }
+        }
+    }
+
+    pub fn set_blocked(&mut self, opponent: &Pubkey, blocked: bool) -> Result<(), ProgramError> {
+        let index = self
+            .previous_opponent_index(opponent)
+            .ok_or(MetalityGameContractError::NotPreviousOpponent)?;
+
+        if blocked {
+            self.blocked_opponents |= 1 << index;
+        } else {
+            self.blocked_opponents &= !(1 << index);
+        }
+
+        Ok(())
+    }
 }
 
 impl Sealed for MetalityGameContractState {}
@@ -30,7 +144,7 @@ impl IsInitialized for MetalityGameContractState {
 }
 
 impl Pack for MetalityGameContractState {
-    const LEN: usize = 228;
+    const LEN: usize = 603;
 
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
         let src = array_ref![src, 0, MetalityGameContractState::LEN];
@@ -47,7 +161,44 @@ impl Pack for MetalityGameContractState {
             pda_account,
             expired,
             game_started,
-        ) = array_refs![src, 1, 32, 32, 32, 32, 1, 32, 32, 32, 1, 1];
+            min_opponent_rating,
+            max_opponent_rating,
+            side_pool,
+            winner,
+            cancel_proposer,
+            outcome,
+            join_bond,
+            bond_forfeit_bps,
+            previous_opponents_src,
+            blocked_opponents,
+            next_previous_opponent,
+            exit_count,
+        ) = array_refs![
+            src,
+            1,
+            32,
+            32,
+            32,
+            32,
+            1,
+            32,
+            32,
+            32,
+            1,
+            1,
+            4,
+            4,
+            32,
+            32,
+            32,
+            1,
+            8,
+            2,
+            PREVIOUS_OPPONENTS_LEN,
+            1,
+            1,
+            2
+        ];
 
         let is_initialized = match is_initialized {
             [0] => false,
@@ -73,6 +224,19 @@ impl Pack for MetalityGameContractState {
             _ => return Err(ProgramError::InvalidAccountData),
         };
 
+        if next_previous_opponent[0] as usize >= MAX_PREVIOUS_OPPONENTS {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mut previous_opponents = [Pubkey::default(); MAX_PREVIOUS_OPPONENTS];
+
+        for (previous_opponent, previous_opponent_src) in previous_opponents
+            .iter_mut()
+            .zip(previous_opponents_src.chunks_exact(32))
+        {
+            *previous_opponent = Pubkey::new_from_array(*array_ref![previous_opponent_src, 0, 32]);
+        }
+
         Ok(MetalityGameContractState {
             is_initialized,
             user_a: Pubkey::new_from_array(*user_a),
@@ -85,6 +249,18 @@ impl Pack for MetalityGameContractState {
             pda_account: Pubkey::new_from_array(*pda_account),
             expired,
             game_started,
+            min_opponent_rating: u32::from_le_bytes(*min_opponent_rating),
+            max_opponent_rating: u32::from_le_bytes(*max_opponent_rating),
+            side_pool: Pubkey::new_from_array(*side_pool),
+            winner: Pubkey::new_from_array(*winner),
+            cancel_proposer: Pubkey::new_from_array(*cancel_proposer),
+            outcome: outcome[0],
+            join_bond: u64::from_le_bytes(*join_bond),
+            bond_forfeit_bps: u16::from_le_bytes(*bond_forfeit_bps),
+            previous_opponents,
+            blocked_opponents: blocked_opponents[0],
+            next_previous_opponent: next_previous_opponent[0],
+            exit_count: u16::from_le_bytes(*exit_count),
         })
     }
 
@@ -103,7 +279,44 @@ impl Pack for MetalityGameContractState {
             pda_account_dest,
             expired_dest,
             game_started_dest,
-        ) = mut_array_refs![dest, 1, 32, 32, 32, 32, 1, 32, 32, 32, 1, 1];
+            min_opponent_rating_dest,
+            max_opponent_rating_dest,
+            side_pool_dest,
+            winner_dest,
+            cancel_proposer_dest,
+            outcome_dest,
+            join_bond_dest,
+            bond_forfeit_bps_dest,
+            previous_opponents_dest,
+            blocked_opponents_dest,
+            next_previous_opponent_dest,
+            exit_count_dest,
+        ) = mut_array_refs![
+            dest,
+            1,
+            32,
+            32,
+            32,
+            32,
+            1,
+            32,
+            32,
+            32,
+            1,
+            1,
+            4,
+            4,
+            32,
+            32,
+            32,
+            1,
+            8,
+            2,
+            PREVIOUS_OPPONENTS_LEN,
+            1,
+            1,
+            2
+        ];
 
         let MetalityGameContractState {
             is_initialized,
@@ -117,6 +330,18 @@ impl Pack for MetalityGameContractState {
             pda_account,
             expired,
             game_started,
+            min_opponent_rating,
+            max_opponent_rating,
+            side_pool,
+            winner,
+            cancel_proposer,
+            outcome,
+            join_bond,
+            bond_forfeit_bps,
+            previous_opponents,
+            blocked_opponents,
+            next_previous_opponent,
+            exit_count,
         } = self;
 
         is_initialized_dest[0] = *is_initialized as u8;
@@ -130,13 +355,104 @@ impl Pack for MetalityGameContractState {
         pda_account_dest.copy_from_slice(pda_account.as_ref());
         expired_dest[0] = *expired as u8;
         game_started_dest[0] = *game_started as u8;
+        *min_opponent_rating_dest = min_opponent_rating.to_le_bytes();
+        *max_opponent_rating_dest = max_opponent_rating.to_le_bytes();
+        side_pool_dest.copy_from_slice(side_pool.as_ref());
+        winner_dest.copy_from_slice(winner.as_ref());
+        cancel_proposer_dest.copy_from_slice(cancel_proposer.as_ref());
+        outcome_dest[0] = *outcome;
+        *join_bond_dest = join_bond.to_le_bytes();
+        *bond_forfeit_bps_dest = bond_forfeit_bps.to_le_bytes();
+
+        for (previous_opponent, previous_opponent_dest) in previous_opponents
+            .iter()
+            .zip(previous_opponents_dest.chunks_exact_mut(32))
+        {
+            previous_opponent_dest.copy_from_slice(previous_opponent.as_ref());
+        }
+
+        blocked_opponents_dest[0] = *blocked_opponents;
+        next_previous_opponent_dest[0] = *next_previous_opponent;
+        *exit_count_dest = exit_count.to_le_bytes();
     }
 }
 
+pub const PROGRAM_DATA_SEED: &str = "Game Contract Main";
+
+pub const GAME_STATE_SEED_PREFIX: &str = "Metality Game State ";
+
+pub const LOBBY_SEED_PREFIX: &str = "Metality Lobby ";
+
+pub const TOURNAMENT_SEED_PREFIX: &str = "Metality Tournament ";
+
+pub const QUEUE_SEED_PREFIX: &str = "Metality Queue ";
+
+/// Only requires a zero-decimals mint and a single token in the staked account.
+pub const NFT_CHECK_BASIC: u8 = 0;
+
+/// Also requires a supply of one that can no longer grow: either no mint
+/// authority or a Metaplex master edition holding it.
+pub const NFT_CHECK_STRICT: u8 = 1;
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct MetalityGameProgramDataState {
     pub is_initialized: bool,
     pub index: u64,
+    pub oracle: Pubkey,
+    pub join_bond_lamports: u64,
+    pub bond_forfeit_bps: u16,
+    pub nft_check_level: u8,
+    pub admin: Pubkey,
+}
+
+impl MetalityGameProgramDataState {
+    /// Size of the program data account before anything beyond the index was
+    /// stored. That layout is a prefix of the current one.
+    pub const LEGACY_LEN: usize = 9;
+
+    /// Reads program data stored in the legacy layout. Settings added since
+    /// take the values a fresh initialization would give them, with `admin`
+    /// as the recorded admin.
+    pub fn unpack_legacy(src: &[u8], admin: Pubkey) -> Result<Self, ProgramError> {
+        if src.len() != Self::LEGACY_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mut data = [0u8; Self::LEN];
+        data[..Self::LEGACY_LEN].copy_from_slice(src);
+
+        let mut state = Self::unpack_from_slice(&data)?;
+        state.nft_check_level = NFT_CHECK_STRICT;
+        state.admin = admin;
+
+        Ok(state)
+    }
+
+    pub fn has_oracle(&self) -> bool {
+        self.oracle != Pubkey::default()
+    }
+
+    /// Seed of the account created at the current index. `create_account_with_seed`
+    /// caps seeds at `MAX_SEED_LEN` bytes, so large indexes run out of room
+    /// before the counter itself overflows.
+    pub fn indexed_seed(&self, prefix: &str) -> Result<String, ProgramError> {
+        let seed = format!("{}{}", prefix, self.index);
+
+        if seed.len() > MAX_SEED_LEN {
+            return Err(MetalityGameContractError::SeedTooLong.into());
+        }
+
+        Ok(seed)
+    }
+
+    pub fn advance_index(&mut self) -> Result<(), ProgramError> {
+        self.index = self
+            .index
+            .checked_add(1)
+            .ok_or(MetalityGameContractError::IndexOverflow)?;
+
+        Ok(())
+    }
 }
 
 impl Sealed for MetalityGameProgramDataState {}
@@ -148,12 +464,20 @@ impl IsInitialized for MetalityGameProgramDataState {
 }
 
 impl Pack for MetalityGameProgramDataState {
-    const LEN: usize = 9;
+    const LEN: usize = 84;
 
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
         let src = array_ref![src, 0, MetalityGameProgramDataState::LEN];
 
-        let (is_initialized, index) = array_refs![src, 1, 8];
+        let (
+            is_initialized,
+            index,
+            oracle,
+            join_bond_lamports,
+            bond_forfeit_bps,
+            nft_check_level,
+            admin,
+        ) = array_refs![src, 1, 8, 32, 8, 2, 1, 32];
 
         let is_initialized = match is_initialized {
             [0] => false,
@@ -164,20 +488,1066 @@ impl Pack for MetalityGameProgramDataState {
         Ok(MetalityGameProgramDataState {
             is_initialized,
             index: u64::from_le_bytes(*index),
+            oracle: Pubkey::new_from_array(*oracle),
+            join_bond_lamports: u64::from_le_bytes(*join_bond_lamports),
+            bond_forfeit_bps: u16::from_le_bytes(*bond_forfeit_bps),
+            nft_check_level: nft_check_level[0],
+            admin: Pubkey::new_from_array(*admin),
         })
     }
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let dest = array_mut_ref![dst, 0, MetalityGameProgramDataState::LEN];
 
-        let (is_initialized_dest, index_dest) = mut_array_refs![dest, 1, 8];
+        let (
+            is_initialized_dest,
+            index_dest,
+            oracle_dest,
+            join_bond_lamports_dest,
+            bond_forfeit_bps_dest,
+            nft_check_level_dest,
+            admin_dest,
+        ) = mut_array_refs![dest, 1, 8, 32, 8, 2, 1, 32];
 
         let MetalityGameProgramDataState {
             is_initialized,
             index,
+            oracle,
+            join_bond_lamports,
+            bond_forfeit_bps,
+            nft_check_level,
+            admin,
         } = self;
 
         is_initialized_dest[0] = *is_initialized as u8;
         *index_dest = index.to_le_bytes();
+        oracle_dest.copy_from_slice(oracle.as_ref());
+        *join_bond_lamports_dest = join_bond_lamports.to_le_bytes();
+        *bond_forfeit_bps_dest = bond_forfeit_bps.to_le_bytes();
+        nft_check_level_dest[0] = *nft_check_level;
+        admin_dest.copy_from_slice(admin.as_ref());
+    }
+}
+
+pub const MAX_LOBBY_PLAYERS: usize = 16;
+
+pub const MIN_LOBBY_PLAYERS: usize = 3;
+
+const LOBBY_ENTRANT_LEN: usize = 96;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MetalityLobbyEntrant {
+    pub user: Pubkey,
+    pub nft_ata: Pubkey,
+    pub nft_mint: Pubkey,
+}
+
+impl MetalityLobbyEntrant {
+    /// Stake recipients handing every stake back to the entrant who staked it.
+    pub fn own_stakes() -> [u8; MAX_LOBBY_PLAYERS] {
+        let mut recipients = [0u8; MAX_LOBBY_PLAYERS];
+
+        for (entrant, recipient) in recipients.iter_mut().enumerate() {
+            *recipient = entrant as u8;
+        }
+
+        recipients
+    }
+
+    fn unpack_entrants(
+        src: &[u8; LOBBY_ENTRANT_LEN * MAX_LOBBY_PLAYERS],
+    ) -> [MetalityLobbyEntrant; MAX_LOBBY_PLAYERS] {
+        let mut entrants = [MetalityLobbyEntrant::default(); MAX_LOBBY_PLAYERS];
+
+        for (entrant, entrant_src) in entrants.iter_mut().zip(src.chunks_exact(LOBBY_ENTRANT_LEN)) {
+            let entrant_src = array_ref![entrant_src, 0, LOBBY_ENTRANT_LEN];
+            let (user, nft_ata, nft_mint) = array_refs![entrant_src, 32, 32, 32];
+
+            *entrant = MetalityLobbyEntrant {
+                user: Pubkey::new_from_array(*user),
+                nft_ata: Pubkey::new_from_array(*nft_ata),
+                nft_mint: Pubkey::new_from_array(*nft_mint),
+            };
+        }
+
+        entrants
+    }
+
+    fn pack_entrants(
+        entrants: &[MetalityLobbyEntrant; MAX_LOBBY_PLAYERS],
+        dst: &mut [u8; LOBBY_ENTRANT_LEN * MAX_LOBBY_PLAYERS],
+    ) {
+        for (entrant, entrant_dest) in entrants.iter().zip(dst.chunks_exact_mut(LOBBY_ENTRANT_LEN))
+        {
+            let entrant_dest = array_mut_ref![entrant_dest, 0, LOBBY_ENTRANT_LEN];
+            let (user_dest, nft_ata_dest, nft_mint_dest) =
+                mut_array_refs![entrant_dest, 32, 32, 32];
+
+            user_dest.copy_from_slice(entrant.user.as_ref());
+            nft_ata_dest.copy_from_slice(entrant.nft_ata.as_ref());
+            nft_mint_dest.copy_from_slice(entrant.nft_mint.as_ref());
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetalityLobbyState {
+    pub is_initialized: bool,
+    pub creator: Pubkey,
+    pub pda_account: Pubkey,
+    pub max_players: u8,
+    pub player_count: u8,
+    pub expired: bool,
+    pub game_started: bool,
+    pub payout_table: [u8; MAX_LOBBY_PLAYERS],
+    pub entrants: [MetalityLobbyEntrant; MAX_LOBBY_PLAYERS],
+    /// Entrant receiving each entrant's stake, fixed once the lobby expires.
+    pub recipients: [u8; MAX_LOBBY_PLAYERS],
+    /// One bit per entrant whose stake has left the escrow.
+    pub released: u16,
+}
+
+impl MetalityLobbyState {
+    /// An all-zero payout table means the top finisher takes every stake.
+    pub fn is_winner_takes_all(&self) -> bool {
+        self.payout_table.iter().all(|count| *count == 0)
+    }
+
+    pub fn validate_payout_table(
+        payout_table: &[u8; MAX_LOBBY_PLAYERS],
+        max_players: u8,
+    ) -> Result<(), ProgramError> {
+        let total: usize = payout_table.iter().map(|count| *count as usize).sum();
+
+        let unused_ranks_empty = payout_table[max_players as usize..]
+            .iter()
+            .all(|count| *count == 0);
+
+        if total != 0 && (total != max_players as usize || !unused_ranks_empty) {
+            return Err(MetalityGameContractError::InvalidPayoutTable.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn entrant_index(&self, user: &Pubkey) -> Option<usize> {
+        self.entrants[..self.player_count as usize]
+            .iter()
+            .position(|entrant| entrant.user == *user)
+    }
+
+    /// Maps every entrant's stake to the entrant receiving it, given `ranking`
+    /// as entrant indices ordered from first to last place.
+    ///
+    /// With a payout table, each paid rank keeps its own stake first and the
+    /// stakes of unpaid ranks fill the remaining slots, worst finisher first.
+    pub fn allocate_stakes(&self, ranking: &[u8]) -> Result<[u8; MAX_LOBBY_PLAYERS], ProgramError> {
+        let player_count = self.player_count as usize;
+
+        if ranking.len() != player_count {
+            return Err(MetalityGameContractError::InvalidRanking.into());
+        }
+
+        let mut seen = [false; MAX_LOBBY_PLAYERS];
+
+        for entrant in ranking {
+            let entrant = *entrant as usize;
+
+            if entrant >= player_count || seen[entrant] {
+                return Err(MetalityGameContractError::InvalidRanking.into());
+            }
+
+            seen[entrant] = true;
+        }
+
+        let mut recipients = [0u8; MAX_LOBBY_PLAYERS];
+
+        if self.is_winner_takes_all() {
+            recipients[..player_count].fill(ranking[0]);
+
+            return Ok(recipients);
+        }
+
+        let mut remaining = [0u8; MAX_LOBBY_PLAYERS];
+        let mut unallocated = Vec::with_capacity(player_count);
+
+        for (rank, entrant) in ranking.iter().enumerate() {
+            match self.payout_table[rank] {
+                0 => unallocated.push(*entrant),
+                count => {
+                    recipients[*entrant as usize] = *entrant;
+                    remaining[rank] = count - 1;
+                }
+            }
+        }
+
+        for (rank, entrant) in ranking.iter().enumerate() {
+            for _ in 0..remaining[rank] {
+                let stake = unallocated
+                    .pop()
+                    .ok_or(MetalityGameContractError::InvalidPayoutTable)?;
+
+                recipients[stake as usize] = *entrant;
+            }
+        }
+
+        if !unallocated.is_empty() {
+            return Err(MetalityGameContractError::InvalidPayoutTable.into());
+        }
+
+        Ok(recipients)
+    }
+}
+
+impl Sealed for MetalityLobbyState {}
+
+impl IsInitialized for MetalityLobbyState {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for MetalityLobbyState {
+    const LEN: usize = 1639;
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, MetalityLobbyState::LEN];
+
+        let (
+            is_initialized,
+            creator,
+            pda_account,
+            max_players,
+            player_count,
+            expired,
+            game_started,
+            payout_table,
+            entrants_src,
+            recipients,
+            released,
+        ) = array_refs![
+            src,
+            1,
+            32,
+            32,
+            1,
+            1,
+            1,
+            1,
+            MAX_LOBBY_PLAYERS,
+            LOBBY_ENTRANT_LEN * MAX_LOBBY_PLAYERS,
+            MAX_LOBBY_PLAYERS,
+            2
+        ];
+
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        let expired = match expired {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        let game_started = match game_started {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        if max_players[0] as usize > MAX_LOBBY_PLAYERS || player_count[0] > max_players[0] {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let entrants = MetalityLobbyEntrant::unpack_entrants(entrants_src);
+
+        Ok(MetalityLobbyState {
+            is_initialized,
+            creator: Pubkey::new_from_array(*creator),
+            pda_account: Pubkey::new_from_array(*pda_account),
+            max_players: max_players[0],
+            player_count: player_count[0],
+            expired,
+            game_started,
+            payout_table: *payout_table,
+            entrants,
+            recipients: *recipients,
+            released: u16::from_le_bytes(*released),
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dest = array_mut_ref![dst, 0, MetalityLobbyState::LEN];
+
+        let (
+            is_initialized_dest,
+            creator_dest,
+            pda_account_dest,
+            max_players_dest,
+            player_count_dest,
+            expired_dest,
+            game_started_dest,
+            payout_table_dest,
+            entrants_dest,
+            recipients_dest,
+            released_dest,
+        ) = mut_array_refs![
+            dest,
+            1,
+            32,
+            32,
+            1,
+            1,
+            1,
+            1,
+            MAX_LOBBY_PLAYERS,
+            LOBBY_ENTRANT_LEN * MAX_LOBBY_PLAYERS,
+            MAX_LOBBY_PLAYERS,
+            2
+        ];
+
+        let MetalityLobbyState {
+            is_initialized,
+            creator,
+            pda_account,
+            max_players,
+            player_count,
+            expired,
+            game_started,
+            payout_table,
+            entrants,
+            recipients,
+            released,
+        } = self;
+
+        is_initialized_dest[0] = *is_initialized as u8;
+        creator_dest.copy_from_slice(creator.as_ref());
+        pda_account_dest.copy_from_slice(pda_account.as_ref());
+        max_players_dest[0] = *max_players;
+        player_count_dest[0] = *player_count;
+        expired_dest[0] = *expired as u8;
+        game_started_dest[0] = *game_started as u8;
+        payout_table_dest.copy_from_slice(payout_table);
+
+        MetalityLobbyEntrant::pack_entrants(entrants, entrants_dest);
+        recipients_dest.copy_from_slice(recipients);
+        *released_dest = released.to_le_bytes();
+    }
+}
+
+pub const MAX_TOURNAMENT_MATCHES: usize = MAX_LOBBY_PLAYERS - 1;
+
+/// Marks a bracket slot whose winner is not known yet.
+pub const NO_ENTRANT: u8 = u8::MAX;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetalityTournamentState {
+    pub is_initialized: bool,
+    pub pda_account: Pubkey,
+    pub capacity: u8,
+    pub entrant_count: u8,
+    pub started: bool,
+    pub expired: bool,
+    pub champion: u8,
+    pub entrants: [MetalityLobbyEntrant; MAX_LOBBY_PLAYERS],
+    pub match_games: [Pubkey; MAX_TOURNAMENT_MATCHES],
+    pub match_winners: [u8; MAX_TOURNAMENT_MATCHES],
+    /// Entrant receiving each entrant's stake, fixed once the tournament expires.
+    pub recipients: [u8; MAX_LOBBY_PLAYERS],
+    /// One bit per entrant whose stake has left the escrow.
+    pub released: u16,
+}
+
+impl MetalityTournamentState {
+    pub fn is_valid_capacity(capacity: u8) -> bool {
+        capacity >= 2 && capacity as usize <= MAX_LOBBY_PLAYERS && capacity.is_power_of_two()
+    }
+
+    pub fn match_count(&self) -> usize {
+        (self.capacity as usize).saturating_sub(1)
+    }
+
+    pub fn final_match(&self) -> usize {
+        (self.capacity as usize).saturating_sub(2)
+    }
+
+    /// Entrant indices meeting in `match_index`, once both are known.
+    ///
+    /// First round matches pair entrants in registration order; every later
+    /// match `m` is fed by the winners of matches `2m - capacity` and
+    /// `2m - capacity + 1`, so the final is match `capacity - 2`.
+    pub fn match_participants(&self, match_index: usize) -> Option<(u8, u8)> {
+        let capacity = self.capacity as usize;
+
+        if match_index >= self.match_count() {
+            return None;
+        }
+
+        if match_index < capacity / 2 {
+            return Some(((2 * match_index) as u8, (2 * match_index + 1) as u8));
+        }
+
+        let feeder = 2 * match_index - capacity;
+
+        match (self.match_winners[feeder], self.match_winners[feeder + 1]) {
+            (NO_ENTRANT, _) | (_, NO_ENTRANT) => None,
+            participants => Some(participants),
+        }
+    }
+
+    pub fn match_index(&self, game_state: &Pubkey) -> Option<usize> {
+        if *game_state == Pubkey::default() {
+            return None;
+        }
+
+        self.match_games[..self.match_count()]
+            .iter()
+            .position(|match_game| match_game == game_state)
+    }
+}
+
+impl Sealed for MetalityTournamentState {}
+
+impl IsInitialized for MetalityTournamentState {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for MetalityTournamentState {
+    const LEN: usize = 2087;
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, MetalityTournamentState::LEN];
+
+        let (
+            is_initialized,
+            pda_account,
+            capacity,
+            entrant_count,
+            started,
+            expired,
+            champion,
+            entrants_src,
+            match_games_src,
+            match_winners,
+            recipients,
+            released,
+        ) = array_refs![
+            src,
+            1,
+            32,
+            1,
+            1,
+            1,
+            1,
+            1,
+            LOBBY_ENTRANT_LEN * MAX_LOBBY_PLAYERS,
+            32 * MAX_TOURNAMENT_MATCHES,
+            MAX_TOURNAMENT_MATCHES,
+            MAX_LOBBY_PLAYERS,
+            2
+        ];
+
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        let started = match started {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        let expired = match expired {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        if capacity[0] as usize > MAX_LOBBY_PLAYERS || entrant_count[0] > capacity[0] {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mut match_games = [Pubkey::default(); MAX_TOURNAMENT_MATCHES];
+
+        for (match_game, match_game_src) in
+            match_games.iter_mut().zip(match_games_src.chunks_exact(32))
+        {
+            *match_game = Pubkey::new_from_array(*array_ref![match_game_src, 0, 32]);
+        }
+
+        Ok(MetalityTournamentState {
+            is_initialized,
+            pda_account: Pubkey::new_from_array(*pda_account),
+            capacity: capacity[0],
+            entrant_count: entrant_count[0],
+            started,
+            expired,
+            champion: champion[0],
+            entrants: MetalityLobbyEntrant::unpack_entrants(entrants_src),
+            match_games,
+            match_winners: *match_winners,
+            recipients: *recipients,
+            released: u16::from_le_bytes(*released),
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dest = array_mut_ref![dst, 0, MetalityTournamentState::LEN];
+
+        let (
+            is_initialized_dest,
+            pda_account_dest,
+            capacity_dest,
+            entrant_count_dest,
+            started_dest,
+            expired_dest,
+            champion_dest,
+            entrants_dest,
+            match_games_dest,
+            match_winners_dest,
+            recipients_dest,
+            released_dest,
+        ) = mut_array_refs![
+            dest,
+            1,
+            32,
+            1,
+            1,
+            1,
+            1,
+            1,
+            LOBBY_ENTRANT_LEN * MAX_LOBBY_PLAYERS,
+            32 * MAX_TOURNAMENT_MATCHES,
+            MAX_TOURNAMENT_MATCHES,
+            MAX_LOBBY_PLAYERS,
+            2
+        ];
+
+        let MetalityTournamentState {
+            is_initialized,
+            pda_account,
+            capacity,
+            entrant_count,
+            started,
+            expired,
+            champion,
+            entrants,
+            match_games,
+            match_winners,
+            recipients,
+            released,
+        } = self;
+
+        is_initialized_dest[0] = *is_initialized as u8;
+        pda_account_dest.copy_from_slice(pda_account.as_ref());
+        capacity_dest[0] = *capacity;
+        entrant_count_dest[0] = *entrant_count;
+        started_dest[0] = *started as u8;
+        expired_dest[0] = *expired as u8;
+        champion_dest[0] = *champion;
+        MetalityLobbyEntrant::pack_entrants(entrants, entrants_dest);
+
+        for (match_game, match_game_dest) in match_games
+            .iter()
+            .zip(match_games_dest.chunks_exact_mut(32))
+        {
+            match_game_dest.copy_from_slice(match_game.as_ref());
+        }
+
+        match_winners_dest.copy_from_slice(match_winners);
+        recipients_dest.copy_from_slice(recipients);
+        *released_dest = released.to_le_bytes();
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MetalityPlayerProfileState {
+    pub is_initialized: bool,
+    pub player: Pubkey,
+    pub games_played: u64,
+    pub wins: u64,
+    pub losses: u64,
+    pub draws: u64,
+    pub cancelled: u64,
+    /// NFT stakes put on the line in decided or drawn games, one per game.
+    /// Stakes carry no on-chain price, so this counts NFTs rather than value.
+    pub nfts_wagered: u64,
+    pub rating: u32,
+    /// Set when the player wants to sign and pay for their own reward claims
+    /// instead of letting anyone settle games they won.
+    pub self_claim: bool,
+}
+
+impl MetalityPlayerProfileState {
+    pub fn record_win(&mut self) {
+        self.games_played = self.games_played.saturating_add(1);
+        self.wins = self.wins.saturating_add(1);
+        self.nfts_wagered = self.nfts_wagered.saturating_add(1);
+    }
+
+    pub fn record_loss(&mut self) {
+        self.games_played = self.games_played.saturating_add(1);
+        self.losses = self.losses.saturating_add(1);
+        self.nfts_wagered = self.nfts_wagered.saturating_add(1);
+    }
+
+    pub fn record_draw(&mut self) {
+        self.games_played = self.games_played.saturating_add(1);
+        self.draws = self.draws.saturating_add(1);
+        self.nfts_wagered = self.nfts_wagered.saturating_add(1);
+    }
+
+    pub fn record_cancel(&mut self) {
+        self.cancelled = self.cancelled.saturating_add(1);
+    }
+}
+
+impl Sealed for MetalityPlayerProfileState {}
+
+impl IsInitialized for MetalityPlayerProfileState {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for MetalityPlayerProfileState {
+    const LEN: usize = 86;
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, MetalityPlayerProfileState::LEN];
+
+        let (
+            is_initialized,
+            player,
+            games_played,
+            wins,
+            losses,
+            draws,
+            cancelled,
+            nfts_wagered,
+            rating,
+            self_claim,
+        ) = array_refs![src, 1, 32, 8, 8, 8, 8, 8, 8, 4, 1];
+
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        let self_claim = match self_claim {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(MetalityPlayerProfileState {
+            is_initialized,
+            player: Pubkey::new_from_array(*player),
+            games_played: u64::from_le_bytes(*games_played),
+            wins: u64::from_le_bytes(*wins),
+            losses: u64::from_le_bytes(*losses),
+            draws: u64::from_le_bytes(*draws),
+            cancelled: u64::from_le_bytes(*cancelled),
+            nfts_wagered: u64::from_le_bytes(*nfts_wagered),
+            rating: u32::from_le_bytes(*rating),
+            self_claim,
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dest = array_mut_ref![dst, 0, MetalityPlayerProfileState::LEN];
+
+        let (
+            is_initialized_dest,
+            player_dest,
+            games_played_dest,
+            wins_dest,
+            losses_dest,
+            draws_dest,
+            cancelled_dest,
+            nfts_wagered_dest,
+            rating_dest,
+            self_claim_dest,
+        ) = mut_array_refs![dest, 1, 32, 8, 8, 8, 8, 8, 8, 4, 1];
+
+        let MetalityPlayerProfileState {
+            is_initialized,
+            player,
+            games_played,
+            wins,
+            losses,
+            draws,
+            cancelled,
+            nfts_wagered,
+            rating,
+            self_claim,
+        } = self;
+
+        is_initialized_dest[0] = *is_initialized as u8;
+        player_dest.copy_from_slice(player.as_ref());
+        *games_played_dest = games_played.to_le_bytes();
+        *wins_dest = wins.to_le_bytes();
+        *losses_dest = losses.to_le_bytes();
+        *draws_dest = draws.to_le_bytes();
+        *cancelled_dest = cancelled.to_le_bytes();
+        *nfts_wagered_dest = nfts_wagered.to_le_bytes();
+        *rating_dest = rating.to_le_bytes();
+        self_claim_dest[0] = *self_claim as u8;
+    }
+}
+
+pub const MAX_QUEUE_ENTRIES: usize = 16;
+
+const QUEUE_ENTRY_LEN: usize = 108;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MetalityQueueEntry {
+    pub user: Pubkey,
+    pub nft_ata: Pubkey,
+    pub nft_mint: Pubkey,
+    pub rating: u32,
+    pub min_opponent_rating: u32,
+    pub max_opponent_rating: u32,
+}
+
+impl MetalityQueueEntry {
+    pub fn accepts(&self, opponent: &MetalityQueueEntry) -> bool {
+        (self.min_opponent_rating..=self.max_opponent_rating).contains(&opponent.rating)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetalityQueueState {
+    pub is_initialized: bool,
+    pub pda_account: Pubkey,
+    pub entry_count: u8,
+    pub entries: [MetalityQueueEntry; MAX_QUEUE_ENTRIES],
+}
+
+impl MetalityQueueState {
+    pub fn entry_index(&self, user: &Pubkey) -> Option<usize> {
+        self.entries[..self.entry_count as usize]
+            .iter()
+            .position(|entry| entry.user == *user)
+    }
+
+    pub fn remove_entry(&mut self, index: usize) {
+        let entry_count = self.entry_count as usize;
+
+        self.entries.copy_within(index + 1..entry_count, index);
+        self.entries[entry_count - 1] = MetalityQueueEntry::default();
+        self.entry_count -= 1;
+    }
+}
+
+impl Sealed for MetalityQueueState {}
+
+impl IsInitialized for MetalityQueueState {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for MetalityQueueState {
+    const LEN: usize = 1762;
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, MetalityQueueState::LEN];
+
+        let (is_initialized, pda_account, entry_count, entries_src) =
+            array_refs![src, 1, 32, 1, QUEUE_ENTRY_LEN * MAX_QUEUE_ENTRIES];
+
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        if entry_count[0] as usize > MAX_QUEUE_ENTRIES {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mut entries = [MetalityQueueEntry::default(); MAX_QUEUE_ENTRIES];
+
+        for (entry, entry_src) in entries
+            .iter_mut()
+            .zip(entries_src.chunks_exact(QUEUE_ENTRY_LEN))
+        {
+            let entry_src = array_ref![entry_src, 0, QUEUE_ENTRY_LEN];
+            let (user, nft_ata, nft_mint, rating, min_opponent_rating, max_opponent_rating) =
+                array_refs![entry_src, 32, 32, 32, 4, 4, 4];
+
+            *entry = MetalityQueueEntry {
+                user: Pubkey::new_from_array(*user),
+                nft_ata: Pubkey::new_from_array(*nft_ata),
+                nft_mint: Pubkey::new_from_array(*nft_mint),
+                rating: u32::from_le_bytes(*rating),
+                min_opponent_rating: u32::from_le_bytes(*min_opponent_rating),
+                max_opponent_rating: u32::from_le_bytes(*max_opponent_rating),
+            };
+        }
+
+        Ok(MetalityQueueState {
+            is_initialized,
+            pda_account: Pubkey::new_from_array(*pda_account),
+            entry_count: entry_count[0],
+            entries,
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dest = array_mut_ref![dst, 0, MetalityQueueState::LEN];
+
+        let (is_initialized_dest, pda_account_dest, entry_count_dest, entries_dest) =
+            mut_array_refs![dest, 1, 32, 1, QUEUE_ENTRY_LEN * MAX_QUEUE_ENTRIES];
+
+        let MetalityQueueState {
+            is_initialized,
+            pda_account,
+            entry_count,
+            entries,
+        } = self;
+
+        is_initialized_dest[0] = *is_initialized as u8;
+        pda_account_dest.copy_from_slice(pda_account.as_ref());
+        entry_count_dest[0] = *entry_count;
+
+        for (entry, entry_dest) in entries
+            .iter()
+            .zip(entries_dest.chunks_exact_mut(QUEUE_ENTRY_LEN))
+        {
+            let entry_dest = array_mut_ref![entry_dest, 0, QUEUE_ENTRY_LEN];
+            let (
+                user_dest,
+                nft_ata_dest,
+                nft_mint_dest,
+                rating_dest,
+                min_opponent_rating_dest,
+                max_opponent_rating_dest,
+            ) = mut_array_refs![entry_dest, 32, 32, 32, 4, 4, 4];
+
+            user_dest.copy_from_slice(entry.user.as_ref());
+            nft_ata_dest.copy_from_slice(entry.nft_ata.as_ref());
+            nft_mint_dest.copy_from_slice(entry.nft_mint.as_ref());
+            *rating_dest = entry.rating.to_le_bytes();
+            *min_opponent_rating_dest = entry.min_opponent_rating.to_le_bytes();
+            *max_opponent_rating_dest = entry.max_opponent_rating.to_le_bytes();
+        }
+    }
+}
+
+pub const SIDE_USER_A: u8 = 1;
+
+pub const SIDE_USER_B: u8 = 2;
+
+pub const POOL_OUTCOME_PENDING: u8 = 0;
+
+/// Pool outcome when the game ends without a winner and every bet is returned.
+pub const POOL_OUTCOME_REFUND: u8 = 3;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MetalitySidePoolState {
+    pub is_initialized: bool,
+    pub game_state: Pubkey,
+    pub token_mint: Pubkey,
+    pub vault: Pubkey,
+    pub total_a: u64,
+    pub total_b: u64,
+    pub locked: bool,
+    pub outcome: u8,
+}
+
+impl MetalitySidePoolState {
+    pub fn is_settled(&self) -> bool {
+        self.outcome != POOL_OUTCOME_PENDING
+    }
+
+    /// Amount owed to a bet of `amount` on `side` once the pool is settled.
+    ///
+    /// Winners get their stake back plus a pro rata share of the losing side.
+    /// If nobody backed the winner every bet is refunded instead.
+    pub fn payout(&self, side: u8, amount: u64) -> u64 {
+        let (winning_total, losing_total) = match self.outcome {
+            SIDE_USER_A => (self.total_a, self.total_b),
+            SIDE_USER_B => (self.total_b, self.total_a),
+            POOL_OUTCOME_REFUND => return amount,
+            _ => return 0,
+        };
+
+        if winning_total == 0 {
+            return amount;
+        }
+
+        if side != self.outcome {
+            return 0;
+        }
+
+        let share = amount as u128 * losing_total as u128 / winning_total as u128;
+
+        amount.saturating_add(share as u64)
+    }
+}
+
+impl Sealed for MetalitySidePoolState {}
+
+impl IsInitialized for MetalitySidePoolState {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for MetalitySidePoolState {
+    const LEN: usize = 115;
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, MetalitySidePoolState::LEN];
+
+        let (is_initialized, game_state, token_mint, vault, total_a, total_b, locked, outcome) =
+            array_refs![src, 1, 32, 32, 32, 8, 8, 1, 1];
+
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        let locked = match locked {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(MetalitySidePoolState {
+            is_initialized,
+            game_state: Pubkey::new_from_array(*game_state),
+            token_mint: Pubkey::new_from_array(*token_mint),
+            vault: Pubkey::new_from_array(*vault),
+            total_a: u64::from_le_bytes(*total_a),
+            total_b: u64::from_le_bytes(*total_b),
+            locked,
+            outcome: outcome[0],
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dest = array_mut_ref![dst, 0, MetalitySidePoolState::LEN];
+
+        let (
+            is_initialized_dest,
+            game_state_dest,
+            token_mint_dest,
+            vault_dest,
+            total_a_dest,
+            total_b_dest,
+            locked_dest,
+            outcome_dest,
+        ) = mut_array_refs![dest, 1, 32, 32, 32, 8, 8, 1, 1];
+
+        let MetalitySidePoolState {
+            is_initialized,
+            game_state,
+            token_mint,
+            vault,
+            total_a,
+            total_b,
+            locked,
+            outcome,
+        } = self;
+
+        is_initialized_dest[0] = *is_initialized as u8;
+        game_state_dest.copy_from_slice(game_state.as_ref());
+        token_mint_dest.copy_from_slice(token_mint.as_ref());
+        vault_dest.copy_from_slice(vault.as_ref());
+        *total_a_dest = total_a.to_le_bytes();
+        *total_b_dest = total_b.to_le_bytes();
+        locked_dest[0] = *locked as u8;
+        outcome_dest[0] = *outcome;
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MetalitySideBetState {
+    pub is_initialized: bool,
+    pub side_pool: Pubkey,
+    pub bettor: Pubkey,
+    pub side: u8,
+    pub amount: u64,
+    pub claimed: bool,
+}
+
+impl Sealed for MetalitySideBetState {}
+
+impl IsInitialized for MetalitySideBetState {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for MetalitySideBetState {
+    const LEN: usize = 75;
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, MetalitySideBetState::LEN];
+
+        let (is_initialized, side_pool, bettor, side, amount, claimed) =
+            array_refs![src, 1, 32, 32, 1, 8, 1];
+
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        let claimed = match claimed {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(MetalitySideBetState {
+            is_initialized,
+            side_pool: Pubkey::new_from_array(*side_pool),
+            bettor: Pubkey::new_from_array(*bettor),
+            side: side[0],
+            amount: u64::from_le_bytes(*amount),
+            claimed,
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dest = array_mut_ref![dst, 0, MetalitySideBetState::LEN];
+
+        let (
+            is_initialized_dest,
+            side_pool_dest,
+            bettor_dest,
+            side_dest,
+            amount_dest,
+            claimed_dest,
+        ) = mut_array_refs![dest, 1, 32, 32, 1, 8, 1];
+
+        let MetalitySideBetState {
+            is_initialized,
+            side_pool,
+            bettor,
+            side,
+            amount,
+            claimed,
+        } = self;
+
+        is_initialized_dest[0] = *is_initialized as u8;
+        side_pool_dest.copy_from_slice(side_pool.as_ref());
+        bettor_dest.copy_from_slice(bettor.as_ref());
+        side_dest[0] = *side;
+        *amount_dest = amount.to_le_bytes();
+        claimed_dest[0] = *claimed as u8;
     }
 }
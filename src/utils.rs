@@ -0,0 +1,159 @@
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+use crate::validations::Validator;
+
+/// Creates a program owned PDA account of `space` bytes paid by `payer`.
+///
+/// Funds, allocates and assigns separately rather than `create_account`, so
+/// lamports sent to the address beforehand can't block creation.
+pub fn create_pda_account<'a>(
+    payer: &AccountInfo<'a>,
+    new_account: &AccountInfo<'a>,
+    system_program_account: &AccountInfo<'a>,
+    space: usize,
+    program_id: &Pubkey,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    Validator::validate_uninitialized(new_account)?;
+
+    let required_lamports = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(new_account.lamports());
+
+    if required_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, new_account.key, required_lamports),
+            &[
+                payer.clone(),
+                new_account.clone(),
+                system_program_account.clone(),
+            ],
+        )?;
+    }
+
+    invoke_signed(
+        &system_instruction::allocate(new_account.key, space as u64),
+        &[new_account.clone(), system_program_account.clone()],
+        &[signer_seeds],
+    )?;
+
+    invoke_signed(
+        &system_instruction::assign(new_account.key, program_id),
+        &[new_account.clone(), system_program_account.clone()],
+        &[signer_seeds],
+    )?;
+
+    Validator::validate_rent_exempt(new_account)
+}
+
+/// Creates a program owned account of `space` bytes at the address derived
+/// from `payer`, `seed` and `program_id`, with `payer` as base and funder.
+///
+/// Accounts already holding data are rejected, while an address that was only
+/// sent lamports beforehand is topped up, allocated and assigned in place.
+pub fn create_seeded_account<'a>(
+    payer: &AccountInfo<'a>,
+    new_account: &AccountInfo<'a>,
+    system_program_account: &AccountInfo<'a>,
+    seed: &str,
+    space: usize,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    Validator::validate_uninitialized(new_account)?;
+
+    let minimum_balance = Rent::get()?.minimum_balance(space);
+
+    if new_account.lamports() == 0 {
+        invoke(
+            &system_instruction::create_account_with_seed(
+                payer.key,
+                new_account.key,
+                payer.key,
+                seed,
+                minimum_balance,
+                space as u64,
+                program_id,
+            ),
+            &[
+                payer.clone(),
+                new_account.clone(),
+                system_program_account.clone(),
+            ],
+        )?;
+    } else {
+        let required_lamports = minimum_balance.saturating_sub(new_account.lamports());
+
+        if required_lamports > 0 {
+            invoke(
+                &system_instruction::transfer(payer.key, new_account.key, required_lamports),
+                &[
+                    payer.clone(),
+                    new_account.clone(),
+                    system_program_account.clone(),
+                ],
+            )?;
+        }
+
+        invoke(
+            &system_instruction::allocate_with_seed(
+                new_account.key,
+                payer.key,
+                seed,
+                space as u64,
+                program_id,
+            ),
+            &[
+                new_account.clone(),
+                payer.clone(),
+                system_program_account.clone(),
+            ],
+        )?;
+
+        invoke(
+            &system_instruction::assign_with_seed(new_account.key, payer.key, seed, program_id),
+            &[
+                new_account.clone(),
+                payer.clone(),
+                system_program_account.clone(),
+            ],
+        )?;
+    }
+
+    Validator::validate_rent_exempt(new_account)
+}
+
+/// Grows a program owned account to `space` bytes, with `payer` topping up
+/// its balance to the new rent exempt minimum. New bytes are zeroed.
+pub fn resize_account<'a>(
+    payer: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    system_program_account: &AccountInfo<'a>,
+    space: usize,
+) -> ProgramResult {
+    let required_lamports = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(account.lamports());
+
+    if required_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, required_lamports),
+            &[
+                payer.clone(),
+                account.clone(),
+                system_program_account.clone(),
+            ],
+        )?;
+    }
+
+    account.realloc(space, true)?;
+
+    Validator::validate_rent_exempt(account)
+}
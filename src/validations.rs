@@ -1,11 +1,18 @@
 use solana_program::{
-    account_info::AccountInfo, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey,
+    account_info::AccountInfo, program_error::ProgramError, program_option::COption,
+    program_pack::Pack, pubkey::Pubkey, rent::Rent, system_program, sysvar, sysvar::Sysvar,
 };
 use spl_token;
 
 use crate::error::MetalityGameContractError;
-use crate::state::MetalityGameContractState;
+use crate::metaplex::find_master_edition_address;
+use crate::state::{
+    MetalityGameContractState, MetalityGameProgramDataState, NFT_CHECK_BASIC, NFT_CHECK_STRICT,
+    PROGRAM_DATA_SEED,
+};
 
+/// Deployer of the program data account, whose address derives from this key.
+/// Initializing the program data records this key as its admin.
 pub mod admin {
     solana_program::declare_id!("3KBgdH5xuVWKVB85L3SaRAiHXhDb77yd9qc6rxpNL2hr");
 }
@@ -42,6 +49,99 @@ impl Validator {
             return Err(MetalityGameContractError::InvalidTokenATA.into());
         }
 
+        // A staked account must stay fully under the escrow PDA's control: a
+        // delegate could move the NFT out and a close authority could close it.
+        if token_ata_unpacked.delegate.is_some() {
+            return Err(MetalityGameContractError::TokenAccountDelegated.into());
+        }
+
+        if token_ata_unpacked.close_authority.is_some() {
+            return Err(MetalityGameContractError::TokenAccountCloseAuthoritySet.into());
+        }
+
+        if token_ata_unpacked.is_frozen() {
+            return Err(MetalityGameContractError::TokenAccountFrozen.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_nft_mint(
+        token_mint: &AccountInfo,
+        nft_check_level: u8,
+    ) -> Result<(), ProgramError> {
+        let token_mint_unpacked = spl_token::state::Mint::unpack(&token_mint.try_borrow_data()?)?;
+
+        match nft_check_level {
+            NFT_CHECK_BASIC => Ok(()),
+            NFT_CHECK_STRICT => {
+                if token_mint_unpacked.supply != 1 {
+                    return Err(MetalityGameContractError::InvalidNftMint.into());
+                }
+
+                match token_mint_unpacked.mint_authority {
+                    COption::None => Ok(()),
+                    COption::Some(authority)
+                        if authority == find_master_edition_address(token_mint.key).0 =>
+                    {
+                        Ok(())
+                    }
+                    COption::Some(_) => Err(MetalityGameContractError::InvalidNftMint.into()),
+                }
+            }
+            _ => Err(MetalityGameContractError::InvalidNftCheckLevel.into()),
+        }
+    }
+
+    pub fn validate_token_program(token_program: &AccountInfo) -> Result<(), ProgramError> {
+        if *token_program.key != spl_token::ID {
+            return Err(MetalityGameContractError::InvalidTokenProgram.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_system_program(system_program: &AccountInfo) -> Result<(), ProgramError> {
+        if *system_program.key != system_program::ID {
+            return Err(MetalityGameContractError::InvalidSystemProgram.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_associated_token_program(
+        associated_token_program: &AccountInfo,
+    ) -> Result<(), ProgramError> {
+        if *associated_token_program.key != spl_associated_token_account::ID {
+            return Err(MetalityGameContractError::InvalidAssociatedTokenProgram.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_rent_sysvar(rent_sysvar: &AccountInfo) -> Result<(), ProgramError> {
+        if *rent_sysvar.key != sysvar::rent::ID {
+            return Err(MetalityGameContractError::InvalidRentSysvar.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_rent_exempt(account: &AccountInfo) -> Result<(), ProgramError> {
+        if !Rent::get()?.is_exempt(account.lamports(), account.data_len()) {
+            return Err(MetalityGameContractError::NotRentExempt.into());
+        }
+
+        Ok(())
+    }
+
+    /// Only a system owned account without data may be created, which keeps
+    /// initialized state, such as the program data game index, from being reset.
+    pub fn validate_uninitialized(account: &AccountInfo) -> Result<(), ProgramError> {
+        if *account.owner != system_program::ID || !account.data_is_empty() {
+            return Err(MetalityGameContractError::AlreadyInitialized.into());
+        }
+
         Ok(())
     }
 
@@ -53,17 +153,38 @@ impl Validator {
         Ok(())
     }
 
-    pub fn validate_admin(admin: &AccountInfo) -> Result<(), ProgramError> {
-        if !admin.is_signer || *admin.key != admin::id() {
+    pub fn validate_deployer(deployer: &AccountInfo) -> Result<(), ProgramError> {
+        if !deployer.is_signer || *deployer.key != admin::id() {
+            return Err(MetalityGameContractError::NotAdmin.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_admin(
+        admin: &AccountInfo,
+        game_program_data_account: &AccountInfo,
+        program_id: Pubkey,
+    ) -> Result<(), ProgramError> {
+        Self::validate_program_data_account(game_program_data_account, program_id)?;
+
+        let game_program_data_unpacked =
+            MetalityGameProgramDataState::unpack(&game_program_data_account.try_borrow_data()?)?;
+
+        if !admin.is_signer || *admin.key != game_program_data_unpacked.admin {
             return Err(MetalityGameContractError::NotAdmin.into());
         }
 
         Ok(())
     }
 
-    pub fn validate_equality(lt: Pubkey, rt: Pubkey) -> Result<(), ProgramError> {
+    pub fn validate_equality(
+        lt: Pubkey,
+        rt: Pubkey,
+        error: MetalityGameContractError,
+    ) -> Result<(), ProgramError> {
         if lt != rt {
-            return Err(MetalityGameContractError::EqualityMismatch.into());
+            return Err(error.into());
         }
 
         Ok(())
@@ -77,9 +198,13 @@ impl Validator {
         Ok(())
     }
 
-    pub fn validate_bool(lt: bool, rt: bool) -> Result<(), ProgramError> {
+    pub fn validate_bool(
+        lt: bool,
+        rt: bool,
+        error: MetalityGameContractError,
+    ) -> Result<(), ProgramError> {
         if lt != rt {
-            return Err(MetalityGameContractError::EqualityMismatch.into());
+            return Err(error.into());
         }
 
         Ok(())
@@ -96,6 +221,49 @@ impl Validator {
         Ok(())
     }
 
+    /// The opponent must be the other participant of the game, never the player.
+    pub fn validate_opponent(
+        state: MetalityGameContractState,
+        player: Pubkey,
+        opponent: Pubkey,
+    ) -> Result<(), ProgramError> {
+        let expected = if player == state.user_a {
+            state.user_b
+        } else if player == state.user_b {
+            state.user_a
+        } else {
+            return Err(MetalityGameContractError::InvalidWinner.into());
+        };
+
+        if opponent != expected || opponent == player {
+            return Err(MetalityGameContractError::InvalidWinner.into());
+        }
+
+        Ok(())
+    }
+
+    /// Anyone may claim a reward on the winner's behalf, unless the winner opted
+    /// into self-claim and must sign and pay for the claim themselves.
+    pub fn validate_reward_claimant(
+        self_claim: bool,
+        winner: &AccountInfo,
+        payer: &AccountInfo,
+    ) -> Result<(), ProgramError> {
+        if self_claim && (!winner.is_signer || winner.key != payer.key) {
+            return Err(MetalityGameContractError::WinnerMustClaim.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_rating_range(rating: u32, min: u32, max: u32) -> Result<(), ProgramError> {
+        if rating < min || rating > max {
+            return Err(MetalityGameContractError::RatingOutOfRange.into());
+        }
+
+        Ok(())
+    }
+
     pub fn validate_state_account(
         game_state_account: &AccountInfo,
         program_id: Pubkey,
@@ -106,4 +274,21 @@ impl Validator {
 
         Ok(())
     }
+
+    pub fn validate_program_data_account(
+        game_program_data_account: &AccountInfo,
+        program_id: Pubkey,
+    ) -> Result<(), ProgramError> {
+        let program_data_address =
+            Pubkey::create_with_seed(&admin::id(), PROGRAM_DATA_SEED, &program_id)
+                .map_err(|_| MetalityGameContractError::InvalidProgramDataAccount)?;
+
+        if *game_program_data_account.key != program_data_address
+            || *game_program_data_account.owner != program_id
+        {
+            return Err(MetalityGameContractError::InvalidProgramDataAccount.into());
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests {
+    use metality_game_contract::error::MetalityGameContractError;
+    use num_traits::FromPrimitive;
+    use solana_program::{decode_error::DecodeError, program_error::ProgramError};
+
+    #[test]
+    fn error_table_test() {
+        let table = MetalityGameContractError::table();
+
+        for (index, (code, _, _)) in table.iter().enumerate() {
+            assert_eq!(*code, index as u32);
+        }
+
+        assert_eq!(
+            table[0],
+            (
+                0,
+                "InvalidInstruction".to_string(),
+                "Invalid Instruction".to_string()
+            )
+        );
+
+        let (code, name, message) = &table[MetalityGameContractError::GameExpired as usize];
+
+        assert_eq!(*code, MetalityGameContractError::GameExpired as u32);
+        assert_eq!(name, "GameExpired");
+        assert_eq!(message, "Game expired");
+
+        assert!(MetalityGameContractError::from_u32(table.len() as u32).is_none());
+    }
+
+    #[test]
+    fn error_decode_test() {
+        let error: ProgramError = MetalityGameContractError::OpponentNotJoined.into();
+
+        let code = match error {
+            ProgramError::Custom(code) => code,
+            _ => panic!("expected a custom error"),
+        };
+
+        assert_eq!(
+            MetalityGameContractError::decode_custom_error_to_enum(code),
+            Some(MetalityGameContractError::OpponentNotJoined)
+        );
+
+        assert_eq!(
+            <MetalityGameContractError as DecodeError<MetalityGameContractError>>::type_of(),
+            "MetalityGameContractError"
+        );
+    }
+}
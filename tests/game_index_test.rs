@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod tests {
+    use metality_game_contract::error::MetalityGameContractError;
+    use metality_game_contract::state::{
+        MetalityGameProgramDataState, GAME_STATE_SEED_PREFIX, LOBBY_SEED_PREFIX, NFT_CHECK_STRICT,
+        QUEUE_SEED_PREFIX, TOURNAMENT_SEED_PREFIX,
+    };
+    use solana_program::pubkey::{Pubkey, MAX_SEED_LEN};
+
+    fn get_program_data(index: u64) -> MetalityGameProgramDataState {
+        MetalityGameProgramDataState {
+            is_initialized: true,
+            index,
+            oracle: Pubkey::default(),
+            join_bond_lamports: 0,
+            bond_forfeit_bps: 0,
+            nft_check_level: NFT_CHECK_STRICT,
+            admin: Pubkey::default(),
+        }
+    }
+
+    #[test]
+    fn indexed_seed_test() {
+        assert_eq!(
+            get_program_data(0)
+                .indexed_seed(GAME_STATE_SEED_PREFIX)
+                .unwrap(),
+            "Metality Game State 0"
+        );
+        assert_eq!(
+            get_program_data(42)
+                .indexed_seed(LOBBY_SEED_PREFIX)
+                .unwrap(),
+            "Metality Lobby 42"
+        );
+    }
+
+    #[test]
+    fn indexed_seed_length_limit_test() {
+        for prefix in [
+            GAME_STATE_SEED_PREFIX,
+            LOBBY_SEED_PREFIX,
+            TOURNAMENT_SEED_PREFIX,
+            QUEUE_SEED_PREFIX,
+        ] {
+            let max_digits = (MAX_SEED_LEN - prefix.len()) as u32;
+            let last_index = 10u64.pow(max_digits) - 1;
+
+            let seed = get_program_data(last_index).indexed_seed(prefix).unwrap();
+            assert_eq!(seed.len(), MAX_SEED_LEN);
+
+            assert_eq!(
+                get_program_data(last_index + 1).indexed_seed(prefix),
+                Err(MetalityGameContractError::SeedTooLong.into())
+            );
+            assert_eq!(
+                get_program_data(u64::MAX).indexed_seed(prefix),
+                Err(MetalityGameContractError::SeedTooLong.into())
+            );
+        }
+    }
+
+    #[test]
+    fn advance_index_test() {
+        let mut program_data = get_program_data(u64::MAX - 1);
+
+        program_data.advance_index().unwrap();
+        assert_eq!(program_data.index, u64::MAX);
+
+        assert_eq!(
+            program_data.advance_index(),
+            Err(MetalityGameContractError::IndexOverflow.into())
+        );
+        assert_eq!(program_data.index, u64::MAX);
+    }
+}
@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod tests {
     use metality_game_contract::instruction::MetalityGameContractInstruction;
+    use metality_game_contract::state::MAX_LOBBY_PLAYERS;
+    use solana_program::pubkey::Pubkey;
 
     #[test]
     fn instruction_data_unpack_test() {
@@ -21,7 +23,10 @@ mod tests {
 
         assert_eq!(
             unpacked_ins_data,
-            MetalityGameContractInstruction::InitializeGame
+            MetalityGameContractInstruction::InitializeGame {
+                min_opponent_rating: 0,
+                max_opponent_rating: u32::MAX,
+            }
         );
 
         packed_ins_data = [2u8];
@@ -71,4 +76,433 @@ mod tests {
             MetalityGameContractInstruction::SetGameStarted
         );
     }
+
+    #[test]
+    fn lobby_instruction_data_unpack_test() {
+        let mut packed_ins_data = vec![8u8, 4, 3, 1];
+        packed_ins_data.resize(2 + MAX_LOBBY_PLAYERS, 0);
+
+        let mut payout_table = [0u8; MAX_LOBBY_PLAYERS];
+        payout_table[0] = 3;
+        payout_table[1] = 1;
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&packed_ins_data).unwrap(),
+            MetalityGameContractInstruction::InitializeLobby {
+                max_players: 4,
+                payout_table,
+            }
+        );
+
+        assert!(MetalityGameContractInstruction::unpack_instruction_data(&[8u8, 4]).is_err());
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[9u8]).unwrap(),
+            MetalityGameContractInstruction::JoinLobby
+        );
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[10u8]).unwrap(),
+            MetalityGameContractInstruction::ExitLobby
+        );
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[11u8]).unwrap(),
+            MetalityGameContractInstruction::CancelLobby
+        );
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[12u8]).unwrap(),
+            MetalityGameContractInstruction::SetLobbyStarted
+        );
+
+        let mut ranking = [0u8; MAX_LOBBY_PLAYERS];
+        ranking[..3].copy_from_slice(&[2, 0, 1]);
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[13u8, 2, 0, 1]).unwrap(),
+            MetalityGameContractInstruction::SettleLobby {
+                ranking,
+                ranking_len: 3,
+            }
+        );
+
+        assert!(MetalityGameContractInstruction::unpack_instruction_data(&[13u8]).is_err());
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[40u8]).unwrap(),
+            MetalityGameContractInstruction::ReleaseLobbyStake
+        );
+    }
+
+    #[test]
+    fn tournament_instruction_data_unpack_test() {
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[14u8, 8]).unwrap(),
+            MetalityGameContractInstruction::InitializeTournament { capacity: 8 }
+        );
+
+        assert!(MetalityGameContractInstruction::unpack_instruction_data(&[14u8]).is_err());
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[15u8]).unwrap(),
+            MetalityGameContractInstruction::RegisterTournamentEntrant
+        );
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[16u8]).unwrap(),
+            MetalityGameContractInstruction::CancelTournament
+        );
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[17u8, 3]).unwrap(),
+            MetalityGameContractInstruction::CreateTournamentMatch { match_index: 3 }
+        );
+
+        assert!(MetalityGameContractInstruction::unpack_instruction_data(&[17u8]).is_err());
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[18u8]).unwrap(),
+            MetalityGameContractInstruction::SettleTournamentMatch
+        );
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[19u8]).unwrap(),
+            MetalityGameContractInstruction::PayoutTournament
+        );
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[41u8]).unwrap(),
+            MetalityGameContractInstruction::ReleaseTournamentStake
+        );
+    }
+
+    #[test]
+    fn rating_bounded_initialize_game_unpack_test() {
+        let mut packed_ins_data = vec![1u8];
+        packed_ins_data.extend_from_slice(&1100u32.to_le_bytes());
+        packed_ins_data.extend_from_slice(&1400u32.to_le_bytes());
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&packed_ins_data).unwrap(),
+            MetalityGameContractInstruction::InitializeGame {
+                min_opponent_rating: 1100,
+                max_opponent_rating: 1400,
+            }
+        );
+
+        assert!(
+            MetalityGameContractInstruction::unpack_instruction_data(&packed_ins_data[..6])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn queue_instruction_data_unpack_test() {
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[20u8]).unwrap(),
+            MetalityGameContractInstruction::InitializeQueue
+        );
+
+        let mut packed_ins_data = vec![21u8];
+        packed_ins_data.extend_from_slice(&1000u32.to_le_bytes());
+        packed_ins_data.extend_from_slice(&1500u32.to_le_bytes());
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&packed_ins_data).unwrap(),
+            MetalityGameContractInstruction::Enqueue {
+                min_opponent_rating: 1000,
+                max_opponent_rating: 1500,
+            }
+        );
+
+        assert!(MetalityGameContractInstruction::unpack_instruction_data(&[21u8, 1, 2]).is_err());
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[22u8]).unwrap(),
+            MetalityGameContractInstruction::Dequeue
+        );
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[23u8, 2, 5]).unwrap(),
+            MetalityGameContractInstruction::MatchQueued {
+                first_entry: 2,
+                second_entry: 5,
+            }
+        );
+
+        assert!(MetalityGameContractInstruction::unpack_instruction_data(&[23u8, 2]).is_err());
+    }
+
+    #[test]
+    fn side_pool_instruction_data_unpack_test() {
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[24u8]).unwrap(),
+            MetalityGameContractInstruction::InitializeSidePool
+        );
+
+        let mut packed_ins_data = vec![25u8, 2];
+        packed_ins_data.extend_from_slice(&5_000u64.to_le_bytes());
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&packed_ins_data).unwrap(),
+            MetalityGameContractInstruction::PlaceSideBet {
+                side: 2,
+                amount: 5_000,
+            }
+        );
+
+        assert!(MetalityGameContractInstruction::unpack_instruction_data(&[25u8, 1, 2]).is_err());
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[26u8]).unwrap(),
+            MetalityGameContractInstruction::ClaimSideBet
+        );
+    }
+
+    #[test]
+    fn oracle_instruction_data_unpack_test() {
+        let oracle = Pubkey::new_from_array([3; 32]);
+
+        let mut packed_ins_data = vec![27u8];
+        packed_ins_data.extend_from_slice(oracle.as_ref());
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&packed_ins_data).unwrap(),
+            MetalityGameContractInstruction::SetOracle { oracle }
+        );
+
+        assert!(
+            MetalityGameContractInstruction::unpack_instruction_data(&packed_ins_data[..20])
+                .is_err()
+        );
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[28u8]).unwrap(),
+            MetalityGameContractInstruction::SubmitSignedResult
+        );
+
+        assert!(MetalityGameContractInstruction::unpack_instruction_data(&[28u8, 1]).is_err());
+    }
+
+    #[test]
+    fn recorded_winner_instruction_data_unpack_test() {
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[29u8]).unwrap(),
+            MetalityGameContractInstruction::RecordWinner
+        );
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[30u8]).unwrap(),
+            MetalityGameContractInstruction::ClaimReward
+        );
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[31u8, 1]).unwrap(),
+            MetalityGameContractInstruction::SetClaimPreference { self_claim: true }
+        );
+
+        assert!(MetalityGameContractInstruction::unpack_instruction_data(&[31u8, 2]).is_err());
+    }
+
+    #[test]
+    fn mutual_cancel_instruction_data_unpack_test() {
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[32u8]).unwrap(),
+            MetalityGameContractInstruction::ProposeMutualCancel
+        );
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[33u8]).unwrap(),
+            MetalityGameContractInstruction::AcceptMutualCancel
+        );
+    }
+
+    #[test]
+    fn resign_instruction_data_unpack_test() {
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[34u8]).unwrap(),
+            MetalityGameContractInstruction::Resign
+        );
+    }
+
+    #[test]
+    fn join_bond_instruction_data_unpack_test() {
+        let mut packed_ins_data = vec![35u8];
+        packed_ins_data.extend_from_slice(&1_000_000u64.to_le_bytes());
+        packed_ins_data.extend_from_slice(&5_000u16.to_le_bytes());
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&packed_ins_data).unwrap(),
+            MetalityGameContractInstruction::SetJoinBond {
+                lamports: 1_000_000,
+                forfeit_bps: 5_000,
+            }
+        );
+
+        assert!(
+            MetalityGameContractInstruction::unpack_instruction_data(&packed_ins_data[..9])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn kick_opponent_instruction_data_unpack_test() {
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[36u8]).unwrap(),
+            MetalityGameContractInstruction::KickOpponent
+        );
+    }
+
+    #[test]
+    fn set_opponent_blocked_instruction_data_unpack_test() {
+        let opponent = Pubkey::new_from_array([4; 32]);
+
+        let mut packed_ins_data = vec![37u8];
+        packed_ins_data.extend_from_slice(opponent.as_ref());
+        packed_ins_data.push(1);
+
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&packed_ins_data).unwrap(),
+            MetalityGameContractInstruction::SetOpponentBlocked {
+                opponent,
+                blocked: true,
+            }
+        );
+
+        assert!(
+            MetalityGameContractInstruction::unpack_instruction_data(&packed_ins_data[..33])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn set_nft_check_level_instruction_data_unpack_test() {
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[38u8, 1]).unwrap(),
+            MetalityGameContractInstruction::SetNftCheckLevel { level: 1 }
+        );
+
+        assert!(MetalityGameContractInstruction::unpack_instruction_data(&[38u8]).is_err());
+    }
+
+    #[test]
+    fn migrate_account_instruction_data_unpack_test() {
+        assert_eq!(
+            MetalityGameContractInstruction::unpack_instruction_data(&[39u8]).unwrap(),
+            MetalityGameContractInstruction::MigrateAccount
+        );
+    }
+
+    fn well_formed_instruction_data() -> Vec<Vec<u8>> {
+        let payload_lens = [
+            (0u8, 0),
+            (1, 8),
+            (2, 0),
+            (3, 0),
+            (4, 0),
+            (5, 0),
+            (6, 0),
+            (7, 0),
+            (8, 1 + MAX_LOBBY_PLAYERS),
+            (9, 0),
+            (10, 0),
+            (11, 0),
+            (12, 0),
+            (13, MAX_LOBBY_PLAYERS),
+            (14, 1),
+            (15, 0),
+            (16, 0),
+            (17, 1),
+            (18, 0),
+            (19, 0),
+            (20, 0),
+            (21, 8),
+            (22, 0),
+            (23, 2),
+            (24, 0),
+            (25, 9),
+            (26, 0),
+            (27, 32),
+            (28, 0),
+            (29, 0),
+            (30, 0),
+            (31, 1),
+            (32, 0),
+            (33, 0),
+            (34, 0),
+            (35, 10),
+            (36, 0),
+            (37, 33),
+            (38, 1),
+            (39, 0),
+            (40, 0),
+            (41, 0),
+        ];
+
+        payload_lens
+            .iter()
+            .map(|&(ins_no, payload_len)| {
+                let mut packed_ins_data = vec![ins_no];
+                // Boolean payloads only accept 0 or 1, so fill with 1 throughout.
+                packed_ins_data.resize(1 + payload_len, 1);
+                packed_ins_data
+            })
+            .collect()
+    }
+
+    #[test]
+    fn well_formed_instruction_data_unpack_test() {
+        for packed_ins_data in well_formed_instruction_data() {
+            assert!(
+                MetalityGameContractInstruction::unpack_instruction_data(&packed_ins_data).is_ok(),
+                "tag {} rejected",
+                packed_ins_data[0]
+            );
+        }
+    }
+
+    #[test]
+    fn trailing_byte_instruction_data_unpack_test() {
+        for mut packed_ins_data in well_formed_instruction_data() {
+            packed_ins_data.push(0);
+
+            assert!(
+                MetalityGameContractInstruction::unpack_instruction_data(&packed_ins_data).is_err(),
+                "tag {} accepted a trailing byte",
+                packed_ins_data[0]
+            );
+        }
+
+        // The rating-less form of InitializeGame is exactly the bare tag.
+        assert!(MetalityGameContractInstruction::unpack_instruction_data(&[1u8, 0]).is_err());
+    }
+
+    #[test]
+    fn missing_byte_instruction_data_unpack_test() {
+        for mut packed_ins_data in well_formed_instruction_data() {
+            if packed_ins_data.len() == 1 {
+                continue;
+            }
+
+            packed_ins_data.pop();
+
+            // SettleLobby takes any ranking up to MAX_LOBBY_PLAYERS entries.
+            if packed_ins_data[0] == 13 {
+                packed_ins_data.truncate(1);
+            }
+
+            assert!(
+                MetalityGameContractInstruction::unpack_instruction_data(&packed_ins_data).is_err(),
+                "tag {} accepted a truncated payload",
+                packed_ins_data[0]
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_tag_instruction_data_unpack_test() {
+        assert!(MetalityGameContractInstruction::unpack_instruction_data(&[]).is_err());
+        assert!(MetalityGameContractInstruction::unpack_instruction_data(&[42u8]).is_err());
+    }
 }
@@ -0,0 +1,1267 @@
+#![cfg(feature = "test-bpf")]
+
+mod tests {
+    use metality_game_contract::error::MetalityGameContractError;
+    use metality_game_contract::escrow::EscrowAuthority;
+    use metality_game_contract::oracle::result_message;
+    use metality_game_contract::processor::Processor;
+    use metality_game_contract::profile::find_profile_address;
+    use metality_game_contract::side_pool::{find_side_bet_address, find_side_pool_address};
+    use metality_game_contract::state::{
+        MetalityGameContractState, MetalityGameProgramDataState, MetalitySidePoolState,
+        GAME_OUTCOME_CANCELLED, GAME_OUTCOME_DRAW, GAME_OUTCOME_WON, GAME_STATE_SEED_PREFIX,
+        NFT_CHECK_STRICT, PROGRAM_DATA_SEED, SIDE_USER_A, SIDE_USER_B,
+    };
+    use metality_game_contract::validations::admin;
+    use solana_program::program_pack::Pack;
+    use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+    use solana_sdk::{
+        account::Account,
+        ed25519_program,
+        instruction::{AccountMeta, Instruction, InstructionError},
+        pubkey::Pubkey,
+        rent::Rent,
+        signature::{Keypair, Signer},
+        system_instruction, system_program, sysvar,
+        transaction::{Transaction, TransactionError},
+    };
+    use spl_associated_token_account::{
+        get_associated_token_address, instruction::create_associated_token_account,
+    };
+    use spl_token::state::{Account as TokenAccount, Mint};
+
+    struct TestEnv {
+        context: ProgramTestContext,
+        program_id: Pubkey,
+        admin: Keypair,
+        program_data: Pubkey,
+    }
+
+    struct Player {
+        keypair: Keypair,
+        nft_mint: Pubkey,
+        nft_ata: Pubkey,
+    }
+
+    impl Player {
+        fn pubkey(&self) -> Pubkey {
+            self.keypair.pubkey()
+        }
+    }
+
+    struct Game {
+        state: Pubkey,
+        pda: Pubkey,
+    }
+
+    impl TestEnv {
+        async fn new() -> Self {
+            let program_id = Pubkey::new_unique();
+
+            let mut program_test = ProgramTest::new(
+                "metality_game_contract",
+                program_id,
+                processor!(Processor::unpack_and_process_instruction),
+            );
+
+            // The program data account is created by the deployer key, so it
+            // is preloaded here with an admin this suite holds the keypair of.
+            let admin = Keypair::new();
+            let program_data =
+                Pubkey::create_with_seed(&admin::id(), PROGRAM_DATA_SEED, &program_id).unwrap();
+
+            let mut data = vec![0; MetalityGameProgramDataState::LEN];
+            MetalityGameProgramDataState::pack(
+                MetalityGameProgramDataState {
+                    is_initialized: true,
+                    index: 0,
+                    oracle: Pubkey::default(),
+                    join_bond_lamports: 0,
+                    bond_forfeit_bps: 0,
+                    nft_check_level: NFT_CHECK_STRICT,
+                    admin: admin.pubkey(),
+                },
+                &mut data,
+            )
+            .unwrap();
+
+            program_test.add_account(
+                program_data,
+                Account {
+                    lamports: Rent::default().minimum_balance(data.len()),
+                    data,
+                    owner: program_id,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            );
+
+            let context = program_test.start_with_context().await;
+
+            let mut env = TestEnv {
+                context,
+                program_id,
+                admin,
+                program_data,
+            };
+
+            env.fund(&env.admin.pubkey()).await;
+
+            env
+        }
+
+        async fn process(
+            &mut self,
+            instructions: &[Instruction],
+            signers: &[&Keypair],
+        ) -> Result<(), BanksClientError> {
+            let payer = self.context.payer.insecure_clone();
+            let mut all_signers = vec![&payer];
+            all_signers.extend_from_slice(signers);
+
+            let recent_blockhash = self
+                .context
+                .banks_client
+                .get_latest_blockhash()
+                .await
+                .unwrap();
+
+            let transaction = Transaction::new_signed_with_payer(
+                instructions,
+                Some(&payer.pubkey()),
+                &all_signers,
+                recent_blockhash,
+            );
+
+            self.context
+                .banks_client
+                .process_transaction(transaction)
+                .await
+        }
+
+        async fn fund(&mut self, account: &Pubkey) {
+            let transfer_ix =
+                system_instruction::transfer(&self.context.payer.pubkey(), account, 10_000_000_000);
+
+            self.process(&[transfer_ix], &[]).await.unwrap();
+        }
+
+        async fn create_player(&mut self) -> Player {
+            self.mint_nft(Keypair::new()).await
+        }
+
+        /// Funds `keypair` and mints it an NFT: a zero decimals mint with one
+        /// token in its associated account and no mint authority left.
+        async fn mint_nft(&mut self, keypair: Keypair) -> Player {
+            self.fund(&keypair.pubkey()).await;
+
+            let mint = Keypair::new();
+            let nft_ata = get_associated_token_address(&keypair.pubkey(), &mint.pubkey());
+            let payer = self.context.payer.pubkey();
+
+            let instructions = [
+                system_instruction::create_account(
+                    &payer,
+                    &mint.pubkey(),
+                    Rent::default().minimum_balance(Mint::LEN),
+                    Mint::LEN as u64,
+                    &spl_token::id(),
+                ),
+                spl_token::instruction::initialize_mint(
+                    &spl_token::id(),
+                    &mint.pubkey(),
+                    &payer,
+                    None,
+                    0,
+                )
+                .unwrap(),
+                create_associated_token_account(&payer, &keypair.pubkey(), &mint.pubkey()),
+                spl_token::instruction::mint_to(
+                    &spl_token::id(),
+                    &mint.pubkey(),
+                    &nft_ata,
+                    &payer,
+                    &[],
+                    1,
+                )
+                .unwrap(),
+                spl_token::instruction::set_authority(
+                    &spl_token::id(),
+                    &mint.pubkey(),
+                    None,
+                    spl_token::instruction::AuthorityType::MintTokens,
+                    &payer,
+                    &[],
+                )
+                .unwrap(),
+            ];
+
+            self.process(&instructions, &[&mint]).await.unwrap();
+
+            Player {
+                keypair,
+                nft_mint: mint.pubkey(),
+                nft_ata,
+            }
+        }
+
+        async fn token_account(&mut self, address: &Pubkey) -> TokenAccount {
+            let account = self
+                .context
+                .banks_client
+                .get_account(*address)
+                .await
+                .unwrap()
+                .unwrap();
+
+            TokenAccount::unpack(&account.data).unwrap()
+        }
+
+        async fn game_state(&mut self, game: &Game) -> MetalityGameContractState {
+            let account = self
+                .context
+                .banks_client
+                .get_account(game.state)
+                .await
+                .unwrap()
+                .unwrap();
+
+            MetalityGameContractState::unpack(&account.data).unwrap()
+        }
+
+        async fn program_data_state(&mut self) -> MetalityGameProgramDataState {
+            let account = self
+                .context
+                .banks_client
+                .get_account(self.program_data)
+                .await
+                .unwrap()
+                .unwrap();
+
+            MetalityGameProgramDataState::unpack(&account.data).unwrap()
+        }
+
+        async fn side_pool_state(&mut self, side_pool: &Pubkey) -> MetalitySidePoolState {
+            let account = self
+                .context
+                .banks_client
+                .get_account(*side_pool)
+                .await
+                .unwrap()
+                .unwrap();
+
+            MetalitySidePoolState::unpack(&account.data).unwrap()
+        }
+
+        fn profile(&self, player: &Player) -> Pubkey {
+            find_profile_address(&player.pubkey(), &self.program_id).0
+        }
+
+        async fn create_game(&mut self, user_a: &Player) -> Game {
+            let seed = self
+                .program_data_state()
+                .await
+                .indexed_seed(GAME_STATE_SEED_PREFIX)
+                .unwrap();
+
+            let state =
+                Pubkey::create_with_seed(&user_a.pubkey(), &seed, &self.program_id).unwrap();
+            let (pda, _bump_seeds) = Pubkey::find_program_address(
+                &["metality_game_contract".as_bytes(), state.as_ref()],
+                &self.program_id,
+            );
+
+            let create_ix = Instruction::new_with_bytes(
+                self.program_id,
+                &[1],
+                vec![
+                    AccountMeta::new(user_a.pubkey(), true),
+                    AccountMeta::new(user_a.nft_ata, false),
+                    AccountMeta::new_readonly(user_a.nft_mint, false),
+                    AccountMeta::new_readonly(pda, false),
+                    AccountMeta::new(state, false),
+                    AccountMeta::new(self.program_data, false),
+                    AccountMeta::new_readonly(spl_token::id(), false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            );
+
+            self.process(&[create_ix], &[&user_a.keypair])
+                .await
+                .unwrap();
+
+            Game { state, pda }
+        }
+
+        fn join_game_ix(&self, game: &Game, user_b: &Player) -> Instruction {
+            Instruction::new_with_bytes(
+                self.program_id,
+                &[3],
+                vec![
+                    AccountMeta::new(user_b.pubkey(), true),
+                    AccountMeta::new(user_b.nft_ata, false),
+                    AccountMeta::new_readonly(user_b.nft_mint, false),
+                    AccountMeta::new_readonly(game.pda, false),
+                    AccountMeta::new(game.state, false),
+                    AccountMeta::new_readonly(spl_token::id(), false),
+                    AccountMeta::new_readonly(self.program_data, false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            )
+        }
+
+        fn exit_game_ix(&self, game: &Game, user_b: &Player) -> Instruction {
+            Instruction::new_with_bytes(
+                self.program_id,
+                &[7],
+                vec![
+                    AccountMeta::new(user_b.pubkey(), true),
+                    AccountMeta::new(user_b.nft_ata, false),
+                    AccountMeta::new_readonly(user_b.nft_mint, false),
+                    AccountMeta::new_readonly(game.pda, false),
+                    AccountMeta::new(game.state, false),
+                    AccountMeta::new_readonly(spl_token::id(), false),
+                ],
+            )
+        }
+
+        fn cancel_game_ix(
+            &self,
+            game: &Game,
+            signer: &Pubkey,
+            user_a: &Player,
+            user_b: &Player,
+        ) -> Instruction {
+            Instruction::new_with_bytes(
+                self.program_id,
+                &[2],
+                vec![
+                    AccountMeta::new(*signer, true),
+                    AccountMeta::new(user_a.nft_ata, false),
+                    AccountMeta::new_readonly(user_a.nft_mint, false),
+                    AccountMeta::new(user_b.pubkey(), false),
+                    AccountMeta::new(user_b.nft_ata, false),
+                    AccountMeta::new_readonly(user_b.nft_mint, false),
+                    AccountMeta::new_readonly(game.pda, false),
+                    AccountMeta::new(game.state, false),
+                    AccountMeta::new_readonly(spl_token::id(), false),
+                    AccountMeta::new(self.profile(user_a), false),
+                    AccountMeta::new(self.profile(user_b), false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            )
+        }
+
+        fn set_game_started_ix(
+            &self,
+            game: &Game,
+            signer: &Pubkey,
+            user_a: &Player,
+            user_b: &Player,
+        ) -> Instruction {
+            Instruction::new_with_bytes(
+                self.program_id,
+                &[6],
+                vec![
+                    AccountMeta::new_readonly(*signer, true),
+                    AccountMeta::new_readonly(self.program_data, false),
+                    AccountMeta::new_readonly(user_a.pubkey(), false),
+                    AccountMeta::new_readonly(user_b.pubkey(), false),
+                    AccountMeta::new_readonly(user_a.nft_ata, false),
+                    AccountMeta::new_readonly(user_a.nft_mint, false),
+                    AccountMeta::new_readonly(user_b.nft_ata, false),
+                    AccountMeta::new_readonly(user_b.nft_mint, false),
+                    AccountMeta::new_readonly(game.pda, false),
+                    AccountMeta::new(game.state, false),
+                ],
+            )
+        }
+
+        fn draw_or_cancel_game_ix(
+            &self,
+            game: &Game,
+            user_a: &Player,
+            user_b: &Player,
+        ) -> Instruction {
+            Instruction::new_with_bytes(
+                self.program_id,
+                &[5],
+                vec![
+                    AccountMeta::new(self.admin.pubkey(), true),
+                    AccountMeta::new_readonly(self.program_data, false),
+                    AccountMeta::new(user_a.pubkey(), false),
+                    AccountMeta::new(user_b.pubkey(), false),
+                    AccountMeta::new(user_a.nft_ata, false),
+                    AccountMeta::new_readonly(user_a.nft_mint, false),
+                    AccountMeta::new(user_b.nft_ata, false),
+                    AccountMeta::new_readonly(user_b.nft_mint, false),
+                    AccountMeta::new_readonly(game.pda, false),
+                    AccountMeta::new(game.state, false),
+                    AccountMeta::new_readonly(spl_token::id(), false),
+                    AccountMeta::new(self.profile(user_a), false),
+                    AccountMeta::new(self.profile(user_b), false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            )
+        }
+
+        fn transfer_reward_ix(&self, game: &Game, winner: &Player) -> Instruction {
+            Instruction::new_with_bytes(
+                self.program_id,
+                &[4],
+                vec![
+                    AccountMeta::new_readonly(self.admin.pubkey(), true),
+                    AccountMeta::new_readonly(self.program_data, false),
+                    AccountMeta::new_readonly(winner.pubkey(), false),
+                    AccountMeta::new(game.state, false),
+                ],
+            )
+        }
+
+        fn side_pool(&self, game: &Game, user_b: &Player) -> Pubkey {
+            find_side_pool_address(&game.state, &user_b.pubkey(), &self.program_id).0
+        }
+
+        /// Opens the side pool of `game`, betting in the token of `mint`.
+        fn initialize_side_pool_ix(
+            &self,
+            game: &Game,
+            user_b: &Player,
+            payer: &Player,
+            mint: &Pubkey,
+        ) -> Instruction {
+            let side_pool = self.side_pool(game, user_b);
+            let (pool_pda, _) = EscrowAuthority::find_pda(&side_pool, &self.program_id);
+
+            Instruction::new_with_bytes(
+                self.program_id,
+                &[24],
+                vec![
+                    AccountMeta::new(payer.pubkey(), true),
+                    AccountMeta::new(game.state, false),
+                    AccountMeta::new(side_pool, false),
+                    AccountMeta::new_readonly(pool_pda, false),
+                    AccountMeta::new(get_associated_token_address(&pool_pda, mint), false),
+                    AccountMeta::new_readonly(*mint, false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                    AccountMeta::new_readonly(spl_token::id(), false),
+                    AccountMeta::new_readonly(sysvar::rent::id(), false),
+                    AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                ],
+            )
+        }
+
+        /// Bets `amount` of the bettor's own token, which the pool must use.
+        fn place_side_bet_ix(
+            &self,
+            game: &Game,
+            user_b: &Player,
+            bettor: &Player,
+            side: u8,
+            amount: u64,
+        ) -> Instruction {
+            let side_pool = self.side_pool(game, user_b);
+            let (pool_pda, _) = EscrowAuthority::find_pda(&side_pool, &self.program_id);
+            let (side_bet, _) =
+                find_side_bet_address(&side_pool, &bettor.pubkey(), &self.program_id);
+
+            let mut data = vec![25, side];
+            data.extend_from_slice(&amount.to_le_bytes());
+
+            Instruction::new_with_bytes(
+                self.program_id,
+                &data,
+                vec![
+                    AccountMeta::new(bettor.pubkey(), true),
+                    AccountMeta::new(bettor.nft_ata, false),
+                    AccountMeta::new(side_pool, false),
+                    AccountMeta::new_readonly(game.state, false),
+                    AccountMeta::new(
+                        get_associated_token_address(&pool_pda, &bettor.nft_mint),
+                        false,
+                    ),
+                    AccountMeta::new(side_bet, false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                    AccountMeta::new_readonly(spl_token::id(), false),
+                ],
+            )
+        }
+
+        fn set_oracle_ix(&self, oracle: &Pubkey) -> Instruction {
+            let mut data = vec![27];
+            data.extend_from_slice(oracle.as_ref());
+
+            Instruction::new_with_bytes(
+                self.program_id,
+                &data,
+                vec![
+                    AccountMeta::new_readonly(self.admin.pubkey(), true),
+                    AccountMeta::new(self.program_data, false),
+                ],
+            )
+        }
+
+        /// The ed25519 verification of `oracle`'s attestation followed by the
+        /// SubmitSignedResult it backs.
+        fn submit_signed_result_ixs(
+            &self,
+            game: &Game,
+            oracle: &Keypair,
+            payer: &Pubkey,
+            winner: &Player,
+        ) -> [Instruction; 2] {
+            let message = result_message(&game.state, &winner.pubkey());
+            let signature = oracle.sign_message(&message);
+
+            let public_key_offset: u16 = 16;
+            let signature_offset = public_key_offset + 32;
+            let message_data_offset = signature_offset + 64;
+
+            let mut data = vec![1u8, 0];
+            for offset in [
+                signature_offset,
+                u16::MAX,
+                public_key_offset,
+                u16::MAX,
+                message_data_offset,
+                message.len() as u16,
+                u16::MAX,
+            ] {
+                data.extend_from_slice(&offset.to_le_bytes());
+            }
+            data.extend_from_slice(oracle.pubkey().as_ref());
+            data.extend_from_slice(signature.as_ref());
+            data.extend_from_slice(&message);
+
+            [
+                Instruction::new_with_bytes(ed25519_program::id(), &data, vec![]),
+                Instruction::new_with_bytes(
+                    self.program_id,
+                    &[28],
+                    vec![
+                        AccountMeta::new_readonly(*payer, true),
+                        AccountMeta::new_readonly(self.program_data, false),
+                        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+                        AccountMeta::new_readonly(winner.pubkey(), false),
+                        AccountMeta::new(game.state, false),
+                    ],
+                ),
+            ]
+        }
+
+        fn claim_reward_ix(
+            &self,
+            game: &Game,
+            payer: &Pubkey,
+            winner: &Player,
+            loser: &Player,
+        ) -> Instruction {
+            Instruction::new_with_bytes(
+                self.program_id,
+                &[30],
+                vec![
+                    AccountMeta::new(*payer, true),
+                    AccountMeta::new(winner.pubkey(), *payer == winner.pubkey()),
+                    AccountMeta::new(loser.pubkey(), false),
+                    AccountMeta::new(loser.nft_ata, false),
+                    AccountMeta::new_readonly(loser.nft_mint, false),
+                    AccountMeta::new(winner.nft_ata, false),
+                    AccountMeta::new_readonly(winner.nft_mint, false),
+                    AccountMeta::new(
+                        get_associated_token_address(&winner.pubkey(), &loser.nft_mint),
+                        false,
+                    ),
+                    AccountMeta::new_readonly(game.pda, false),
+                    AccountMeta::new(game.state, false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                    AccountMeta::new_readonly(spl_token::id(), false),
+                    AccountMeta::new_readonly(sysvar::rent::id(), false),
+                    AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                    AccountMeta::new(self.profile(winner), false),
+                    AccountMeta::new(self.profile(loser), false),
+                ],
+            )
+        }
+
+        fn set_claim_preference_ix(&self, player: &Player, self_claim: bool) -> Instruction {
+            Instruction::new_with_bytes(
+                self.program_id,
+                &[31, self_claim as u8],
+                vec![
+                    AccountMeta::new(player.pubkey(), true),
+                    AccountMeta::new(self.profile(player), false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            )
+        }
+
+        fn propose_mutual_cancel_ix(&self, game: &Game, player: &Player) -> Instruction {
+            Instruction::new_with_bytes(
+                self.program_id,
+                &[32],
+                vec![
+                    AccountMeta::new_readonly(player.pubkey(), true),
+                    AccountMeta::new(game.state, false),
+                ],
+            )
+        }
+
+        fn accept_mutual_cancel_ix(
+            &self,
+            game: &Game,
+            player: &Player,
+            user_a: &Player,
+            user_b: &Player,
+        ) -> Instruction {
+            Instruction::new_with_bytes(
+                self.program_id,
+                &[33],
+                vec![
+                    AccountMeta::new(player.pubkey(), true),
+                    AccountMeta::new(user_a.pubkey(), false),
+                    AccountMeta::new(user_b.pubkey(), false),
+                    AccountMeta::new(user_a.nft_ata, false),
+                    AccountMeta::new(user_b.nft_ata, false),
+                    AccountMeta::new_readonly(game.pda, false),
+                    AccountMeta::new(game.state, false),
+                    AccountMeta::new_readonly(spl_token::id(), false),
+                    AccountMeta::new(self.profile(user_a), false),
+                    AccountMeta::new(self.profile(user_b), false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            )
+        }
+
+        /// Creates a game for a fresh user A and has a fresh user B join it.
+        async fn joined_game(&mut self) -> (Game, Player, Player) {
+            let user_a = self.create_player().await;
+            let user_b = self.create_player().await;
+
+            let game = self.create_game(&user_a).await;
+
+            let join_ix = self.join_game_ix(&game, &user_b);
+            self.process(&[join_ix], &[&user_b.keypair]).await.unwrap();
+
+            (game, user_a, user_b)
+        }
+
+        /// Like `joined_game`, with the admin marking the game as started.
+        async fn started_game(&mut self) -> (Game, Player, Player) {
+            let (game, user_a, user_b) = self.joined_game().await;
+
+            let admin = self.admin.insecure_clone();
+            let start_ix = self.set_game_started_ix(&game, &admin.pubkey(), &user_a, &user_b);
+            self.process(&[start_ix], &[&admin]).await.unwrap();
+
+            (game, user_a, user_b)
+        }
+    }
+
+    fn assert_error(result: Result<(), BanksClientError>, error: MetalityGameContractError) {
+        assert_instruction_error(result, 0, error);
+    }
+
+    fn assert_instruction_error(
+        result: Result<(), BanksClientError>,
+        index: u8,
+        error: MetalityGameContractError,
+    ) {
+        assert_eq!(
+            result.unwrap_err().unwrap(),
+            TransactionError::InstructionError(index, InstructionError::Custom(error as u32))
+        );
+    }
+
+    #[tokio::test]
+    async fn create_game_test() {
+        let mut env = TestEnv::new().await;
+        let user_a = env.create_player().await;
+
+        let game = env.create_game(&user_a).await;
+
+        assert_eq!(env.token_account(&user_a.nft_ata).await.owner, game.pda);
+        assert_eq!(env.program_data_state().await.index, 1);
+
+        let game_state = env.game_state(&game).await;
+        assert!(game_state.is_initialized);
+        assert_eq!(game_state.user_a, user_a.pubkey());
+        assert_eq!(game_state.a_nft_ata, user_a.nft_ata);
+        assert_eq!(game_state.a_nft_mint, user_a.nft_mint);
+        assert_eq!(game_state.pda_account, game.pda);
+        assert!(!game_state.user_b_joined);
+    }
+
+    #[tokio::test]
+    async fn join_game_test() {
+        let mut env = TestEnv::new().await;
+        let (game, _user_a, user_b) = env.joined_game().await;
+
+        assert_eq!(env.token_account(&user_b.nft_ata).await.owner, game.pda);
+
+        let game_state = env.game_state(&game).await;
+        assert!(game_state.user_b_joined);
+        assert_eq!(game_state.user_b, user_b.pubkey());
+        assert_eq!(game_state.b_nft_ata, user_b.nft_ata);
+        assert_eq!(game_state.b_nft_mint, user_b.nft_mint);
+    }
+
+    #[tokio::test]
+    async fn join_own_game_test() {
+        let mut env = TestEnv::new().await;
+        let user_a = env.create_player().await;
+        let game = env.create_game(&user_a).await;
+
+        let user_a_second_nft = env.mint_nft(user_a.keypair.insecure_clone()).await;
+
+        let join_ix = env.join_game_ix(&game, &user_a_second_nft);
+        let result = env.process(&[join_ix], &[&user_a.keypair]).await;
+
+        assert_error(result, MetalityGameContractError::SameUserJoining);
+    }
+
+    #[tokio::test]
+    async fn join_cancelled_game_test() {
+        let mut env = TestEnv::new().await;
+        let user_a = env.create_player().await;
+        let user_b = env.create_player().await;
+        let game = env.create_game(&user_a).await;
+
+        let cancel_ix = env.cancel_game_ix(&game, &user_a.pubkey(), &user_a, &user_b);
+        env.process(&[cancel_ix], &[&user_a.keypair]).await.unwrap();
+
+        let join_ix = env.join_game_ix(&game, &user_b);
+        let result = env.process(&[join_ix], &[&user_b.keypair]).await;
+
+        assert_error(result, MetalityGameContractError::GameExpired);
+        assert_eq!(
+            env.token_account(&user_b.nft_ata).await.owner,
+            user_b.pubkey()
+        );
+    }
+
+    #[tokio::test]
+    async fn join_started_game_test() {
+        let mut env = TestEnv::new().await;
+        let user_a = env.create_player().await;
+        let user_b = env.create_player().await;
+        let game = env.create_game(&user_a).await;
+
+        // No instruction starts a game nobody joined, so the state is edited
+        // directly to cover games created already started.
+        let mut account = env
+            .context
+            .banks_client
+            .get_account(game.state)
+            .await
+            .unwrap()
+            .unwrap();
+        let mut game_state = MetalityGameContractState::unpack(&account.data).unwrap();
+        game_state.game_started = true;
+        MetalityGameContractState::pack(game_state, &mut account.data).unwrap();
+        env.context.set_account(&game.state, &account.into());
+
+        let join_ix = env.join_game_ix(&game, &user_b);
+        let result = env.process(&[join_ix], &[&user_b.keypair]).await;
+
+        assert_error(result, MetalityGameContractError::GameAlreadyStarted);
+        assert_eq!(
+            env.token_account(&user_b.nft_ata).await.owner,
+            user_b.pubkey()
+        );
+    }
+
+    #[tokio::test]
+    async fn user_b_exit_game_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, user_b) = env.joined_game().await;
+
+        let exit_ix = env.exit_game_ix(&game, &user_b);
+        env.process(&[exit_ix], &[&user_b.keypair]).await.unwrap();
+
+        assert_eq!(
+            env.token_account(&user_b.nft_ata).await.owner,
+            user_b.pubkey()
+        );
+        assert_eq!(env.token_account(&user_a.nft_ata).await.owner, game.pda);
+
+        let game_state = env.game_state(&game).await;
+        assert!(!game_state.user_b_joined);
+        assert!(!game_state.expired);
+        assert_eq!(game_state.exit_count, 1);
+        assert_eq!(game_state.previous_opponents[0], user_b.pubkey());
+    }
+
+    #[tokio::test]
+    async fn user_b_exit_started_game_test() {
+        let mut env = TestEnv::new().await;
+        let (game, _user_a, user_b) = env.started_game().await;
+
+        let exit_ix = env.exit_game_ix(&game, &user_b);
+        let result = env.process(&[exit_ix], &[&user_b.keypair]).await;
+
+        assert_error(result, MetalityGameContractError::GameAlreadyStarted);
+        assert_eq!(env.token_account(&user_b.nft_ata).await.owner, game.pda);
+    }
+
+    #[tokio::test]
+    async fn cancel_game_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, user_b) = env.joined_game().await;
+
+        let cancel_ix = env.cancel_game_ix(&game, &user_a.pubkey(), &user_a, &user_b);
+        env.process(&[cancel_ix], &[&user_a.keypair]).await.unwrap();
+
+        assert_eq!(
+            env.token_account(&user_a.nft_ata).await.owner,
+            user_a.pubkey()
+        );
+        assert_eq!(
+            env.token_account(&user_b.nft_ata).await.owner,
+            user_b.pubkey()
+        );
+
+        let game_state = env.game_state(&game).await;
+        assert!(game_state.expired);
+        assert_eq!(game_state.outcome, GAME_OUTCOME_CANCELLED);
+    }
+
+    #[tokio::test]
+    async fn cancel_game_by_opponent_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, user_b) = env.joined_game().await;
+
+        let cancel_ix = env.cancel_game_ix(&game, &user_b.pubkey(), &user_a, &user_b);
+        let result = env.process(&[cancel_ix], &[&user_b.keypair]).await;
+
+        assert_error(result, MetalityGameContractError::UserAMismatch);
+        assert_eq!(env.token_account(&user_a.nft_ata).await.owner, game.pda);
+        assert!(!env.game_state(&game).await.expired);
+    }
+
+    #[tokio::test]
+    async fn set_game_started_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, user_b) = env.started_game().await;
+
+        assert!(env.game_state(&game).await.game_started);
+        assert_eq!(env.token_account(&user_a.nft_ata).await.owner, game.pda);
+        assert_eq!(env.token_account(&user_b.nft_ata).await.owner, game.pda);
+    }
+
+    #[tokio::test]
+    async fn set_game_started_by_player_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, user_b) = env.joined_game().await;
+
+        let start_ix = env.set_game_started_ix(&game, &user_a.pubkey(), &user_a, &user_b);
+        let result = env.process(&[start_ix], &[&user_a.keypair]).await;
+
+        assert_error(result, MetalityGameContractError::NotAdmin);
+        assert!(!env.game_state(&game).await.game_started);
+    }
+
+    #[tokio::test]
+    async fn transfer_reward_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, user_b) = env.started_game().await;
+
+        let admin = env.admin.insecure_clone();
+        let reward_ix = env.transfer_reward_ix(&game, &user_a);
+        env.process(&[reward_ix], &[&admin]).await.unwrap();
+
+        let game_state = env.game_state(&game).await;
+        assert!(!game_state.expired);
+        assert_eq!(game_state.outcome, GAME_OUTCOME_WON);
+        assert_eq!(game_state.winner, user_a.pubkey());
+        assert_eq!(env.token_account(&user_b.nft_ata).await.owner, game.pda);
+
+        let claim_ix = env.claim_reward_ix(&game, &user_a.pubkey(), &user_a, &user_b);
+        env.process(&[claim_ix], &[&user_a.keypair]).await.unwrap();
+
+        let won_nft_ata = get_associated_token_address(&user_a.pubkey(), &user_b.nft_mint);
+        let won_nft = env.token_account(&won_nft_ata).await;
+        assert_eq!(won_nft.owner, user_a.pubkey());
+        assert_eq!(won_nft.amount, 1);
+
+        let owned_nft = env.token_account(&user_a.nft_ata).await;
+        assert_eq!(owned_nft.owner, user_a.pubkey());
+        assert_eq!(owned_nft.amount, 1);
+
+        let lost_nft = env.token_account(&user_b.nft_ata).await;
+        assert_eq!(lost_nft.owner, user_b.pubkey());
+        assert_eq!(lost_nft.amount, 0);
+
+        let game_state = env.game_state(&game).await;
+        assert!(game_state.expired);
+        assert_eq!(game_state.outcome, GAME_OUTCOME_WON);
+        assert_eq!(game_state.winner, user_a.pubkey());
+    }
+
+    #[tokio::test]
+    async fn transfer_reward_before_start_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, _user_b) = env.joined_game().await;
+
+        let admin = env.admin.insecure_clone();
+        let reward_ix = env.transfer_reward_ix(&game, &user_a);
+        let result = env.process(&[reward_ix], &[&admin]).await;
+
+        assert_error(result, MetalityGameContractError::GameNotStarted);
+        assert!(!env.game_state(&game).await.has_recorded_winner());
+    }
+
+    #[tokio::test]
+    async fn legacy_transfer_reward_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, user_b) = env.started_game().await;
+
+        // The payout accounts TransferReward took before settlement was split
+        // into recording and claiming.
+        let admin = env.admin.insecure_clone();
+        let mut accounts = env
+            .claim_reward_ix(&game, &admin.pubkey(), &user_a, &user_b)
+            .accounts;
+        accounts.truncate(14);
+        let reward_ix = Instruction::new_with_bytes(env.program_id, &[4], accounts);
+        let result = env.process(&[reward_ix], &[&admin]).await;
+
+        assert_error(result, MetalityGameContractError::LegacyTransferReward);
+        assert_eq!(env.token_account(&user_b.nft_ata).await.owner, game.pda);
+        assert!(!env.game_state(&game).await.has_recorded_winner());
+    }
+
+    #[tokio::test]
+    async fn side_bet_on_started_game_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, user_b) = env.started_game().await;
+        let bettor = env.create_player().await;
+
+        let pool_ix = env.initialize_side_pool_ix(&game, &user_b, &bettor, &bettor.nft_mint);
+        env.process(&[pool_ix], &[&bettor.keypair]).await.unwrap();
+
+        let bet_ix = env.place_side_bet_ix(&game, &user_b, &bettor, SIDE_USER_A, 1);
+        env.process(&[bet_ix], &[&bettor.keypair]).await.unwrap();
+
+        let side_pool = env.side_pool(&game, &user_b);
+        assert_eq!(env.game_state(&game).await.side_pool, side_pool);
+        assert_eq!(env.side_pool_state(&side_pool).await.total_a, 1);
+
+        let admin = env.admin.insecure_clone();
+        let reward_ix = env.transfer_reward_ix(&game, &user_a);
+        env.process(&[reward_ix], &[&admin]).await.unwrap();
+
+        let bet_ix = env.place_side_bet_ix(&game, &user_b, &bettor, SIDE_USER_A, 1);
+        let result = env.process(&[bet_ix], &[&bettor.keypair]).await;
+
+        assert_error(result, MetalityGameContractError::WinnerAlreadyRecorded);
+        assert_eq!(env.side_pool_state(&side_pool).await.total_a, 1);
+    }
+
+    #[tokio::test]
+    async fn side_pool_locked_at_start_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, user_b) = env.joined_game().await;
+        let bettor = env.create_player().await;
+
+        let pool_ix = env.initialize_side_pool_ix(&game, &user_b, &bettor, &bettor.nft_mint);
+        env.process(&[pool_ix], &[&bettor.keypair]).await.unwrap();
+
+        let side_pool = env.side_pool(&game, &user_b);
+        let admin = env.admin.insecure_clone();
+        let mut start_ix = env.set_game_started_ix(&game, &admin.pubkey(), &user_a, &user_b);
+        start_ix.accounts.push(AccountMeta::new(side_pool, false));
+        env.process(&[start_ix], &[&admin]).await.unwrap();
+
+        assert!(env.side_pool_state(&side_pool).await.locked);
+
+        let bet_ix = env.place_side_bet_ix(&game, &user_b, &bettor, SIDE_USER_B, 1);
+        let result = env.process(&[bet_ix], &[&bettor.keypair]).await;
+
+        assert_error(result, MetalityGameContractError::SidePoolLocked);
+    }
+
+    #[tokio::test]
+    async fn side_pool_after_recorded_winner_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, user_b) = env.started_game().await;
+        let bettor = env.create_player().await;
+
+        let admin = env.admin.insecure_clone();
+        let reward_ix = env.transfer_reward_ix(&game, &user_a);
+        env.process(&[reward_ix], &[&admin]).await.unwrap();
+
+        let pool_ix = env.initialize_side_pool_ix(&game, &user_b, &bettor, &bettor.nft_mint);
+        let result = env.process(&[pool_ix], &[&bettor.keypair]).await;
+
+        assert_error(result, MetalityGameContractError::WinnerAlreadyRecorded);
+        assert_eq!(env.game_state(&game).await.side_pool, Pubkey::default());
+    }
+
+    #[tokio::test]
+    async fn submit_signed_result_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, user_b) = env.started_game().await;
+
+        let admin = env.admin.insecure_clone();
+        let oracle = Keypair::new();
+        let oracle_ix = env.set_oracle_ix(&oracle.pubkey());
+        env.process(&[oracle_ix], &[&admin]).await.unwrap();
+
+        let result_ixs = env.submit_signed_result_ixs(&game, &oracle, &user_a.pubkey(), &user_a);
+        env.process(&result_ixs, &[&user_a.keypair]).await.unwrap();
+
+        let game_state = env.game_state(&game).await;
+        assert_eq!(game_state.winner, user_a.pubkey());
+        assert_eq!(game_state.outcome, GAME_OUTCOME_WON);
+        assert!(!game_state.expired);
+
+        // Submitting the same attestation again, here from another payer.
+        let result_ixs = env.submit_signed_result_ixs(&game, &oracle, &user_b.pubkey(), &user_a);
+        let result = env.process(&result_ixs, &[&user_b.keypair]).await;
+
+        assert_instruction_error(result, 1, MetalityGameContractError::WinnerAlreadyRecorded);
+    }
+
+    #[tokio::test]
+    async fn submit_result_signed_by_other_key_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, _user_b) = env.started_game().await;
+
+        let admin = env.admin.insecure_clone();
+        let oracle_ix = env.set_oracle_ix(&Keypair::new().pubkey());
+        env.process(&[oracle_ix], &[&admin]).await.unwrap();
+
+        let result_ixs =
+            env.submit_signed_result_ixs(&game, &user_a.keypair, &user_a.pubkey(), &user_a);
+        let result = env.process(&result_ixs, &[&user_a.keypair]).await;
+
+        assert_instruction_error(
+            result,
+            1,
+            MetalityGameContractError::InvalidOracleAttestation,
+        );
+        assert!(!env.game_state(&game).await.has_recorded_winner());
+    }
+
+    #[tokio::test]
+    async fn claim_unrecorded_reward_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, user_b) = env.started_game().await;
+
+        let claim_ix = env.claim_reward_ix(&game, &user_a.pubkey(), &user_a, &user_b);
+        let result = env.process(&[claim_ix], &[&user_a.keypair]).await;
+
+        assert_error(result, MetalityGameContractError::WinnerNotRecorded);
+        assert_eq!(env.token_account(&user_b.nft_ata).await.owner, game.pda);
+    }
+
+    #[tokio::test]
+    async fn claim_reward_by_loser_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, user_b) = env.started_game().await;
+
+        let admin = env.admin.insecure_clone();
+        let reward_ix = env.transfer_reward_ix(&game, &user_a);
+        env.process(&[reward_ix], &[&admin]).await.unwrap();
+
+        let claim_ix = env.claim_reward_ix(&game, &user_b.pubkey(), &user_b, &user_a);
+        let result = env.process(&[claim_ix], &[&user_b.keypair]).await;
+
+        assert_error(result, MetalityGameContractError::InvalidWinner);
+        assert_eq!(env.token_account(&user_a.nft_ata).await.owner, game.pda);
+    }
+
+    #[tokio::test]
+    async fn claim_reward_by_crank_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, user_b) = env.started_game().await;
+        let crank = env.create_player().await;
+
+        let admin = env.admin.insecure_clone();
+        let reward_ix = env.transfer_reward_ix(&game, &user_a);
+        env.process(&[reward_ix], &[&admin]).await.unwrap();
+
+        let winner_lamports = env
+            .context
+            .banks_client
+            .get_balance(user_a.pubkey())
+            .await
+            .unwrap();
+
+        let claim_ix = env.claim_reward_ix(&game, &crank.pubkey(), &user_a, &user_b);
+        env.process(&[claim_ix], &[&crank.keypair]).await.unwrap();
+
+        let won_nft_ata = get_associated_token_address(&user_a.pubkey(), &user_b.nft_mint);
+        let won_nft = env.token_account(&won_nft_ata).await;
+        assert_eq!(won_nft.owner, user_a.pubkey());
+        assert_eq!(won_nft.amount, 1);
+
+        // The crank paid for the winner's token account and profile.
+        assert_eq!(
+            env.context
+                .banks_client
+                .get_balance(user_a.pubkey())
+                .await
+                .unwrap(),
+            winner_lamports
+        );
+        assert!(env.game_state(&game).await.expired);
+    }
+
+    #[tokio::test]
+    async fn claim_self_claim_reward_by_crank_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, user_b) = env.started_game().await;
+        let crank = env.create_player().await;
+
+        let preference_ix = env.set_claim_preference_ix(&user_a, true);
+        env.process(&[preference_ix], &[&user_a.keypair])
+            .await
+            .unwrap();
+
+        let admin = env.admin.insecure_clone();
+        let reward_ix = env.transfer_reward_ix(&game, &user_a);
+        env.process(&[reward_ix], &[&admin]).await.unwrap();
+
+        let claim_ix = env.claim_reward_ix(&game, &crank.pubkey(), &user_a, &user_b);
+        let result = env.process(&[claim_ix], &[&crank.keypair]).await;
+
+        assert_error(result, MetalityGameContractError::WinnerMustClaim);
+        assert_eq!(env.token_account(&user_b.nft_ata).await.owner, game.pda);
+
+        let claim_ix = env.claim_reward_ix(&game, &user_a.pubkey(), &user_a, &user_b);
+        env.process(&[claim_ix], &[&user_a.keypair]).await.unwrap();
+
+        assert!(env.game_state(&game).await.expired);
+    }
+
+    #[tokio::test]
+    async fn draw_game_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, user_b) = env.started_game().await;
+
+        let admin = env.admin.insecure_clone();
+        let draw_ix = env.draw_or_cancel_game_ix(&game, &user_a, &user_b);
+        env.process(&[draw_ix], &[&admin]).await.unwrap();
+
+        assert_eq!(
+            env.token_account(&user_a.nft_ata).await.owner,
+            user_a.pubkey()
+        );
+        assert_eq!(
+            env.token_account(&user_b.nft_ata).await.owner,
+            user_b.pubkey()
+        );
+
+        let game_state = env.game_state(&game).await;
+        assert!(game_state.expired);
+        assert_eq!(game_state.outcome, GAME_OUTCOME_DRAW);
+    }
+
+    #[tokio::test]
+    async fn draw_settled_game_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, user_b) = env.started_game().await;
+
+        let admin = env.admin.insecure_clone();
+        let reward_ix = env.transfer_reward_ix(&game, &user_a);
+        let claim_ix = env.claim_reward_ix(&game, &user_a.pubkey(), &user_a, &user_b);
+        env.process(&[reward_ix, claim_ix], &[&admin, &user_a.keypair])
+            .await
+            .unwrap();
+
+        let draw_ix = env.draw_or_cancel_game_ix(&game, &user_a, &user_b);
+        let result = env.process(&[draw_ix], &[&admin]).await;
+
+        assert_error(result, MetalityGameContractError::GameExpired);
+    }
+
+    #[tokio::test]
+    async fn draw_recorded_game_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, user_b) = env.started_game().await;
+
+        let admin = env.admin.insecure_clone();
+        let reward_ix = env.transfer_reward_ix(&game, &user_a);
+        env.process(&[reward_ix], &[&admin]).await.unwrap();
+
+        let draw_ix = env.draw_or_cancel_game_ix(&game, &user_a, &user_b);
+        let result = env.process(&[draw_ix], &[&admin]).await;
+
+        assert_error(result, MetalityGameContractError::WinnerAlreadyRecorded);
+        assert_eq!(env.token_account(&user_b.nft_ata).await.owner, game.pda);
+    }
+
+    #[tokio::test]
+    async fn mutual_cancel_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, user_b) = env.started_game().await;
+
+        let propose_ix = env.propose_mutual_cancel_ix(&game, &user_a);
+        let accept_ix = env.accept_mutual_cancel_ix(&game, &user_b, &user_a, &user_b);
+        env.process(
+            &[propose_ix, accept_ix],
+            &[&user_a.keypair, &user_b.keypair],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            env.token_account(&user_a.nft_ata).await.owner,
+            user_a.pubkey()
+        );
+        assert_eq!(
+            env.token_account(&user_b.nft_ata).await.owner,
+            user_b.pubkey()
+        );
+
+        let game_state = env.game_state(&game).await;
+        assert!(game_state.expired);
+        assert_eq!(game_state.outcome, GAME_OUTCOME_CANCELLED);
+    }
+
+    #[tokio::test]
+    async fn accept_mutual_cancel_after_recorded_winner_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, user_b) = env.started_game().await;
+
+        let propose_ix = env.propose_mutual_cancel_ix(&game, &user_a);
+        env.process(&[propose_ix], &[&user_a.keypair])
+            .await
+            .unwrap();
+
+        let admin = env.admin.insecure_clone();
+        let reward_ix = env.transfer_reward_ix(&game, &user_a);
+        env.process(&[reward_ix], &[&admin]).await.unwrap();
+
+        let accept_ix = env.accept_mutual_cancel_ix(&game, &user_b, &user_a, &user_b);
+        let result = env.process(&[accept_ix], &[&user_b.keypair]).await;
+
+        assert_error(result, MetalityGameContractError::WinnerAlreadyRecorded);
+        assert_eq!(env.token_account(&user_a.nft_ata).await.owner, game.pda);
+        assert_eq!(env.token_account(&user_b.nft_ata).await.owner, game.pda);
+
+        let game_state = env.game_state(&game).await;
+        assert!(!game_state.expired);
+        assert_eq!(game_state.winner, user_a.pubkey());
+    }
+
+    #[tokio::test]
+    async fn propose_mutual_cancel_after_recorded_winner_test() {
+        let mut env = TestEnv::new().await;
+        let (game, user_a, user_b) = env.started_game().await;
+
+        let admin = env.admin.insecure_clone();
+        let reward_ix = env.transfer_reward_ix(&game, &user_a);
+        env.process(&[reward_ix], &[&admin]).await.unwrap();
+
+        let propose_ix = env.propose_mutual_cancel_ix(&game, &user_b);
+        let result = env.process(&[propose_ix], &[&user_b.keypair]).await;
+
+        assert_error(result, MetalityGameContractError::WinnerAlreadyRecorded);
+        assert_eq!(
+            env.game_state(&game).await.cancel_proposer,
+            Pubkey::default()
+        );
+    }
+}
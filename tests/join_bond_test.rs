@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod tests {
+    use metality_game_contract::state::{MetalityGameContractState, BASIS_POINTS};
+    use solana_program::program_pack::Pack;
+
+    fn get_game_state(join_bond: u64, bond_forfeit_bps: u16) -> MetalityGameContractState {
+        let mut game_state =
+            MetalityGameContractState::unpack_unchecked(&[0; MetalityGameContractState::LEN])
+                .unwrap();
+
+        game_state.join_bond = join_bond;
+        game_state.bond_forfeit_bps = bond_forfeit_bps;
+
+        game_state
+    }
+
+    #[test]
+    fn partial_bond_forfeit_test() {
+        assert_eq!(get_game_state(1_000_000, 2_500).forfeited_bond(), 250_000);
+        assert_eq!(get_game_state(3, 5_000).forfeited_bond(), 1);
+    }
+
+    #[test]
+    fn full_and_no_bond_forfeit_test() {
+        assert_eq!(
+            get_game_state(1_000_000, BASIS_POINTS).forfeited_bond(),
+            1_000_000
+        );
+        assert_eq!(get_game_state(1_000_000, 0).forfeited_bond(), 0);
+        assert_eq!(get_game_state(0, BASIS_POINTS).forfeited_bond(), 0);
+    }
+
+    #[test]
+    fn large_bond_forfeit_test() {
+        assert_eq!(
+            get_game_state(u64::MAX, BASIS_POINTS).forfeited_bond(),
+            u64::MAX
+        );
+    }
+}
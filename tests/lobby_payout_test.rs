@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+    use metality_game_contract::state::{
+        MetalityLobbyEntrant, MetalityLobbyState, MAX_LOBBY_PLAYERS,
+    };
+    use solana_program::pubkey::Pubkey;
+
+    fn get_lobby(player_count: u8, payout: &[u8]) -> MetalityLobbyState {
+        let mut payout_table = [0u8; MAX_LOBBY_PLAYERS];
+        payout_table[..payout.len()].copy_from_slice(payout);
+
+        MetalityLobbyState {
+            is_initialized: true,
+            creator: Pubkey::new_from_array([10; 32]),
+            pda_account: Pubkey::new_from_array([7; 32]),
+            max_players: player_count,
+            player_count,
+            expired: false,
+            game_started: true,
+            payout_table,
+            entrants: [MetalityLobbyEntrant::default(); MAX_LOBBY_PLAYERS],
+            recipients: [0; MAX_LOBBY_PLAYERS],
+            released: 0,
+        }
+    }
+
+    #[test]
+    fn winner_takes_all_test() {
+        let lobby = get_lobby(4, &[]);
+
+        let recipients = lobby.allocate_stakes(&[2, 0, 3, 1]).unwrap();
+
+        assert_eq!(recipients[..4], [2, 2, 2, 2]);
+    }
+
+    #[test]
+    fn ranked_payout_table_test() {
+        let lobby = get_lobby(4, &[3, 1]);
+
+        // Ranks: entrant 2 first, 0 second, 3 third, 1 last.
+        let recipients = lobby.allocate_stakes(&[2, 0, 3, 1]).unwrap();
+
+        assert_eq!(recipients[..4], [0, 2, 2, 2]);
+    }
+
+    #[test]
+    fn ranked_payout_table_three_paid_ranks_test() {
+        let lobby = get_lobby(6, &[3, 2, 1]);
+
+        let recipients = lobby.allocate_stakes(&[0, 1, 2, 3, 4, 5]).unwrap();
+
+        // Entrant 5 finished last so its stake goes to the winner first.
+        assert_eq!(recipients[..6], [0, 1, 2, 1, 0, 0]);
+    }
+
+    #[test]
+    fn failure_ranking_length_test() {
+        let lobby = get_lobby(4, &[]);
+
+        assert!(lobby.allocate_stakes(&[0, 1, 2]).is_err());
+    }
+
+    #[test]
+    fn failure_ranking_duplicate_test() {
+        let lobby = get_lobby(4, &[]);
+
+        assert!(lobby.allocate_stakes(&[0, 1, 1, 2]).is_err());
+    }
+
+    #[test]
+    fn failure_ranking_out_of_range_test() {
+        let lobby = get_lobby(3, &[]);
+
+        assert!(lobby.allocate_stakes(&[0, 1, 3]).is_err());
+    }
+
+    #[test]
+    fn validate_payout_table_test() {
+        let mut payout_table = [0u8; MAX_LOBBY_PLAYERS];
+
+        assert!(MetalityLobbyState::validate_payout_table(&payout_table, 4).is_ok());
+
+        payout_table[0] = 3;
+        payout_table[1] = 1;
+
+        assert!(MetalityLobbyState::validate_payout_table(&payout_table, 4).is_ok());
+        assert!(MetalityLobbyState::validate_payout_table(&payout_table, 5).is_err());
+
+        payout_table[1] = 0;
+        payout_table[5] = 1;
+
+        assert!(MetalityLobbyState::validate_payout_table(&payout_table, 4).is_err());
+    }
+
+    #[test]
+    fn own_stakes_test() {
+        let recipients = MetalityLobbyEntrant::own_stakes();
+
+        for (entrant, recipient) in recipients.iter().enumerate() {
+            assert_eq!(*recipient as usize, entrant);
+        }
+    }
+}
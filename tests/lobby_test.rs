@@ -0,0 +1,453 @@
+#[cfg(test)]
+mod tests {
+    use metality_game_contract::error::MetalityGameContractError;
+    use metality_game_contract::processor::Processor;
+    use metality_game_contract::state::{
+        MetalityGameProgramDataState, MetalityLobbyState, LOBBY_SEED_PREFIX, MAX_LOBBY_PLAYERS,
+        NFT_CHECK_STRICT, PROGRAM_DATA_SEED,
+    };
+    use metality_game_contract::validations::admin;
+    use solana_program::{program_option::COption, program_pack::Pack};
+    use solana_program_test::{processor, BanksClient, BanksClientError, ProgramTest};
+    use solana_sdk::{
+        account::Account,
+        hash::Hash,
+        instruction::{AccountMeta, Instruction, InstructionError},
+        packet::PACKET_DATA_SIZE,
+        pubkey::Pubkey,
+        rent::Rent,
+        signature::{Keypair, Signer},
+        system_program, sysvar,
+        transaction::{Transaction, TransactionError},
+    };
+    use spl_associated_token_account::get_associated_token_address;
+    use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+    fn packed_account<T: Pack>(state: T, owner: Pubkey) -> Account {
+        let mut data = vec![0; T::LEN];
+        T::pack(state, &mut data).unwrap();
+
+        Account {
+            lamports: Rent::default().minimum_balance(T::LEN),
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    struct Entrant {
+        keypair: Keypair,
+        nft_ata: Pubkey,
+        nft_mint: Pubkey,
+    }
+
+    impl Entrant {
+        fn pubkey(&self) -> Pubkey {
+            self.keypair.pubkey()
+        }
+    }
+
+    struct LobbyTest {
+        banks_client: BanksClient,
+        recent_blockhash: Hash,
+        program_id: Pubkey,
+        admin: Keypair,
+        program_data: Pubkey,
+        entrants: Vec<Entrant>,
+        lobby_state: Pubkey,
+        pda: Pubkey,
+    }
+
+    /// Starts a test validator with program data and `player_count` wallets
+    /// each holding an NFT, then has the first one open a winner takes all
+    /// lobby for `player_count` players that every other wallet joins.
+    async fn full_lobby(player_count: usize) -> LobbyTest {
+        let program_id = Pubkey::new_unique();
+
+        let mut program_test = ProgramTest::new(
+            "metality_game_contract",
+            program_id,
+            processor!(Processor::unpack_and_process_instruction),
+        );
+
+        let admin = Keypair::new();
+        let program_data =
+            Pubkey::create_with_seed(&admin::id(), PROGRAM_DATA_SEED, &program_id).unwrap();
+        program_test.add_account(
+            program_data,
+            packed_account(
+                MetalityGameProgramDataState {
+                    is_initialized: true,
+                    index: 0,
+                    oracle: Pubkey::default(),
+                    join_bond_lamports: 0,
+                    bond_forfeit_bps: 0,
+                    nft_check_level: NFT_CHECK_STRICT,
+                    admin: admin.pubkey(),
+                },
+                program_id,
+            ),
+        );
+        program_test.add_account(
+            admin.pubkey(),
+            Account::new(1_000_000_000, 0, &system_program::id()),
+        );
+
+        let mut entrants = Vec::with_capacity(player_count);
+
+        for _ in 0..player_count {
+            let keypair = Keypair::new();
+            let nft_mint = Pubkey::new_unique();
+            let nft_ata = Pubkey::new_unique();
+
+            program_test.add_account(
+                keypair.pubkey(),
+                Account::new(1_000_000_000, 0, &system_program::id()),
+            );
+            program_test.add_account(
+                nft_mint,
+                packed_account(
+                    Mint {
+                        mint_authority: COption::None,
+                        supply: 1,
+                        decimals: 0,
+                        is_initialized: true,
+                        freeze_authority: COption::None,
+                    },
+                    spl_token::id(),
+                ),
+            );
+            program_test.add_account(
+                nft_ata,
+                packed_account(
+                    TokenAccount {
+                        mint: nft_mint,
+                        owner: keypair.pubkey(),
+                        amount: 1,
+                        state: AccountState::Initialized,
+                        ..TokenAccount::default()
+                    },
+                    spl_token::id(),
+                ),
+            );
+
+            entrants.push(Entrant {
+                keypair,
+                nft_ata,
+                nft_mint,
+            });
+        }
+
+        let lobby_state = Pubkey::create_with_seed(
+            &entrants[0].pubkey(),
+            &format!("{}0", LOBBY_SEED_PREFIX),
+            &program_id,
+        )
+        .unwrap();
+        let (pda, _bump_seeds) = Pubkey::find_program_address(
+            &["metality_game_contract".as_bytes(), lobby_state.as_ref()],
+            &program_id,
+        );
+
+        let (banks_client, _payer, recent_blockhash) = program_test.start().await;
+
+        let mut lobby = LobbyTest {
+            banks_client,
+            recent_blockhash,
+            program_id,
+            admin,
+            program_data,
+            entrants,
+            lobby_state,
+            pda,
+        };
+
+        let mut ins_data = vec![8, player_count as u8];
+        ins_data.resize(2 + MAX_LOBBY_PLAYERS, 0);
+        let accounts = vec![
+            AccountMeta::new(lobby.entrants[0].pubkey(), true),
+            AccountMeta::new(lobby.entrants[0].nft_ata, false),
+            AccountMeta::new_readonly(lobby.entrants[0].nft_mint, false),
+            AccountMeta::new_readonly(lobby.pda, false),
+            AccountMeta::new(lobby.lobby_state, false),
+            AccountMeta::new(lobby.program_data, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ];
+        lobby.process(&ins_data, accounts, 0).await.unwrap();
+
+        for index in 1..player_count {
+            let accounts = vec![
+                AccountMeta::new(lobby.entrants[index].pubkey(), true),
+                AccountMeta::new(lobby.entrants[index].nft_ata, false),
+                AccountMeta::new_readonly(lobby.entrants[index].nft_mint, false),
+                AccountMeta::new_readonly(lobby.pda, false),
+                AccountMeta::new(lobby.lobby_state, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(lobby.program_data, false),
+            ];
+            lobby.process(&[9], accounts, index).await.unwrap();
+        }
+
+        lobby
+    }
+
+    impl LobbyTest {
+        /// Sends a single instruction signed and paid by entrant `signer`,
+        /// checking the transaction fits in a packet.
+        async fn process(
+            &mut self,
+            ins_data: &[u8],
+            accounts: Vec<AccountMeta>,
+            signer: usize,
+        ) -> Result<(), BanksClientError> {
+            let signer = &self.entrants[signer].keypair;
+            self.process_signed(ins_data, accounts, signer.pubkey(), signer.to_bytes())
+                .await
+        }
+
+        async fn process_admin(
+            &mut self,
+            ins_data: &[u8],
+            accounts: Vec<AccountMeta>,
+        ) -> Result<(), BanksClientError> {
+            self.process_signed(
+                ins_data,
+                accounts,
+                self.admin.pubkey(),
+                self.admin.to_bytes(),
+            )
+            .await
+        }
+
+        async fn process_signed(
+            &mut self,
+            ins_data: &[u8],
+            accounts: Vec<AccountMeta>,
+            signer: Pubkey,
+            signer_bytes: [u8; 64],
+        ) -> Result<(), BanksClientError> {
+            let signer_keypair = Keypair::from_bytes(&signer_bytes).unwrap();
+
+            let transaction = Transaction::new_signed_with_payer(
+                &[Instruction::new_with_bytes(
+                    self.program_id,
+                    ins_data,
+                    accounts,
+                )],
+                Some(&signer),
+                &[&signer_keypair],
+                self.recent_blockhash,
+            );
+
+            // Signature count, signatures and the message.
+            let transaction_size =
+                1 + 64 * transaction.signatures.len() + transaction.message_data().len();
+            assert!(
+                transaction_size <= PACKET_DATA_SIZE,
+                "instruction {} needs {} bytes",
+                ins_data[0],
+                transaction_size
+            );
+
+            self.banks_client.process_transaction(transaction).await
+        }
+
+        async fn set_started(&mut self) -> Result<(), BanksClientError> {
+            let accounts = vec![
+                AccountMeta::new_readonly(self.admin.pubkey(), true),
+                AccountMeta::new_readonly(self.program_data, false),
+                AccountMeta::new(self.lobby_state, false),
+            ];
+
+            self.process_admin(&[12], accounts).await
+        }
+
+        async fn settle(&mut self, ranking: &[u8]) -> Result<(), BanksClientError> {
+            let mut ins_data = vec![13];
+            ins_data.extend_from_slice(ranking);
+
+            let accounts = vec![
+                AccountMeta::new_readonly(self.admin.pubkey(), true),
+                AccountMeta::new_readonly(self.program_data, false),
+                AccountMeta::new(self.lobby_state, false),
+            ];
+
+            self.process_admin(&ins_data, accounts).await
+        }
+
+        async fn cancel(&mut self) -> Result<(), BanksClientError> {
+            let accounts = vec![
+                AccountMeta::new_readonly(self.entrants[0].pubkey(), true),
+                AccountMeta::new_readonly(self.program_data, false),
+                AccountMeta::new(self.lobby_state, false),
+            ];
+
+            self.process(&[11], accounts, 0).await
+        }
+
+        /// Releases the stake of entrant `index` to entrant `recipient`,
+        /// paid for by entrant `payer`.
+        async fn release(
+            &mut self,
+            index: usize,
+            recipient: usize,
+            payer: usize,
+        ) -> Result<(), BanksClientError> {
+            let entrant = &self.entrants[index];
+            let recipient = self.entrants[recipient].pubkey();
+
+            let accounts = vec![
+                AccountMeta::new(self.entrants[payer].pubkey(), true),
+                AccountMeta::new_readonly(self.pda, false),
+                AccountMeta::new(self.lobby_state, false),
+                AccountMeta::new_readonly(entrant.pubkey(), false),
+                AccountMeta::new(entrant.nft_ata, false),
+                AccountMeta::new_readonly(entrant.nft_mint, false),
+                AccountMeta::new_readonly(recipient, false),
+                AccountMeta::new(
+                    get_associated_token_address(&recipient, &entrant.nft_mint),
+                    false,
+                ),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            ];
+
+            self.process(&[40], accounts, payer).await
+        }
+
+        async fn token_account(&mut self, address: Pubkey) -> TokenAccount {
+            let account = self
+                .banks_client
+                .get_account(address)
+                .await
+                .unwrap()
+                .unwrap();
+
+            TokenAccount::unpack(&account.data).unwrap()
+        }
+
+        async fn lobby_state(&mut self) -> MetalityLobbyState {
+            let account = self
+                .banks_client
+                .get_account(self.lobby_state)
+                .await
+                .unwrap()
+                .unwrap();
+
+            MetalityLobbyState::unpack(&account.data).unwrap()
+        }
+    }
+
+    fn assert_error(result: Result<(), BanksClientError>, error: MetalityGameContractError) {
+        assert_eq!(
+            result.unwrap_err().unwrap(),
+            TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+        );
+    }
+
+    #[tokio::test]
+    async fn settle_full_lobby_test() {
+        let mut lobby = full_lobby(MAX_LOBBY_PLAYERS).await;
+
+        lobby.set_started().await.unwrap();
+
+        // The last entrant to join wins every stake.
+        let winner = MAX_LOBBY_PLAYERS - 1;
+        let ranking: Vec<u8> = (0..MAX_LOBBY_PLAYERS as u8).rev().collect();
+        lobby.settle(&ranking).await.unwrap();
+
+        assert!(lobby.lobby_state().await.expired);
+
+        for index in 0..MAX_LOBBY_PLAYERS {
+            lobby.release(index, winner, winner).await.unwrap();
+        }
+
+        let lobby_state = lobby.lobby_state().await;
+        assert_eq!(lobby_state.released, u16::MAX);
+
+        for index in 0..MAX_LOBBY_PLAYERS {
+            let nft_ata = lobby.entrants[index].nft_ata;
+            let owner = lobby.entrants[index].pubkey();
+            let staked_nft = lobby.token_account(nft_ata).await;
+            assert_eq!(staked_nft.owner, owner);
+
+            if index == winner {
+                assert_eq!(staked_nft.amount, 1);
+                continue;
+            }
+
+            assert_eq!(staked_nft.amount, 0);
+
+            let won_nft = get_associated_token_address(
+                &lobby.entrants[winner].pubkey(),
+                &lobby.entrants[index].nft_mint,
+            );
+            assert_eq!(lobby.token_account(won_nft).await.amount, 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn cancel_full_lobby_test() {
+        let mut lobby = full_lobby(MAX_LOBBY_PLAYERS).await;
+
+        lobby.cancel().await.unwrap();
+
+        for index in 0..MAX_LOBBY_PLAYERS {
+            lobby.release(index, index, index).await.unwrap();
+        }
+
+        for index in 0..MAX_LOBBY_PLAYERS {
+            let nft_ata = lobby.entrants[index].nft_ata;
+            let owner = lobby.entrants[index].pubkey();
+            let staked_nft = lobby.token_account(nft_ata).await;
+
+            assert_eq!(staked_nft.owner, owner);
+            assert_eq!(staked_nft.amount, 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn release_before_settle_test() {
+        let mut lobby = full_lobby(4).await;
+
+        lobby.set_started().await.unwrap();
+
+        assert_error(
+            lobby.release(0, 0, 0).await,
+            MetalityGameContractError::StakesLocked,
+        );
+    }
+
+    #[tokio::test]
+    async fn release_twice_test() {
+        let mut lobby = full_lobby(4).await;
+
+        lobby.set_started().await.unwrap();
+        lobby.settle(&[0, 1, 2, 3]).await.unwrap();
+
+        lobby.release(1, 0, 0).await.unwrap();
+
+        // Paid by another entrant so the transaction isn't deduplicated.
+        assert_error(
+            lobby.release(1, 0, 1).await,
+            MetalityGameContractError::StakeAlreadyReleased,
+        );
+    }
+
+    #[tokio::test]
+    async fn release_to_wrong_recipient_test() {
+        let mut lobby = full_lobby(4).await;
+
+        lobby.set_started().await.unwrap();
+        lobby.settle(&[0, 1, 2, 3]).await.unwrap();
+
+        assert_error(
+            lobby.release(1, 1, 1).await,
+            MetalityGameContractError::EntrantMismatch,
+        );
+    }
+}
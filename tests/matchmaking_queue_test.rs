@@ -0,0 +1,240 @@
+#[cfg(test)]
+mod tests {
+    use metality_game_contract::error::MetalityGameContractError;
+    use metality_game_contract::processor::Processor;
+    use metality_game_contract::state::{
+        MetalityGameContractState, MetalityGameProgramDataState, MetalityQueueEntry,
+        MetalityQueueState, GAME_STATE_SEED_PREFIX, MAX_QUEUE_ENTRIES, NFT_CHECK_STRICT,
+        PROGRAM_DATA_SEED,
+    };
+    use metality_game_contract::validations::admin;
+    use solana_program::{program_pack::Pack, pubkey::Pubkey};
+    use solana_program_test::{processor, BanksClientError, ProgramTest};
+    use solana_sdk::{
+        account::Account,
+        instruction::{AccountMeta, Instruction, InstructionError},
+        rent::Rent,
+        signature::Signer,
+        system_program,
+        transaction::{Transaction, TransactionError},
+    };
+    use spl_token::state::{Account as TokenAccount, AccountState};
+
+    fn get_entry(user: u8, rating: u32, min: u32, max: u32) -> MetalityQueueEntry {
+        MetalityQueueEntry {
+            user: Pubkey::new_from_array([user; 32]),
+            nft_ata: Pubkey::new_from_array([user + 100; 32]),
+            nft_mint: Pubkey::new_from_array([user + 150; 32]),
+            rating,
+            min_opponent_rating: min,
+            max_opponent_rating: max,
+        }
+    }
+
+    #[test]
+    fn compatible_entries_test() {
+        let first = get_entry(1, 1200, 1100, 1300);
+        let second = get_entry(2, 1250, 1000, 1200);
+
+        assert!(first.accepts(&second));
+        assert!(second.accepts(&first));
+    }
+
+    #[test]
+    fn incompatible_entries_test() {
+        let first = get_entry(1, 1200, 1100, 1300);
+        let second = get_entry(2, 1250, 1210, 1400);
+
+        assert!(first.accepts(&second));
+        assert!(!second.accepts(&first));
+    }
+
+    #[test]
+    fn remove_entry_test() {
+        let mut entries = [MetalityQueueEntry::default(); MAX_QUEUE_ENTRIES];
+
+        for index in 0..4u8 {
+            entries[index as usize] = get_entry(index + 1, 1200, 0, u32::MAX);
+        }
+
+        let mut queue = MetalityQueueState {
+            is_initialized: true,
+            pda_account: Pubkey::new_from_array([7; 32]),
+            entry_count: 4,
+            entries,
+        };
+
+        queue.remove_entry(1);
+
+        assert_eq!(queue.entry_count, 3);
+        assert_eq!(queue.entry_index(&Pubkey::new_from_array([2; 32])), None);
+        assert_eq!(queue.entry_index(&Pubkey::new_from_array([3; 32])), Some(1));
+        assert_eq!(queue.entry_index(&Pubkey::new_from_array([4; 32])), Some(2));
+        assert_eq!(queue.entries[3], MetalityQueueEntry::default());
+
+        queue.remove_entry(2);
+
+        assert_eq!(queue.entry_count, 2);
+        assert_eq!(queue.entry_index(&Pubkey::new_from_array([4; 32])), None);
+    }
+
+    fn packed_account<T: Pack>(state: T, owner: Pubkey) -> Account {
+        let mut data = vec![0; T::LEN];
+        T::pack(state, &mut data).unwrap();
+
+        Account {
+            lamports: Rent::default().minimum_balance(T::LEN),
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    /// Matches the first two entries of a queue holding both stakes, against
+    /// program data preloaded at the real address or, if `forged`, at a
+    /// fresh one. Returns the result and the new game's state account.
+    async fn match_queued(forged: bool) -> (Result<(), BanksClientError>, Option<Account>) {
+        let program_id = Pubkey::new_unique();
+
+        let mut program_test = ProgramTest::new(
+            "metality_game_contract",
+            program_id,
+            processor!(Processor::unpack_and_process_instruction),
+        );
+
+        let program_data_address = if forged {
+            Pubkey::new_unique()
+        } else {
+            Pubkey::create_with_seed(&admin::id(), PROGRAM_DATA_SEED, &program_id).unwrap()
+        };
+        let program_data = MetalityGameProgramDataState {
+            is_initialized: true,
+            index: 3,
+            oracle: Pubkey::default(),
+            join_bond_lamports: 0,
+            bond_forfeit_bps: 0,
+            nft_check_level: NFT_CHECK_STRICT,
+            admin: admin::id(),
+        };
+        program_test.add_account(
+            program_data_address,
+            packed_account(program_data, program_id),
+        );
+
+        let queue_state_address = Pubkey::new_unique();
+        let (queue_pda, _bump_seeds) = Pubkey::find_program_address(
+            &[
+                "metality_game_contract".as_bytes(),
+                queue_state_address.as_ref(),
+            ],
+            &program_id,
+        );
+
+        let mut entries = [MetalityQueueEntry::default(); MAX_QUEUE_ENTRIES];
+
+        for (index, entry) in entries.iter_mut().take(2).enumerate() {
+            *entry = get_entry(index as u8 + 1, 1200, 0, u32::MAX);
+
+            program_test.add_account(
+                entry.nft_ata,
+                packed_account(
+                    TokenAccount {
+                        mint: entry.nft_mint,
+                        owner: queue_pda,
+                        amount: 1,
+                        state: AccountState::Initialized,
+                        ..TokenAccount::default()
+                    },
+                    spl_token::id(),
+                ),
+            );
+        }
+
+        program_test.add_account(
+            queue_state_address,
+            packed_account(
+                MetalityQueueState {
+                    is_initialized: true,
+                    pda_account: queue_pda,
+                    entry_count: 2,
+                    entries,
+                },
+                program_id,
+            ),
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let game_state_address = Pubkey::create_with_seed(
+            &payer.pubkey(),
+            &program_data.indexed_seed(GAME_STATE_SEED_PREFIX).unwrap(),
+            &program_id,
+        )
+        .unwrap();
+        let (pda, _bump_seeds) = Pubkey::find_program_address(
+            &[
+                "metality_game_contract".as_bytes(),
+                game_state_address.as_ref(),
+            ],
+            &program_id,
+        );
+
+        let transaction = Transaction::new_signed_with_payer(
+            &[Instruction::new_with_bytes(
+                program_id,
+                &[23, 0, 1],
+                vec![
+                    AccountMeta::new(payer.pubkey(), true),
+                    AccountMeta::new_readonly(queue_pda, false),
+                    AccountMeta::new(queue_state_address, false),
+                    AccountMeta::new(entries[0].nft_ata, false),
+                    AccountMeta::new(entries[1].nft_ata, false),
+                    AccountMeta::new_readonly(pda, false),
+                    AccountMeta::new(game_state_address, false),
+                    AccountMeta::new(program_data_address, false),
+                    AccountMeta::new_readonly(spl_token::id(), false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            )],
+            Some(&payer.pubkey()),
+            &[&payer],
+            recent_blockhash,
+        );
+
+        let result = banks_client.process_transaction(transaction).await;
+
+        (
+            result,
+            banks_client.get_account(game_state_address).await.unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn match_queued_test() {
+        let (result, game_state) = match_queued(false).await;
+
+        result.unwrap();
+
+        let game_state = MetalityGameContractState::unpack(&game_state.unwrap().data).unwrap();
+        assert!(game_state.game_started);
+        assert_eq!(game_state.user_a, Pubkey::new_from_array([1; 32]));
+        assert_eq!(game_state.user_b, Pubkey::new_from_array([2; 32]));
+    }
+
+    #[tokio::test]
+    async fn match_queued_forged_program_data_test() {
+        let (result, game_state) = match_queued(true).await;
+
+        assert_eq!(
+            result.unwrap_err().unwrap(),
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(
+                    MetalityGameContractError::InvalidProgramDataAccount as u32
+                )
+            )
+        );
+        assert!(game_state.is_none());
+    }
+}
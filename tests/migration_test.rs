@@ -0,0 +1,478 @@
+#[cfg(test)]
+mod tests {
+    use metality_game_contract::error::MetalityGameContractError;
+    use metality_game_contract::processor::Processor;
+    use metality_game_contract::profile::find_profile_address;
+    use metality_game_contract::state::{
+        MetalityGameContractState, MetalityGameProgramDataState, GAME_OUTCOME_WON,
+        NFT_CHECK_STRICT, PROGRAM_DATA_SEED,
+    };
+    use metality_game_contract::validations::admin;
+    use solana_program::{program_option::COption, program_pack::Pack};
+    use solana_program_test::{processor, BanksClient, BanksClientError, ProgramTest};
+    use solana_sdk::{
+        account::Account,
+        hash::Hash,
+        instruction::{AccountMeta, Instruction, InstructionError},
+        pubkey::Pubkey,
+        rent::Rent,
+        signature::{Keypair, Signer},
+        system_program, sysvar,
+        transaction::{Transaction, TransactionError},
+    };
+    use spl_associated_token_account::get_associated_token_address;
+    use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+    fn data_account(data: Vec<u8>, owner: Pubkey) -> Account {
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    fn packed_account<T: Pack>(state: T, owner: Pubkey) -> Account {
+        let mut data = vec![0; T::LEN];
+        T::pack(state, &mut data).unwrap();
+
+        data_account(data, owner)
+    }
+
+    fn nft_mint() -> Account {
+        packed_account(
+            Mint {
+                mint_authority: COption::None,
+                supply: 1,
+                decimals: 0,
+                is_initialized: true,
+                freeze_authority: COption::None,
+            },
+            spl_token::id(),
+        )
+    }
+
+    fn staked_nft(mint: Pubkey, pda: Pubkey) -> Account {
+        packed_account(
+            TokenAccount {
+                mint,
+                owner: pda,
+                amount: 1,
+                state: AccountState::Initialized,
+                ..TokenAccount::default()
+            },
+            spl_token::id(),
+        )
+    }
+
+    struct LegacyGame {
+        banks_client: BanksClient,
+        recent_blockhash: Hash,
+        program_id: Pubkey,
+        admin: Keypair,
+        program_data: Pubkey,
+        user_a: Keypair,
+        user_b: Keypair,
+        game_state: Pubkey,
+        expected_state: MetalityGameContractState,
+        pda: Pubkey,
+        a_nft_ata: Pubkey,
+        a_nft_mint: Pubkey,
+        b_nft_ata: Pubkey,
+        b_nft_mint: Pubkey,
+    }
+
+    /// Starts a test validator holding a started game with both NFTs in
+    /// escrow, stored in the legacy layout when `legacy` is set.
+    async fn legacy_game(legacy: bool) -> LegacyGame {
+        let program_id = Pubkey::new_unique();
+
+        let mut program_test = ProgramTest::new(
+            "metality_game_contract",
+            program_id,
+            processor!(Processor::unpack_and_process_instruction),
+        );
+
+        let admin = Keypair::new();
+        let program_data =
+            Pubkey::create_with_seed(&admin::id(), PROGRAM_DATA_SEED, &program_id).unwrap();
+        program_test.add_account(
+            program_data,
+            packed_account(
+                MetalityGameProgramDataState {
+                    is_initialized: true,
+                    index: 1,
+                    oracle: Pubkey::default(),
+                    join_bond_lamports: 0,
+                    bond_forfeit_bps: 0,
+                    nft_check_level: NFT_CHECK_STRICT,
+                    admin: admin.pubkey(),
+                },
+                program_id,
+            ),
+        );
+
+        let user_a = Keypair::new();
+        let user_b = Keypair::new();
+        for user in [&admin, &user_a, &user_b] {
+            program_test.add_account(
+                user.pubkey(),
+                Account::new(1_000_000_000, 0, &system_program::id()),
+            );
+        }
+
+        let game_state = Pubkey::new_unique();
+        let (pda, _bump_seeds) = Pubkey::find_program_address(
+            &["metality_game_contract".as_bytes(), game_state.as_ref()],
+            &program_id,
+        );
+
+        let a_nft_mint = Pubkey::new_unique();
+        let a_nft_ata = Pubkey::new_unique();
+        let b_nft_mint = Pubkey::new_unique();
+        let b_nft_ata = Pubkey::new_unique();
+        program_test.add_account(a_nft_mint, nft_mint());
+        program_test.add_account(b_nft_mint, nft_mint());
+        program_test.add_account(a_nft_ata, staked_nft(a_nft_mint, pda));
+        program_test.add_account(b_nft_ata, staked_nft(b_nft_mint, pda));
+
+        let mut expected_state =
+            MetalityGameContractState::unpack_unchecked(&[0; MetalityGameContractState::LEN])
+                .unwrap();
+        expected_state.is_initialized = true;
+        expected_state.user_a = user_a.pubkey();
+        expected_state.a_nft_ata = a_nft_ata;
+        expected_state.a_nft_mint = a_nft_mint;
+        expected_state.user_b = user_b.pubkey();
+        expected_state.user_b_joined = true;
+        expected_state.b_nft_ata = b_nft_ata;
+        expected_state.b_nft_mint = b_nft_mint;
+        expected_state.pda_account = pda;
+        expected_state.game_started = true;
+        expected_state.max_opponent_rating = u32::MAX;
+
+        let mut data = vec![0; MetalityGameContractState::LEN];
+        MetalityGameContractState::pack(expected_state, &mut data).unwrap();
+        if legacy {
+            data.truncate(MetalityGameContractState::LEGACY_LEN);
+        }
+        program_test.add_account(game_state, data_account(data, program_id));
+
+        let (banks_client, _payer, recent_blockhash) = program_test.start().await;
+
+        LegacyGame {
+            banks_client,
+            recent_blockhash,
+            program_id,
+            admin,
+            program_data,
+            user_a,
+            user_b,
+            game_state,
+            expected_state,
+            pda,
+            a_nft_ata,
+            a_nft_mint,
+            b_nft_ata,
+            b_nft_mint,
+        }
+    }
+
+    impl LegacyGame {
+        async fn process(
+            &mut self,
+            ins_data: &[u8],
+            accounts: Vec<AccountMeta>,
+            signer: &Keypair,
+        ) -> Result<(), BanksClientError> {
+            let transaction = Transaction::new_signed_with_payer(
+                &[Instruction::new_with_bytes(
+                    self.program_id,
+                    ins_data,
+                    accounts,
+                )],
+                Some(&signer.pubkey()),
+                &[signer],
+                self.recent_blockhash,
+            );
+
+            self.banks_client.process_transaction(transaction).await
+        }
+
+        async fn migrate(&mut self, payer: &Keypair) -> Result<(), BanksClientError> {
+            self.process(
+                &[39],
+                vec![
+                    AccountMeta::new(payer.pubkey(), true),
+                    AccountMeta::new(self.game_state, false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+                payer,
+            )
+            .await
+        }
+
+        async fn game_state_account(&mut self) -> Account {
+            self.banks_client
+                .get_account(self.game_state)
+                .await
+                .unwrap()
+                .unwrap()
+        }
+    }
+
+    fn assert_error(result: Result<(), BanksClientError>, error: MetalityGameContractError) {
+        assert_eq!(
+            result.unwrap_err().unwrap(),
+            TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+        );
+    }
+
+    #[test]
+    fn unpack_legacy_game_state_test() {
+        let mut state =
+            MetalityGameContractState::unpack_unchecked(&[0; MetalityGameContractState::LEN])
+                .unwrap();
+        state.is_initialized = true;
+        state.user_a = Pubkey::new_unique();
+        state.game_started = true;
+        state.max_opponent_rating = u32::MAX;
+
+        let mut data = vec![0; MetalityGameContractState::LEN];
+        MetalityGameContractState::pack(state, &mut data).unwrap();
+
+        assert_eq!(
+            MetalityGameContractState::unpack_legacy(
+                &data[..MetalityGameContractState::LEGACY_LEN]
+            )
+            .unwrap(),
+            state
+        );
+
+        assert!(MetalityGameContractState::unpack_legacy(&data).is_err());
+    }
+
+    #[tokio::test]
+    async fn migrate_and_settle_legacy_game_test() {
+        let mut game = legacy_game(true).await;
+        let user_a = Keypair::from_bytes(&game.user_a.to_bytes()).unwrap();
+
+        game.migrate(&user_a).await.unwrap();
+
+        let game_state_account = game.game_state_account().await;
+        assert_eq!(
+            game_state_account.data.len(),
+            MetalityGameContractState::LEN
+        );
+        assert!(
+            Rent::default().is_exempt(game_state_account.lamports, MetalityGameContractState::LEN)
+        );
+        assert_eq!(
+            MetalityGameContractState::unpack(&game_state_account.data).unwrap(),
+            game.expected_state
+        );
+
+        let admin = Keypair::from_bytes(&game.admin.to_bytes()).unwrap();
+        let (program_data, user_b, game_state) =
+            (game.program_data, game.user_b.pubkey(), game.game_state);
+        game.process(
+            &[29],
+            vec![
+                AccountMeta::new_readonly(admin.pubkey(), true),
+                AccountMeta::new_readonly(program_data, false),
+                AccountMeta::new_readonly(user_b, false),
+                AccountMeta::new(game_state, false),
+            ],
+            &admin,
+        )
+        .await
+        .unwrap();
+
+        let winner = Keypair::from_bytes(&game.user_b.to_bytes()).unwrap();
+        let winner_won_nft_ata = get_associated_token_address(&winner.pubkey(), &game.a_nft_mint);
+        let accounts = vec![
+            AccountMeta::new(winner.pubkey(), true),
+            AccountMeta::new(winner.pubkey(), true),
+            AccountMeta::new(game.user_a.pubkey(), false),
+            AccountMeta::new(game.a_nft_ata, false),
+            AccountMeta::new_readonly(game.a_nft_mint, false),
+            AccountMeta::new(game.b_nft_ata, false),
+            AccountMeta::new_readonly(game.b_nft_mint, false),
+            AccountMeta::new(winner_won_nft_ata, false),
+            AccountMeta::new_readonly(game.pda, false),
+            AccountMeta::new(game.game_state, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new(
+                find_profile_address(&winner.pubkey(), &game.program_id).0,
+                false,
+            ),
+            AccountMeta::new(
+                find_profile_address(&game.user_a.pubkey(), &game.program_id).0,
+                false,
+            ),
+        ];
+        game.process(&[30], accounts, &winner).await.unwrap();
+
+        let game_state =
+            MetalityGameContractState::unpack(&game.game_state_account().await.data).unwrap();
+        assert!(game_state.expired);
+        assert_eq!(game_state.winner, winner.pubkey());
+        assert_eq!(game_state.outcome, GAME_OUTCOME_WON);
+
+        let won_nft = game
+            .banks_client
+            .get_account(winner_won_nft_ata)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(TokenAccount::unpack(&won_nft.data).unwrap().amount, 1);
+
+        // The winner's own stake is handed back by transferring ownership.
+        let owned_nft = game
+            .banks_client
+            .get_account(game.b_nft_ata)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            TokenAccount::unpack(&owned_nft.data).unwrap().owner,
+            winner.pubkey()
+        );
+    }
+
+    #[tokio::test]
+    async fn migrate_current_game_test() {
+        let mut game = legacy_game(false).await;
+        let user_a = Keypair::from_bytes(&game.user_a.to_bytes()).unwrap();
+
+        assert_error(
+            game.migrate(&user_a).await,
+            MetalityGameContractError::NothingToMigrate,
+        );
+    }
+
+    #[tokio::test]
+    async fn migrate_legacy_game_twice_test() {
+        let mut game = legacy_game(true).await;
+
+        let user_a = Keypair::from_bytes(&game.user_a.to_bytes()).unwrap();
+        let user_b = Keypair::from_bytes(&game.user_b.to_bytes()).unwrap();
+
+        game.migrate(&user_a).await.unwrap();
+
+        // Paid by the other player so the transaction isn't deduplicated.
+        assert_error(
+            game.migrate(&user_b).await,
+            MetalityGameContractError::NothingToMigrate,
+        );
+    }
+
+    #[test]
+    fn unpack_legacy_program_data_test() {
+        let deployer = Pubkey::new_unique();
+
+        let mut data = vec![1];
+        data.extend_from_slice(&7u64.to_le_bytes());
+
+        assert_eq!(
+            MetalityGameProgramDataState::unpack_legacy(&data, deployer).unwrap(),
+            MetalityGameProgramDataState {
+                is_initialized: true,
+                index: 7,
+                oracle: Pubkey::default(),
+                join_bond_lamports: 0,
+                bond_forfeit_bps: 0,
+                nft_check_level: NFT_CHECK_STRICT,
+                admin: deployer,
+            }
+        );
+
+        data.push(0);
+        assert!(MetalityGameProgramDataState::unpack_legacy(&data, deployer).is_err());
+    }
+
+    /// Migrates a legacy program data account, stored at the program data
+    /// address when `at_program_data_address` is set and elsewhere otherwise.
+    /// Returns the result and the account afterwards.
+    async fn migrate_legacy_program_data(
+        at_program_data_address: bool,
+    ) -> (Result<(), BanksClientError>, Account) {
+        let program_id = Pubkey::new_unique();
+
+        let mut program_test = ProgramTest::new(
+            "metality_game_contract",
+            program_id,
+            processor!(Processor::unpack_and_process_instruction),
+        );
+
+        let program_data = if at_program_data_address {
+            Pubkey::create_with_seed(&admin::id(), PROGRAM_DATA_SEED, &program_id).unwrap()
+        } else {
+            Pubkey::new_unique()
+        };
+
+        let mut data = vec![1];
+        data.extend_from_slice(&7u64.to_le_bytes());
+        program_test.add_account(program_data, data_account(data, program_id));
+
+        let payer = Keypair::new();
+        program_test.add_account(
+            payer.pubkey(),
+            Account::new(1_000_000_000, 0, &system_program::id()),
+        );
+
+        let (mut banks_client, _payer, recent_blockhash) = program_test.start().await;
+
+        let transaction = Transaction::new_signed_with_payer(
+            &[Instruction::new_with_bytes(
+                program_id,
+                &[39],
+                vec![
+                    AccountMeta::new(payer.pubkey(), true),
+                    AccountMeta::new(program_data, false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            )],
+            Some(&payer.pubkey()),
+            &[&payer],
+            recent_blockhash,
+        );
+
+        let result = banks_client.process_transaction(transaction).await;
+
+        let account = banks_client
+            .get_account(program_data)
+            .await
+            .unwrap()
+            .unwrap();
+
+        (result, account)
+    }
+
+    #[tokio::test]
+    async fn migrate_legacy_program_data_test() {
+        let (result, account) = migrate_legacy_program_data(true).await;
+
+        result.unwrap();
+        assert_eq!(account.data.len(), MetalityGameProgramDataState::LEN);
+        assert!(Rent::default().is_exempt(account.lamports, MetalityGameProgramDataState::LEN));
+
+        let program_data = MetalityGameProgramDataState::unpack(&account.data).unwrap();
+        assert!(program_data.is_initialized);
+        assert_eq!(program_data.index, 7);
+        assert_eq!(program_data.nft_check_level, NFT_CHECK_STRICT);
+        assert_eq!(program_data.admin, admin::id());
+    }
+
+    #[tokio::test]
+    async fn migrate_legacy_program_data_elsewhere_test() {
+        let (result, account) = migrate_legacy_program_data(false).await;
+
+        assert_error(result, MetalityGameContractError::InvalidProgramDataAccount);
+        assert_eq!(account.data.len(), MetalityGameProgramDataState::LEGACY_LEN);
+    }
+}
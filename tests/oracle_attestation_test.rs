@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod tests {
+    use metality_game_contract::oracle::{
+        parse_ed25519_instruction, result_message, RESULT_MESSAGE_LEN,
+    };
+    use solana_program::pubkey::Pubkey;
+
+    const DATA_START: u16 = 16;
+
+    fn get_ed25519_data(signer: &Pubkey, message: &[u8], instruction_index: u16) -> Vec<u8> {
+        let public_key_offset = DATA_START;
+        let signature_offset = public_key_offset + 32;
+        let message_data_offset = signature_offset + 64;
+
+        let mut data = vec![1u8, 0];
+
+        for offset in [
+            signature_offset,
+            instruction_index,
+            public_key_offset,
+            instruction_index,
+            message_data_offset,
+            message.len() as u16,
+            instruction_index,
+        ] {
+            data.extend_from_slice(&offset.to_le_bytes());
+        }
+
+        data.extend_from_slice(signer.as_ref());
+        data.extend_from_slice(&[0u8; 64]);
+        data.extend_from_slice(message);
+
+        data
+    }
+
+    #[test]
+    fn result_message_layout_test() {
+        let game_state = Pubkey::new_from_array([1; 32]);
+        let winner = Pubkey::new_from_array([2; 32]);
+
+        let message = result_message(&game_state, &winner);
+
+        assert_eq!(message.len(), RESULT_MESSAGE_LEN);
+        assert_eq!(&message[..32], game_state.as_ref());
+        assert_eq!(&message[32..], winner.as_ref());
+    }
+
+    #[test]
+    fn parse_ed25519_instruction_test() {
+        let oracle = Pubkey::new_from_array([3; 32]);
+        let message = result_message(
+            &Pubkey::new_from_array([1; 32]),
+            &Pubkey::new_from_array([2; 32]),
+        );
+
+        let data = get_ed25519_data(&oracle, &message, u16::MAX);
+
+        let (signer, parsed_message) = parse_ed25519_instruction(&data).unwrap();
+
+        assert_eq!(signer, oracle);
+        assert_eq!(parsed_message, message);
+    }
+
+    #[test]
+    fn reject_data_from_other_instruction_test() {
+        let oracle = Pubkey::new_from_array([3; 32]);
+
+        let data = get_ed25519_data(&oracle, &[5u8; RESULT_MESSAGE_LEN], 0);
+
+        assert!(parse_ed25519_instruction(&data).is_err());
+    }
+
+    #[test]
+    fn reject_multiple_signatures_test() {
+        let oracle = Pubkey::new_from_array([3; 32]);
+
+        let mut data = get_ed25519_data(&oracle, &[5u8; RESULT_MESSAGE_LEN], u16::MAX);
+        data[0] = 2;
+
+        assert!(parse_ed25519_instruction(&data).is_err());
+    }
+
+    #[test]
+    fn reject_truncated_data_test() {
+        let oracle = Pubkey::new_from_array([3; 32]);
+
+        let data = get_ed25519_data(&oracle, &[5u8; RESULT_MESSAGE_LEN], u16::MAX);
+
+        assert!(parse_ed25519_instruction(&data[..data.len() - 1]).is_err());
+        assert!(parse_ed25519_instruction(&data[..10]).is_err());
+        assert!(parse_ed25519_instruction(&[]).is_err());
+    }
+}
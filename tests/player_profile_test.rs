@@ -0,0 +1,154 @@
+use solana_sdk::{
+    account::Account, account_info::IntoAccountInfo, program_pack::Pack, pubkey::Pubkey,
+};
+
+pub fn get_account(size: usize, owner: Pubkey) -> Account {
+    Account {
+        lamports: u32::MAX as u64,
+        data: vec![0u8; size],
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metality_game_contract::profile::{find_profile_address, ProfileLoader};
+    use metality_game_contract::state::MetalityPlayerProfileState;
+
+    #[test]
+    fn record_results_test() {
+        let mut profile = MetalityPlayerProfileState::default();
+
+        profile.record_win();
+        profile.record_loss();
+        profile.record_draw();
+        profile.record_cancel();
+
+        assert_eq!(profile.games_played, 3);
+        assert_eq!(profile.wins, 1);
+        assert_eq!(profile.losses, 1);
+        assert_eq!(profile.draws, 1);
+        assert_eq!(profile.cancelled, 1);
+        assert_eq!(profile.nfts_wagered, 3);
+    }
+
+    #[test]
+    fn record_results_saturate_test() {
+        let mut profile = MetalityPlayerProfileState {
+            games_played: u64::MAX,
+            wins: u64::MAX,
+            nfts_wagered: u64::MAX,
+            ..MetalityPlayerProfileState::default()
+        };
+
+        profile.record_win();
+
+        assert_eq!(profile.games_played, u64::MAX);
+        assert_eq!(profile.wins, u64::MAX);
+        assert_eq!(profile.nfts_wagered, u64::MAX);
+    }
+
+    #[test]
+    fn success_load_existing_profile_test() {
+        let program_id = Pubkey::new_from_array([55; 32]);
+        let player = Pubkey::new_from_array([10; 32]);
+        let (profile_pubkey, _) = find_profile_address(&player, &program_id);
+
+        let mut profile_account = get_account(MetalityPlayerProfileState::LEN, program_id);
+        let profile_account_info =
+            (&profile_pubkey, false, &mut profile_account).into_account_info();
+
+        let stored_profile = MetalityPlayerProfileState {
+            is_initialized: true,
+            player,
+            games_played: 4,
+            wins: 4,
+            nfts_wagered: 4,
+            ..MetalityPlayerProfileState::default()
+        };
+
+        ProfileLoader::store(&profile_account_info, stored_profile).unwrap();
+
+        let mut payer_account = get_account(0, solana_sdk::system_program::id());
+        let payer_pubkey = Pubkey::new_from_array([11; 32]);
+        let payer_account_info = (&payer_pubkey, true, &mut payer_account).into_account_info();
+
+        let mut system_account = get_account(0, Pubkey::default());
+        let system_pubkey = solana_sdk::system_program::id();
+        let system_account_info = (&system_pubkey, false, &mut system_account).into_account_info();
+
+        let profile_loader = ProfileLoader {
+            payer: &payer_account_info,
+            system_program_account: &system_account_info,
+            program_id: &program_id,
+        };
+
+        assert_eq!(
+            profile_loader.load(&profile_account_info, &player).unwrap(),
+            stored_profile
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_load_profile_wrong_address_test() {
+        let program_id = Pubkey::new_from_array([55; 32]);
+        let player = Pubkey::new_from_array([10; 32]);
+        let (profile_pubkey, _) =
+            find_profile_address(&Pubkey::new_from_array([12; 32]), &program_id);
+
+        let mut profile_account = get_account(MetalityPlayerProfileState::LEN, program_id);
+        let profile_account_info =
+            (&profile_pubkey, false, &mut profile_account).into_account_info();
+
+        let mut payer_account = get_account(0, solana_sdk::system_program::id());
+        let payer_pubkey = Pubkey::new_from_array([11; 32]);
+        let payer_account_info = (&payer_pubkey, true, &mut payer_account).into_account_info();
+
+        let mut system_account = get_account(0, Pubkey::default());
+        let system_pubkey = solana_sdk::system_program::id();
+        let system_account_info = (&system_pubkey, false, &mut system_account).into_account_info();
+
+        let profile_loader = ProfileLoader {
+            payer: &payer_account_info,
+            system_program_account: &system_account_info,
+            program_id: &program_id,
+        };
+
+        profile_loader.load(&profile_account_info, &player).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_load_profile_wrong_owner_test() {
+        let program_id = Pubkey::new_from_array([55; 32]);
+        let player = Pubkey::new_from_array([10; 32]);
+        let (profile_pubkey, _) = find_profile_address(&player, &program_id);
+
+        let mut profile_account = get_account(
+            MetalityPlayerProfileState::LEN,
+            Pubkey::new_from_array([56; 32]),
+        );
+        let profile_account_info =
+            (&profile_pubkey, false, &mut profile_account).into_account_info();
+
+        let mut payer_account = get_account(0, solana_sdk::system_program::id());
+        let payer_pubkey = Pubkey::new_from_array([11; 32]);
+        let payer_account_info = (&payer_pubkey, true, &mut payer_account).into_account_info();
+
+        let mut system_account = get_account(0, Pubkey::default());
+        let system_pubkey = solana_sdk::system_program::id();
+        let system_account_info = (&system_pubkey, false, &mut system_account).into_account_info();
+
+        let profile_loader = ProfileLoader {
+            payer: &payer_account_info,
+            system_program_account: &system_account_info,
+            program_id: &program_id,
+        };
+
+        profile_loader.load(&profile_account_info, &player).unwrap();
+    }
+}
@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod tests {
+    use metality_game_contract::state::{MetalityGameContractState, MAX_PREVIOUS_OPPONENTS};
+    use solana_program::{program_pack::Pack, pubkey::Pubkey};
+
+    fn get_game_state() -> MetalityGameContractState {
+        MetalityGameContractState::unpack_unchecked(&[0; MetalityGameContractState::LEN]).unwrap()
+    }
+
+    fn get_opponent(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    #[test]
+    fn record_previous_opponent_once_test() {
+        let mut game_state = get_game_state();
+
+        game_state.record_previous_opponent(get_opponent(1));
+        game_state.record_previous_opponent(get_opponent(1));
+        game_state.record_previous_opponent(get_opponent(2));
+
+        assert_eq!(
+            game_state.previous_opponent_index(&get_opponent(1)),
+            Some(0)
+        );
+        assert_eq!(
+            game_state.previous_opponent_index(&get_opponent(2)),
+            Some(1)
+        );
+        assert_eq!(game_state.previous_opponent_index(&Pubkey::default()), None);
+    }
+
+    #[test]
+    fn oldest_previous_opponent_evicted_test() {
+        let mut game_state = get_game_state();
+
+        for seed in 1..=MAX_PREVIOUS_OPPONENTS as u8 + 1 {
+            game_state.record_previous_opponent(get_opponent(seed));
+        }
+
+        assert_eq!(game_state.previous_opponent_index(&get_opponent(1)), None);
+        assert_eq!(
+            game_state.previous_opponent_index(&get_opponent(MAX_PREVIOUS_OPPONENTS as u8 + 1)),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn blocked_opponent_never_evicted_test() {
+        let mut game_state = get_game_state();
+
+        game_state.record_previous_opponent(get_opponent(1));
+        game_state.set_blocked(&get_opponent(1), true).unwrap();
+
+        for seed in 2..=2 * MAX_PREVIOUS_OPPONENTS as u8 {
+            game_state.record_previous_opponent(get_opponent(seed));
+        }
+
+        assert!(game_state.is_blocked(&get_opponent(1)));
+        assert!(!game_state.is_blocked(&get_opponent(2 * MAX_PREVIOUS_OPPONENTS as u8)));
+    }
+
+    #[test]
+    fn unblock_opponent_test() {
+        let mut game_state = get_game_state();
+
+        game_state.record_previous_opponent(get_opponent(1));
+        game_state.set_blocked(&get_opponent(1), true).unwrap();
+        game_state.set_blocked(&get_opponent(1), false).unwrap();
+
+        assert!(!game_state.is_blocked(&get_opponent(1)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_block_unknown_opponent_test() {
+        let mut game_state = get_game_state();
+
+        game_state.set_blocked(&get_opponent(1), true).unwrap();
+    }
+}
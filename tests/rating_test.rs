@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod tests {
+    use metality_game_contract::rating::{elo_update, expected_score, Score, DEFAULT_RATING};
+
+    #[test]
+    fn expected_score_reference_values_test() {
+        // 1 / (1 + 10^(d / 400)) in basis points.
+        assert_eq!(expected_score(1200, 1200), 5000);
+        assert_eq!(expected_score(1200, 1600), 909);
+        assert_eq!(expected_score(1600, 1200), 9091);
+        assert_eq!(expected_score(1500, 1700), 2403);
+        assert_eq!(expected_score(1400, 1500), 3599);
+    }
+
+    #[test]
+    fn expected_score_interpolation_test() {
+        // Exact value for a 110 point gap is 3469.
+        let expected = expected_score(1400, 1510);
+
+        assert!((3460..=3480).contains(&expected));
+        assert_eq!(expected + expected_score(1510, 1400), 10000);
+    }
+
+    #[test]
+    fn expected_score_clamped_test() {
+        assert_eq!(expected_score(0, 3000), 99);
+        assert_eq!(expected_score(3000, 0), 9901);
+    }
+
+    #[test]
+    fn equal_ratings_win_test() {
+        assert_eq!(
+            elo_update(DEFAULT_RATING, DEFAULT_RATING, Score::Win),
+            (1216, 1184)
+        );
+        assert_eq!(
+            elo_update(DEFAULT_RATING, DEFAULT_RATING, Score::Loss),
+            (1184, 1216)
+        );
+    }
+
+    #[test]
+    fn equal_ratings_draw_test() {
+        assert_eq!(
+            elo_update(DEFAULT_RATING, DEFAULT_RATING, Score::Draw),
+            (1200, 1200)
+        );
+    }
+
+    #[test]
+    fn favourite_and_underdog_test() {
+        // Favourite gains 32 * (1 - 0.9091) = 2.9 points.
+        assert_eq!(elo_update(1600, 1200, Score::Win), (1603, 1197));
+
+        // Upset gains 32 * (1 - 0.0909) = 29.1 points.
+        assert_eq!(elo_update(1200, 1600, Score::Win), (1229, 1571));
+
+        // Draw costs the favourite 32 * (0.5 - 0.9091) = -13.1 points.
+        assert_eq!(elo_update(1600, 1200, Score::Draw), (1587, 1213));
+
+        // 32 * (1 - 0.3599) = 20.5 points.
+        assert_eq!(elo_update(1400, 1500, Score::Win), (1420, 1480));
+    }
+
+    #[test]
+    fn rating_floor_test() {
+        assert_eq!(elo_update(5, 5, Score::Loss), (0, 21));
+    }
+}
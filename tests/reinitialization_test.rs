@@ -0,0 +1,200 @@
+#[cfg(test)]
+mod tests {
+    use metality_game_contract::error::MetalityGameContractError;
+    use metality_game_contract::processor::Processor;
+    use metality_game_contract::state::{
+        MetalityGameContractState, MetalityGameProgramDataState, GAME_STATE_SEED_PREFIX,
+        NFT_CHECK_STRICT, PROGRAM_DATA_SEED,
+    };
+    use metality_game_contract::validations::admin;
+    use solana_program::{program_option::COption, program_pack::Pack};
+    use solana_program_test::{processor, BanksClientError, ProgramTest};
+    use solana_sdk::{
+        account::Account,
+        instruction::{AccountMeta, Instruction, InstructionError},
+        pubkey::Pubkey,
+        rent::Rent,
+        signature::{Keypair, Signer},
+        system_program,
+        transaction::{Transaction, TransactionError},
+    };
+    use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+    const GAME_INDEX: u64 = 5;
+
+    fn packed_account<T: Pack>(state: T, owner: Pubkey) -> Account {
+        let mut data = vec![0; T::LEN];
+        T::pack(state, &mut data).unwrap();
+
+        Account {
+            lamports: Rent::default().minimum_balance(T::LEN),
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    /// Runs InitializeGame as a fresh player against program data at
+    /// `GAME_INDEX`, with `game_state` preloaded at the next game address.
+    /// Returns the result and the program data index afterwards.
+    async fn initialize_game(
+        program_id: Pubkey,
+        game_state: Option<Account>,
+    ) -> (Result<(), BanksClientError>, u64) {
+        let mut program_test = ProgramTest::new(
+            "metality_game_contract",
+            program_id,
+            processor!(Processor::unpack_and_process_instruction),
+        );
+
+        let user_a = Keypair::new();
+        program_test.add_account(
+            user_a.pubkey(),
+            Account::new(1_000_000_000, 0, &system_program::id()),
+        );
+
+        let program_data_address =
+            Pubkey::create_with_seed(&admin::id(), PROGRAM_DATA_SEED, &program_id).unwrap();
+        let program_data = MetalityGameProgramDataState {
+            is_initialized: true,
+            index: GAME_INDEX,
+            oracle: Pubkey::default(),
+            join_bond_lamports: 0,
+            bond_forfeit_bps: 0,
+            nft_check_level: NFT_CHECK_STRICT,
+            admin: admin::id(),
+        };
+        program_test.add_account(
+            program_data_address,
+            packed_account(program_data, program_id),
+        );
+
+        let nft_mint = Pubkey::new_unique();
+        program_test.add_account(
+            nft_mint,
+            packed_account(
+                Mint {
+                    mint_authority: COption::None,
+                    supply: 1,
+                    decimals: 0,
+                    is_initialized: true,
+                    freeze_authority: COption::None,
+                },
+                spl_token::id(),
+            ),
+        );
+
+        let nft_ata = Pubkey::new_unique();
+        program_test.add_account(
+            nft_ata,
+            packed_account(
+                TokenAccount {
+                    mint: nft_mint,
+                    owner: user_a.pubkey(),
+                    amount: 1,
+                    state: AccountState::Initialized,
+                    ..TokenAccount::default()
+                },
+                spl_token::id(),
+            ),
+        );
+
+        let game_state_address = Pubkey::create_with_seed(
+            &user_a.pubkey(),
+            &program_data.indexed_seed(GAME_STATE_SEED_PREFIX).unwrap(),
+            &program_id,
+        )
+        .unwrap();
+
+        if let Some(game_state) = game_state {
+            program_test.add_account(game_state_address, game_state);
+        }
+
+        let (pda, _bump_seeds) = Pubkey::find_program_address(
+            &[
+                "metality_game_contract".as_bytes(),
+                game_state_address.as_ref(),
+            ],
+            &program_id,
+        );
+
+        let (mut banks_client, _payer, recent_blockhash) = program_test.start().await;
+
+        let transaction = Transaction::new_signed_with_payer(
+            &[Instruction::new_with_bytes(
+                program_id,
+                &[1],
+                vec![
+                    AccountMeta::new(user_a.pubkey(), true),
+                    AccountMeta::new(nft_ata, false),
+                    AccountMeta::new_readonly(nft_mint, false),
+                    AccountMeta::new_readonly(pda, false),
+                    AccountMeta::new(game_state_address, false),
+                    AccountMeta::new(program_data_address, false),
+                    AccountMeta::new_readonly(spl_token::id(), false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            )],
+            Some(&user_a.pubkey()),
+            &[&user_a],
+            recent_blockhash,
+        );
+
+        let result = banks_client.process_transaction(transaction).await;
+
+        let program_data = banks_client
+            .get_account(program_data_address)
+            .await
+            .unwrap()
+            .unwrap();
+
+        (
+            result,
+            MetalityGameProgramDataState::unpack(&program_data.data)
+                .unwrap()
+                .index,
+        )
+    }
+
+    #[tokio::test]
+    async fn initialize_game_test() {
+        let (result, index) = initialize_game(Pubkey::new_unique(), None).await;
+
+        result.unwrap();
+        assert_eq!(index, GAME_INDEX + 1);
+    }
+
+    #[tokio::test]
+    async fn initialize_pre_funded_game_test() {
+        let (result, index) = initialize_game(
+            Pubkey::new_unique(),
+            Some(Account::new(1, 0, &system_program::id())),
+        )
+        .await;
+
+        result.unwrap();
+        assert_eq!(index, GAME_INDEX + 1);
+    }
+
+    #[tokio::test]
+    async fn reinitialize_game_test() {
+        let mut game_state =
+            MetalityGameContractState::unpack_unchecked(&[0; MetalityGameContractState::LEN])
+                .unwrap();
+        game_state.is_initialized = true;
+
+        let program_id = Pubkey::new_unique();
+        let (result, index) =
+            initialize_game(program_id, Some(packed_account(game_state, program_id))).await;
+
+        assert_eq!(
+            result.unwrap_err().unwrap(),
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(MetalityGameContractError::AlreadyInitialized as u32)
+            )
+        );
+        assert_eq!(index, GAME_INDEX);
+    }
+}
@@ -0,0 +1,162 @@
+#[cfg(test)]
+mod tests {
+    use metality_game_contract::processor::Processor;
+    use metality_game_contract::profile::find_profile_address;
+    use metality_game_contract::state::{MetalityGameContractState, MetalityPlayerProfileState};
+    use solana_program::{program_option::COption, program_pack::Pack};
+    use solana_program_test::{processor, ProgramTest};
+    use solana_sdk::{
+        account::Account,
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        rent::Rent,
+        signature::{Keypair, Signer},
+        system_program,
+        transaction::Transaction,
+    };
+    use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+    fn packed_account<T: Pack>(state: T, owner: Pubkey) -> Account {
+        let mut data = vec![0; T::LEN];
+        T::pack(state, &mut data).unwrap();
+
+        Account {
+            lamports: Rent::default().minimum_balance(T::LEN),
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn profile_funded_with_cluster_rent_test() {
+        let program_id = Pubkey::new_unique();
+
+        let mut program_test = ProgramTest::new(
+            "metality_game_contract",
+            program_id,
+            processor!(Processor::unpack_and_process_instruction),
+        );
+
+        let player = Keypair::new();
+        program_test.add_account(
+            player.pubkey(),
+            Account::new(1_000_000_000, 0, &system_program::id()),
+        );
+
+        let game_state_address = Pubkey::new_unique();
+        let (pda, _bump_seeds) = Pubkey::find_program_address(
+            &[
+                "metality_game_contract".as_bytes(),
+                game_state_address.as_ref(),
+            ],
+            &program_id,
+        );
+
+        let nft_mint = Pubkey::new_unique();
+        program_test.add_account(
+            nft_mint,
+            packed_account(
+                Mint {
+                    mint_authority: COption::None,
+                    supply: 1,
+                    decimals: 0,
+                    is_initialized: true,
+                    freeze_authority: COption::None,
+                },
+                spl_token::id(),
+            ),
+        );
+
+        let nft_ata = Pubkey::new_unique();
+        program_test.add_account(
+            nft_ata,
+            packed_account(
+                TokenAccount {
+                    mint: nft_mint,
+                    owner: pda,
+                    amount: 1,
+                    state: AccountState::Initialized,
+                    ..TokenAccount::default()
+                },
+                spl_token::id(),
+            ),
+        );
+
+        let mut game_state =
+            MetalityGameContractState::unpack_unchecked(&[0; MetalityGameContractState::LEN])
+                .unwrap();
+        game_state.is_initialized = true;
+        game_state.user_a = player.pubkey();
+        game_state.a_nft_ata = nft_ata;
+        game_state.a_nft_mint = nft_mint;
+        game_state.pda_account = pda;
+        game_state.max_opponent_rating = u32::MAX;
+        program_test.add_account(game_state_address, packed_account(game_state, program_id));
+
+        let mut context = program_test.start_with_context().await;
+
+        // Twice the default rate, so funding from `Rent::default()` would
+        // leave the profile short of rent exemption.
+        let rent = Rent {
+            lamports_per_byte_year: Rent::default().lamports_per_byte_year * 2,
+            ..Rent::default()
+        };
+
+        context.set_sysvar(&rent);
+
+        let (profile_address, _bump_seeds) = find_profile_address(&player.pubkey(), &program_id);
+        let user_b = Pubkey::default();
+
+        // Cancelling the game records it on the player's new profile.
+        let transaction = Transaction::new_signed_with_payer(
+            &[Instruction::new_with_bytes(
+                program_id,
+                &[2],
+                vec![
+                    AccountMeta::new(player.pubkey(), true),
+                    AccountMeta::new(nft_ata, false),
+                    AccountMeta::new_readonly(nft_mint, false),
+                    AccountMeta::new(user_b, false),
+                    AccountMeta::new(user_b, false),
+                    AccountMeta::new_readonly(user_b, false),
+                    AccountMeta::new_readonly(pda, false),
+                    AccountMeta::new(game_state_address, false),
+                    AccountMeta::new_readonly(spl_token::id(), false),
+                    AccountMeta::new(profile_address, false),
+                    AccountMeta::new(find_profile_address(&user_b, &program_id).0, false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            )],
+            Some(&player.pubkey()),
+            &[&player],
+            context.last_blockhash,
+        );
+
+        context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+
+        let profile_account = context
+            .banks_client
+            .get_account(profile_address)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            profile_account.lamports,
+            rent.minimum_balance(MetalityPlayerProfileState::LEN)
+        );
+        assert!(rent.is_exempt(profile_account.lamports, profile_account.data.len()));
+        assert_eq!(
+            MetalityPlayerProfileState::unpack(&profile_account.data)
+                .unwrap()
+                .cancelled,
+            1
+        );
+    }
+}
@@ -0,0 +1,304 @@
+#[cfg(test)]
+mod tests {
+    use metality_game_contract::error::MetalityGameContractError;
+    use metality_game_contract::processor::Processor;
+    use metality_game_contract::profile::find_profile_address;
+    use metality_game_contract::rating::DEFAULT_RATING;
+    use metality_game_contract::state::{
+        MetalityGameContractState, MetalityPlayerProfileState, GAME_OUTCOME_FORFEIT,
+    };
+    use solana_program::{program_option::COption, program_pack::Pack};
+    use solana_program_test::{processor, BanksClientError, ProgramTest};
+    use solana_sdk::{
+        account::Account,
+        instruction::{AccountMeta, Instruction, InstructionError},
+        pubkey::Pubkey,
+        rent::Rent,
+        signature::{Keypair, Signer},
+        system_program, sysvar,
+        transaction::{Transaction, TransactionError},
+    };
+    use spl_associated_token_account::get_associated_token_address;
+    use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+    fn packed_account<T: Pack>(state: T, owner: Pubkey) -> Account {
+        let mut data = vec![0; T::LEN];
+        T::pack(state, &mut data).unwrap();
+
+        Account {
+            lamports: Rent::default().minimum_balance(T::LEN),
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    fn nft_mint() -> Account {
+        packed_account(
+            Mint {
+                mint_authority: COption::None,
+                supply: 1,
+                decimals: 0,
+                is_initialized: true,
+                freeze_authority: COption::None,
+            },
+            spl_token::id(),
+        )
+    }
+
+    fn staked_nft(mint: Pubkey, pda: Pubkey) -> Account {
+        packed_account(
+            TokenAccount {
+                mint,
+                owner: pda,
+                amount: 1,
+                state: AccountState::Initialized,
+                ..TokenAccount::default()
+            },
+            spl_token::id(),
+        )
+    }
+
+    /// Has user A of a started game resign, naming themselves as the winner
+    /// when `name_self` is set and user B otherwise, and then has the named
+    /// winner claim the reward when `claim` is set. User A opts into
+    /// self-claim first when `self_claim` is set. Returns both results, the
+    /// game state and the named winner's ATA for the won NFT afterwards.
+    async fn resign(
+        name_self: bool,
+        claim: bool,
+        self_claim: bool,
+    ) -> (
+        Result<(), BanksClientError>,
+        Option<Result<(), BanksClientError>>,
+        MetalityGameContractState,
+        Option<Account>,
+    ) {
+        let program_id = Pubkey::new_unique();
+
+        let mut program_test = ProgramTest::new(
+            "metality_game_contract",
+            program_id,
+            processor!(Processor::unpack_and_process_instruction),
+        );
+
+        let user_a = Keypair::new();
+        let user_b = Keypair::new();
+        for user in [&user_a, &user_b] {
+            program_test.add_account(
+                user.pubkey(),
+                Account::new(1_000_000_000, 0, &system_program::id()),
+            );
+        }
+
+        let game_state_address = Pubkey::new_unique();
+        let (pda, _bump_seeds) = Pubkey::find_program_address(
+            &[
+                "metality_game_contract".as_bytes(),
+                game_state_address.as_ref(),
+            ],
+            &program_id,
+        );
+
+        let a_nft_mint = Pubkey::new_unique();
+        let a_nft_ata = Pubkey::new_unique();
+        let b_nft_mint = Pubkey::new_unique();
+        let b_nft_ata = Pubkey::new_unique();
+        program_test.add_account(a_nft_mint, nft_mint());
+        program_test.add_account(b_nft_mint, nft_mint());
+        program_test.add_account(a_nft_ata, staked_nft(a_nft_mint, pda));
+        program_test.add_account(b_nft_ata, staked_nft(b_nft_mint, pda));
+
+        let mut game_state =
+            MetalityGameContractState::unpack_unchecked(&[0; MetalityGameContractState::LEN])
+                .unwrap();
+        game_state.is_initialized = true;
+        game_state.user_a = user_a.pubkey();
+        game_state.a_nft_ata = a_nft_ata;
+        game_state.a_nft_mint = a_nft_mint;
+        game_state.user_b = user_b.pubkey();
+        game_state.user_b_joined = true;
+        game_state.b_nft_ata = b_nft_ata;
+        game_state.b_nft_mint = b_nft_mint;
+        game_state.pda_account = pda;
+        game_state.game_started = true;
+        game_state.max_opponent_rating = u32::MAX;
+        program_test.add_account(game_state_address, packed_account(game_state, program_id));
+
+        if self_claim {
+            program_test.add_account(
+                find_profile_address(&user_a.pubkey(), &program_id).0,
+                packed_account(
+                    MetalityPlayerProfileState {
+                        is_initialized: true,
+                        player: user_a.pubkey(),
+                        rating: DEFAULT_RATING,
+                        self_claim,
+                        ..MetalityPlayerProfileState::default()
+                    },
+                    program_id,
+                ),
+            );
+        }
+
+        // Resigning to yourself names you as both the loser and the winner.
+        let (winner, loser, won_nft, won_nft_mint, owned_nft, owned_nft_mint) = if name_self {
+            (
+                &user_a, &user_b, b_nft_ata, b_nft_mint, a_nft_ata, a_nft_mint,
+            )
+        } else {
+            (
+                &user_b, &user_a, a_nft_ata, a_nft_mint, b_nft_ata, b_nft_mint,
+            )
+        };
+        let winner_won_nft_ata = get_associated_token_address(&winner.pubkey(), &won_nft_mint);
+
+        let (mut banks_client, _payer, recent_blockhash) = program_test.start().await;
+
+        let transaction = Transaction::new_signed_with_payer(
+            &[Instruction::new_with_bytes(
+                program_id,
+                &[34],
+                vec![
+                    AccountMeta::new_readonly(user_a.pubkey(), true),
+                    AccountMeta::new_readonly(winner.pubkey(), false),
+                    AccountMeta::new(game_state_address, false),
+                ],
+            )],
+            Some(&user_a.pubkey()),
+            &[&user_a],
+            recent_blockhash,
+        );
+
+        let result = banks_client.process_transaction(transaction).await;
+
+        let claim_result = if claim {
+            let transaction = Transaction::new_signed_with_payer(
+                &[Instruction::new_with_bytes(
+                    program_id,
+                    &[30],
+                    vec![
+                        AccountMeta::new(winner.pubkey(), true),
+                        AccountMeta::new(winner.pubkey(), true),
+                        AccountMeta::new(loser.pubkey(), false),
+                        AccountMeta::new(won_nft, false),
+                        AccountMeta::new_readonly(won_nft_mint, false),
+                        AccountMeta::new(owned_nft, false),
+                        AccountMeta::new_readonly(owned_nft_mint, false),
+                        AccountMeta::new(winner_won_nft_ata, false),
+                        AccountMeta::new_readonly(pda, false),
+                        AccountMeta::new(game_state_address, false),
+                        AccountMeta::new_readonly(system_program::id(), false),
+                        AccountMeta::new_readonly(spl_token::id(), false),
+                        AccountMeta::new_readonly(sysvar::rent::id(), false),
+                        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                        AccountMeta::new(
+                            find_profile_address(&winner.pubkey(), &program_id).0,
+                            false,
+                        ),
+                        AccountMeta::new(
+                            find_profile_address(&loser.pubkey(), &program_id).0,
+                            false,
+                        ),
+                    ],
+                )],
+                Some(&winner.pubkey()),
+                &[winner],
+                recent_blockhash,
+            );
+
+            Some(banks_client.process_transaction(transaction).await)
+        } else {
+            None
+        };
+
+        let game_state = banks_client
+            .get_account(game_state_address)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let winner_won_nft = banks_client.get_account(winner_won_nft_ata).await.unwrap();
+
+        (
+            result,
+            claim_result,
+            MetalityGameContractState::unpack(&game_state.data).unwrap(),
+            winner_won_nft,
+        )
+    }
+
+    fn assert_error(result: Result<(), BanksClientError>, error: MetalityGameContractError) {
+        assert_eq!(
+            result.unwrap_err().unwrap(),
+            TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+        );
+    }
+
+    #[tokio::test]
+    async fn resign_to_opponent_test() {
+        let (result, _claim_result, game_state, winner_won_nft) = resign(false, false, false).await;
+
+        result.unwrap();
+        assert!(!game_state.expired);
+        assert_eq!(game_state.winner, game_state.user_b);
+        assert_eq!(game_state.outcome, GAME_OUTCOME_FORFEIT);
+        assert!(winner_won_nft.is_none());
+    }
+
+    #[tokio::test]
+    async fn resign_to_opponent_and_claim_test() {
+        let (result, claim_result, game_state, winner_won_nft) = resign(false, true, false).await;
+
+        result.unwrap();
+        claim_result.unwrap().unwrap();
+        assert!(game_state.expired);
+        assert_eq!(game_state.winner, game_state.user_b);
+        assert_eq!(game_state.outcome, GAME_OUTCOME_FORFEIT);
+        assert_eq!(
+            TokenAccount::unpack(&winner_won_nft.unwrap().data)
+                .unwrap()
+                .amount,
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn resign_to_self_test() {
+        let (result, _claim_result, game_state, winner_won_nft) = resign(true, false, false).await;
+
+        assert_error(result, MetalityGameContractError::InvalidWinner);
+        assert!(!game_state.expired);
+        assert!(!game_state.has_recorded_winner());
+        assert!(winner_won_nft.is_none());
+    }
+
+    #[tokio::test]
+    async fn resign_to_self_and_claim_test() {
+        let (result, claim_result, game_state, winner_won_nft) = resign(true, true, false).await;
+
+        assert_error(result, MetalityGameContractError::InvalidWinner);
+        assert_error(
+            claim_result.unwrap(),
+            MetalityGameContractError::WinnerNotRecorded,
+        );
+        assert!(!game_state.expired);
+        assert!(!game_state.has_recorded_winner());
+        assert!(winner_won_nft.is_none());
+    }
+
+    #[tokio::test]
+    async fn resign_to_self_with_self_claim_test() {
+        let (result, claim_result, game_state, winner_won_nft) = resign(true, true, true).await;
+
+        assert_error(result, MetalityGameContractError::InvalidWinner);
+        assert_error(
+            claim_result.unwrap(),
+            MetalityGameContractError::WinnerNotRecorded,
+        );
+        assert!(!game_state.expired);
+        assert!(!game_state.has_recorded_winner());
+        assert!(winner_won_nft.is_none());
+    }
+}
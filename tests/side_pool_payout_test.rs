@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod tests {
+    use metality_game_contract::state::{
+        MetalitySidePoolState, POOL_OUTCOME_PENDING, POOL_OUTCOME_REFUND, SIDE_USER_A, SIDE_USER_B,
+    };
+    use solana_program::pubkey::Pubkey;
+
+    fn get_side_pool(total_a: u64, total_b: u64, outcome: u8) -> MetalitySidePoolState {
+        MetalitySidePoolState {
+            is_initialized: true,
+            game_state: Pubkey::new_from_array([1; 32]),
+            token_mint: Pubkey::new_from_array([2; 32]),
+            vault: Pubkey::new_from_array([3; 32]),
+            total_a,
+            total_b,
+            locked: true,
+            outcome,
+        }
+    }
+
+    #[test]
+    fn winning_side_shares_losing_side_test() {
+        let side_pool = get_side_pool(300, 600, SIDE_USER_A);
+
+        assert_eq!(side_pool.payout(SIDE_USER_A, 100), 300);
+        assert_eq!(side_pool.payout(SIDE_USER_A, 200), 600);
+        assert_eq!(side_pool.payout(SIDE_USER_B, 600), 0);
+    }
+
+    #[test]
+    fn payouts_never_exceed_pool_test() {
+        let side_pool = get_side_pool(3, 10, SIDE_USER_B);
+
+        let paid: u64 = [3, 3, 4]
+            .iter()
+            .map(|amount| side_pool.payout(SIDE_USER_B, *amount))
+            .sum();
+
+        assert!(paid <= 13);
+        assert_eq!(side_pool.payout(SIDE_USER_B, 3), 3);
+    }
+
+    #[test]
+    fn refund_outcome_returns_stake_test() {
+        let side_pool = get_side_pool(300, 600, POOL_OUTCOME_REFUND);
+
+        assert_eq!(side_pool.payout(SIDE_USER_A, 100), 100);
+        assert_eq!(side_pool.payout(SIDE_USER_B, 600), 600);
+    }
+
+    #[test]
+    fn unbacked_winner_refunds_everyone_test() {
+        let side_pool = get_side_pool(0, 600, SIDE_USER_A);
+
+        assert_eq!(side_pool.payout(SIDE_USER_B, 600), 600);
+    }
+
+    #[test]
+    fn pending_pool_pays_nothing_test() {
+        let side_pool = get_side_pool(300, 600, POOL_OUTCOME_PENDING);
+
+        assert!(!side_pool.is_settled());
+        assert_eq!(side_pool.payout(SIDE_USER_A, 100), 0);
+    }
+}
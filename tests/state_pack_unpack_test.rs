@@ -1,22 +1,44 @@
 #[cfg(test)]
 mod tests {
-    use metality_game_contract::state::{MetalityGameContractState, MetalityGameProgramDataState};
+    use metality_game_contract::state::{
+        MetalityGameContractState, MetalityGameProgramDataState, MetalityLobbyEntrant,
+        MetalityLobbyState, MetalityPlayerProfileState, MetalityQueueEntry, MetalityQueueState,
+        MetalitySideBetState, MetalitySidePoolState, MetalityTournamentState, GAME_OUTCOME_WON,
+        MAX_LOBBY_PLAYERS, MAX_PREVIOUS_OPPONENTS, MAX_QUEUE_ENTRIES, MAX_TOURNAMENT_MATCHES,
+        NFT_CHECK_STRICT, NO_ENTRANT, SIDE_USER_A, SIDE_USER_B,
+    };
     use solana_program::{program_pack::Pack, pubkey::Pubkey};
 
     #[test]
     fn game_state_pack_unpack_test() {
+        let mut previous_opponents = [Pubkey::default(); MAX_PREVIOUS_OPPONENTS];
+        previous_opponents[0] = Pubkey::new_from_array([20; 32]);
+        previous_opponents[1] = Pubkey::new_from_array([21; 32]);
+
         let game_state = MetalityGameContractState {
             is_initialized: true,
-            user_a: Pubkey::new(&[1; 32]),
-            a_nft_ata: Pubkey::new(&[2; 32]),
-            a_nft_mint: Pubkey::new(&[3; 32]),
-            user_b: Pubkey::new(&[4; 32]),
+            user_a: Pubkey::new_from_array([1; 32]),
+            a_nft_ata: Pubkey::new_from_array([2; 32]),
+            a_nft_mint: Pubkey::new_from_array([3; 32]),
+            user_b: Pubkey::new_from_array([4; 32]),
             user_b_joined: true,
-            b_nft_ata: Pubkey::new(&[5; 32]),
-            b_nft_mint: Pubkey::new(&[6; 32]),
-            pda_account: Pubkey::new(&[7; 32]),
+            b_nft_ata: Pubkey::new_from_array([5; 32]),
+            b_nft_mint: Pubkey::new_from_array([6; 32]),
+            pda_account: Pubkey::new_from_array([7; 32]),
             expired: false,
             game_started: true,
+            min_opponent_rating: 1100,
+            max_opponent_rating: 1400,
+            side_pool: Pubkey::new_from_array([8; 32]),
+            winner: Pubkey::new_from_array([4; 32]),
+            cancel_proposer: Pubkey::new_from_array([1; 32]),
+            outcome: GAME_OUTCOME_WON,
+            join_bond: 5_000,
+            bond_forfeit_bps: 2_500,
+            previous_opponents,
+            blocked_opponents: 0b10,
+            next_previous_opponent: 2,
+            exit_count: 3,
         };
 
         let mut packed = vec![0; MetalityGameContractState::get_packed_len()];
@@ -33,6 +55,11 @@ mod tests {
         let program_data_state = MetalityGameProgramDataState {
             is_initialized: true,
             index: 1,
+            oracle: Pubkey::new_from_array([9; 32]),
+            join_bond_lamports: 5_000,
+            bond_forfeit_bps: 10_000,
+            nft_check_level: NFT_CHECK_STRICT,
+            admin: Pubkey::new_from_array([8; 32]),
         };
 
         let mut packed = vec![0; MetalityGameProgramDataState::get_packed_len()];
@@ -43,4 +70,185 @@ mod tests {
 
         assert_eq!(program_data_state, unpacked_data);
     }
+
+    #[test]
+    fn lobby_state_pack_unpack_test() {
+        let mut entrants = [MetalityLobbyEntrant::default(); MAX_LOBBY_PLAYERS];
+
+        for (index, entrant) in entrants.iter_mut().take(3).enumerate() {
+            let index = index as u8;
+
+            *entrant = MetalityLobbyEntrant {
+                user: Pubkey::new_from_array([10 + index; 32]),
+                nft_ata: Pubkey::new_from_array([20 + index; 32]),
+                nft_mint: Pubkey::new_from_array([30 + index; 32]),
+            };
+        }
+
+        let mut payout_table = [0u8; MAX_LOBBY_PLAYERS];
+        payout_table[0] = 3;
+        payout_table[1] = 1;
+
+        let lobby_state = MetalityLobbyState {
+            is_initialized: true,
+            creator: Pubkey::new_from_array([10; 32]),
+            pda_account: Pubkey::new_from_array([7; 32]),
+            max_players: 4,
+            player_count: 3,
+            expired: false,
+            game_started: true,
+            payout_table,
+            entrants,
+            recipients: MetalityLobbyEntrant::own_stakes(),
+            released: 0b101,
+        };
+
+        let mut packed = vec![0; MetalityLobbyState::get_packed_len()];
+
+        MetalityLobbyState::pack(lobby_state, &mut packed).unwrap();
+
+        let unpacked_data = MetalityLobbyState::unpack(&packed).unwrap();
+
+        assert_eq!(lobby_state, unpacked_data);
+    }
+
+    #[test]
+    fn tournament_state_pack_unpack_test() {
+        let mut entrants = [MetalityLobbyEntrant::default(); MAX_LOBBY_PLAYERS];
+
+        for (index, entrant) in entrants.iter_mut().take(4).enumerate() {
+            let index = index as u8;
+
+            *entrant = MetalityLobbyEntrant {
+                user: Pubkey::new_from_array([10 + index; 32]),
+                nft_ata: Pubkey::new_from_array([20 + index; 32]),
+                nft_mint: Pubkey::new_from_array([30 + index; 32]),
+            };
+        }
+
+        let mut match_games = [Pubkey::default(); MAX_TOURNAMENT_MATCHES];
+        match_games[0] = Pubkey::new_from_array([40; 32]);
+        match_games[1] = Pubkey::new_from_array([41; 32]);
+
+        let mut match_winners = [NO_ENTRANT; MAX_TOURNAMENT_MATCHES];
+        match_winners[0] = 1;
+
+        let tournament_state = MetalityTournamentState {
+            is_initialized: true,
+            pda_account: Pubkey::new_from_array([7; 32]),
+            capacity: 4,
+            entrant_count: 4,
+            started: true,
+            expired: false,
+            champion: NO_ENTRANT,
+            entrants,
+            match_games,
+            match_winners,
+            recipients: [1; MAX_LOBBY_PLAYERS],
+            released: 0b11,
+        };
+
+        let mut packed = vec![0; MetalityTournamentState::get_packed_len()];
+
+        MetalityTournamentState::pack(tournament_state, &mut packed).unwrap();
+
+        let unpacked_data = MetalityTournamentState::unpack(&packed).unwrap();
+
+        assert_eq!(tournament_state, unpacked_data);
+    }
+
+    #[test]
+    fn player_profile_state_pack_unpack_test() {
+        let profile_state = MetalityPlayerProfileState {
+            is_initialized: true,
+            player: Pubkey::new_from_array([1; 32]),
+            games_played: 10,
+            wins: 5,
+            losses: 3,
+            draws: 2,
+            cancelled: 1,
+            nfts_wagered: 10,
+            rating: 1216,
+            self_claim: true,
+        };
+
+        let mut packed = vec![0; MetalityPlayerProfileState::get_packed_len()];
+
+        MetalityPlayerProfileState::pack(profile_state, &mut packed).unwrap();
+
+        let unpacked_data = MetalityPlayerProfileState::unpack(&packed).unwrap();
+
+        assert_eq!(profile_state, unpacked_data);
+    }
+
+    #[test]
+    fn queue_state_pack_unpack_test() {
+        let mut entries = [MetalityQueueEntry::default(); MAX_QUEUE_ENTRIES];
+
+        entries[0] = MetalityQueueEntry {
+            user: Pubkey::new_from_array([1; 32]),
+            nft_ata: Pubkey::new_from_array([2; 32]),
+            nft_mint: Pubkey::new_from_array([3; 32]),
+            rating: 1250,
+            min_opponent_rating: 1100,
+            max_opponent_rating: 1400,
+        };
+
+        let queue_state = MetalityQueueState {
+            is_initialized: true,
+            pda_account: Pubkey::new_from_array([7; 32]),
+            entry_count: 1,
+            entries,
+        };
+
+        let mut packed = vec![0; MetalityQueueState::get_packed_len()];
+
+        MetalityQueueState::pack(queue_state, &mut packed).unwrap();
+
+        let unpacked_data = MetalityQueueState::unpack(&packed).unwrap();
+
+        assert_eq!(queue_state, unpacked_data);
+    }
+
+    #[test]
+    fn side_pool_state_pack_unpack_test() {
+        let side_pool_state = MetalitySidePoolState {
+            is_initialized: true,
+            game_state: Pubkey::new_from_array([1; 32]),
+            token_mint: Pubkey::new_from_array([2; 32]),
+            vault: Pubkey::new_from_array([3; 32]),
+            total_a: 1_500,
+            total_b: 250,
+            locked: true,
+            outcome: SIDE_USER_B,
+        };
+
+        let mut packed = vec![0; MetalitySidePoolState::get_packed_len()];
+
+        MetalitySidePoolState::pack(side_pool_state, &mut packed).unwrap();
+
+        let unpacked_data = MetalitySidePoolState::unpack(&packed).unwrap();
+
+        assert_eq!(side_pool_state, unpacked_data);
+    }
+
+    #[test]
+    fn side_bet_state_pack_unpack_test() {
+        let side_bet_state = MetalitySideBetState {
+            is_initialized: true,
+            side_pool: Pubkey::new_from_array([1; 32]),
+            bettor: Pubkey::new_from_array([2; 32]),
+            side: SIDE_USER_A,
+            amount: 42,
+            claimed: true,
+        };
+
+        let mut packed = vec![0; MetalitySideBetState::get_packed_len()];
+
+        MetalitySideBetState::pack(side_bet_state, &mut packed).unwrap();
+
+        let unpacked_data = MetalitySideBetState::unpack(&packed).unwrap();
+
+        assert_eq!(side_bet_state, unpacked_data);
+    }
 }
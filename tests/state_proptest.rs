@@ -0,0 +1,159 @@
+#[cfg(test)]
+mod tests {
+    use metality_game_contract::instruction::MetalityGameContractInstruction;
+    use metality_game_contract::state::{
+        MetalityGameContractState, MetalityGameProgramDataState, MAX_PREVIOUS_OPPONENTS,
+    };
+    use proptest::prelude::*;
+    use solana_program::{program_pack::Pack, pubkey::Pubkey};
+
+    fn pubkey() -> impl Strategy<Value = Pubkey> {
+        any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+    }
+
+    prop_compose! {
+        fn game_state()(
+            (is_initialized, user_a, a_nft_ata, a_nft_mint, user_b, user_b_joined, b_nft_ata, b_nft_mint) in (
+                any::<bool>(),
+                pubkey(),
+                pubkey(),
+                pubkey(),
+                pubkey(),
+                any::<bool>(),
+                pubkey(),
+                pubkey(),
+            ),
+            (pda_account, expired, game_started, min_opponent_rating, max_opponent_rating, side_pool, winner, cancel_proposer) in (
+                pubkey(),
+                any::<bool>(),
+                any::<bool>(),
+                any::<u32>(),
+                any::<u32>(),
+                pubkey(),
+                pubkey(),
+                pubkey(),
+            ),
+            (outcome, join_bond, bond_forfeit_bps, previous_opponents, blocked_opponents, next_previous_opponent, exit_count) in (
+                any::<u8>(),
+                any::<u64>(),
+                any::<u16>(),
+                prop::array::uniform8(pubkey()),
+                any::<u8>(),
+                0..MAX_PREVIOUS_OPPONENTS as u8,
+                any::<u16>(),
+            ),
+        ) -> MetalityGameContractState {
+            MetalityGameContractState {
+                is_initialized,
+                user_a,
+                a_nft_ata,
+                a_nft_mint,
+                user_b,
+                user_b_joined,
+                b_nft_ata,
+                b_nft_mint,
+                pda_account,
+                expired,
+                game_started,
+                min_opponent_rating,
+                max_opponent_rating,
+                side_pool,
+                winner,
+                cancel_proposer,
+                outcome,
+                join_bond,
+                bond_forfeit_bps,
+                previous_opponents,
+                blocked_opponents,
+                next_previous_opponent,
+                exit_count,
+            }
+        }
+    }
+
+    prop_compose! {
+        fn program_data_state()(
+            is_initialized in any::<bool>(),
+            index in any::<u64>(),
+            oracle in pubkey(),
+            join_bond_lamports in any::<u64>(),
+            bond_forfeit_bps in any::<u16>(),
+            nft_check_level in any::<u8>(),
+            admin in pubkey(),
+        ) -> MetalityGameProgramDataState {
+            MetalityGameProgramDataState {
+                is_initialized,
+                index,
+                oracle,
+                join_bond_lamports,
+                bond_forfeit_bps,
+                nft_check_level,
+                admin,
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn game_state_round_trip(game_state in game_state()) {
+            let mut packed = vec![0; MetalityGameContractState::LEN];
+            game_state.pack_into_slice(&mut packed);
+
+            prop_assert_eq!(MetalityGameContractState::unpack_from_slice(&packed)?, game_state);
+        }
+
+        #[test]
+        fn game_state_corrupted_byte(
+            game_state in game_state(),
+            index in 0..MetalityGameContractState::LEN,
+            byte in any::<u8>(),
+        ) {
+            let mut packed = vec![0; MetalityGameContractState::LEN];
+            game_state.pack_into_slice(&mut packed);
+            packed[index] = byte;
+
+            // A corrupted encoding is either rejected or the only encoding of
+            // the state it decodes to.
+            if let Ok(corrupted_state) = MetalityGameContractState::unpack_from_slice(&packed) {
+                let mut repacked = vec![0; MetalityGameContractState::LEN];
+                corrupted_state.pack_into_slice(&mut repacked);
+
+                prop_assert_eq!(repacked, packed);
+            }
+        }
+
+        #[test]
+        fn program_data_state_round_trip(program_data_state in program_data_state()) {
+            let mut packed = vec![0; MetalityGameProgramDataState::LEN];
+            program_data_state.pack_into_slice(&mut packed);
+
+            prop_assert_eq!(
+                MetalityGameProgramDataState::unpack_from_slice(&packed)?,
+                program_data_state
+            );
+        }
+
+        #[test]
+        fn program_data_state_corrupted_byte(
+            program_data_state in program_data_state(),
+            index in 0..MetalityGameProgramDataState::LEN,
+            byte in any::<u8>(),
+        ) {
+            let mut packed = vec![0; MetalityGameProgramDataState::LEN];
+            program_data_state.pack_into_slice(&mut packed);
+            packed[index] = byte;
+
+            if let Ok(corrupted_state) = MetalityGameProgramDataState::unpack_from_slice(&packed) {
+                let mut repacked = vec![0; MetalityGameProgramDataState::LEN];
+                corrupted_state.pack_into_slice(&mut repacked);
+
+                prop_assert_eq!(repacked, packed);
+            }
+        }
+
+        #[test]
+        fn instruction_data_unpack_never_panics(bytes in prop::collection::vec(any::<u8>(), 0..256)) {
+            let _ = MetalityGameContractInstruction::unpack_instruction_data(&bytes);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod tests {
+    use metality_game_contract::state::{
+        MetalityLobbyEntrant, MetalityTournamentState, MAX_LOBBY_PLAYERS, MAX_TOURNAMENT_MATCHES,
+        NO_ENTRANT,
+    };
+    use solana_program::pubkey::Pubkey;
+
+    fn get_tournament(capacity: u8) -> MetalityTournamentState {
+        MetalityTournamentState {
+            is_initialized: true,
+            pda_account: Pubkey::new_from_array([7; 32]),
+            capacity,
+            entrant_count: capacity,
+            started: true,
+            expired: false,
+            champion: NO_ENTRANT,
+            entrants: [MetalityLobbyEntrant::default(); MAX_LOBBY_PLAYERS],
+            match_games: [Pubkey::default(); MAX_TOURNAMENT_MATCHES],
+            match_winners: [NO_ENTRANT; MAX_TOURNAMENT_MATCHES],
+            recipients: [0; MAX_LOBBY_PLAYERS],
+            released: 0,
+        }
+    }
+
+    #[test]
+    fn valid_capacity_test() {
+        assert!(MetalityTournamentState::is_valid_capacity(2));
+        assert!(MetalityTournamentState::is_valid_capacity(8));
+        assert!(MetalityTournamentState::is_valid_capacity(16));
+        assert!(!MetalityTournamentState::is_valid_capacity(0));
+        assert!(!MetalityTournamentState::is_valid_capacity(1));
+        assert!(!MetalityTournamentState::is_valid_capacity(6));
+        assert!(!MetalityTournamentState::is_valid_capacity(32));
+    }
+
+    #[test]
+    fn first_round_pairings_test() {
+        let tournament = get_tournament(8);
+
+        assert_eq!(tournament.match_count(), 7);
+        assert_eq!(tournament.final_match(), 6);
+        assert_eq!(tournament.match_participants(0), Some((0, 1)));
+        assert_eq!(tournament.match_participants(3), Some((6, 7)));
+    }
+
+    #[test]
+    fn later_rounds_wait_for_winners_test() {
+        let mut tournament = get_tournament(8);
+
+        assert_eq!(tournament.match_participants(4), None);
+
+        tournament.match_winners[0] = 1;
+
+        assert_eq!(tournament.match_participants(4), None);
+
+        tournament.match_winners[1] = 2;
+
+        assert_eq!(tournament.match_participants(4), Some((1, 2)));
+
+        tournament.match_winners[2] = 5;
+        tournament.match_winners[3] = 6;
+        tournament.match_winners[4] = 2;
+
+        assert_eq!(tournament.match_participants(6), None);
+
+        tournament.match_winners[5] = 5;
+
+        assert_eq!(tournament.match_participants(6), Some((2, 5)));
+        assert_eq!(tournament.match_participants(7), None);
+    }
+
+    #[test]
+    fn two_player_tournament_test() {
+        let tournament = get_tournament(2);
+
+        assert_eq!(tournament.match_count(), 1);
+        assert_eq!(tournament.final_match(), 0);
+        assert_eq!(tournament.match_participants(0), Some((0, 1)));
+        assert_eq!(tournament.match_participants(1), None);
+    }
+
+    #[test]
+    fn match_index_test() {
+        let mut tournament = get_tournament(4);
+
+        tournament.match_games[1] = Pubkey::new_from_array([41; 32]);
+
+        assert_eq!(
+            tournament.match_index(&Pubkey::new_from_array([41; 32])),
+            Some(1)
+        );
+        assert_eq!(
+            tournament.match_index(&Pubkey::new_from_array([42; 32])),
+            None
+        );
+        assert_eq!(tournament.match_index(&Pubkey::default()), None);
+    }
+}
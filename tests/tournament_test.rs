@@ -0,0 +1,487 @@
+#[cfg(test)]
+mod tests {
+    use metality_game_contract::error::MetalityGameContractError;
+    use metality_game_contract::processor::Processor;
+    use metality_game_contract::profile::find_profile_address;
+    use metality_game_contract::rating::DEFAULT_RATING;
+    use metality_game_contract::state::{
+        MetalityGameContractState, MetalityGameProgramDataState, MetalityPlayerProfileState,
+        MetalityTournamentState, GAME_OUTCOME_FORFEIT, GAME_OUTCOME_WON, GAME_STATE_SEED_PREFIX,
+        MAX_LOBBY_PLAYERS, NFT_CHECK_STRICT, PROGRAM_DATA_SEED, TOURNAMENT_SEED_PREFIX,
+    };
+    use metality_game_contract::validations::admin;
+    use solana_program::{
+        program_option::COption,
+        program_pack::{IsInitialized, Pack},
+    };
+    use solana_program_test::{processor, BanksClient, BanksClientError, ProgramTest};
+    use solana_sdk::{
+        account::Account,
+        hash::Hash,
+        instruction::{AccountMeta, Instruction, InstructionError},
+        packet::PACKET_DATA_SIZE,
+        pubkey::Pubkey,
+        rent::Rent,
+        signature::{Keypair, Signer},
+        system_program, sysvar,
+        transaction::{Transaction, TransactionError},
+    };
+    use spl_associated_token_account::get_associated_token_address;
+    use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+    fn packed_account<T: Pack>(state: T, owner: Pubkey) -> Account {
+        let mut data = vec![0; T::LEN];
+        T::pack(state, &mut data).unwrap();
+
+        Account {
+            lamports: Rent::default().minimum_balance(T::LEN),
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    struct Entrant {
+        keypair: Keypair,
+        nft_ata: Pubkey,
+        nft_mint: Pubkey,
+    }
+
+    impl Entrant {
+        fn pubkey(&self) -> Pubkey {
+            self.keypair.pubkey()
+        }
+    }
+
+    struct TournamentTest {
+        banks_client: BanksClient,
+        recent_blockhash: Hash,
+        program_id: Pubkey,
+        admin: Keypair,
+        program_data: Pubkey,
+        entrants: Vec<Entrant>,
+        tournament_state: Pubkey,
+        pda: Pubkey,
+        next_index: u64,
+    }
+
+    /// Starts a test validator with program data and `capacity` wallets each
+    /// holding an NFT, then has the admin open a tournament of `capacity`
+    /// that every wallet registers for.
+    async fn full_tournament(capacity: usize) -> TournamentTest {
+        let program_id = Pubkey::new_unique();
+
+        let mut program_test = ProgramTest::new(
+            "metality_game_contract",
+            program_id,
+            processor!(Processor::unpack_and_process_instruction),
+        );
+
+        let admin = Keypair::new();
+        let program_data =
+            Pubkey::create_with_seed(&admin::id(), PROGRAM_DATA_SEED, &program_id).unwrap();
+        program_test.add_account(
+            program_data,
+            packed_account(
+                MetalityGameProgramDataState {
+                    is_initialized: true,
+                    index: 0,
+                    oracle: Pubkey::default(),
+                    join_bond_lamports: 0,
+                    bond_forfeit_bps: 0,
+                    nft_check_level: NFT_CHECK_STRICT,
+                    admin: admin.pubkey(),
+                },
+                program_id,
+            ),
+        );
+        program_test.add_account(
+            admin.pubkey(),
+            Account::new(10_000_000_000, 0, &system_program::id()),
+        );
+
+        let mut entrants = Vec::with_capacity(capacity);
+
+        for _ in 0..capacity {
+            let keypair = Keypair::new();
+            let nft_mint = Pubkey::new_unique();
+            let nft_ata = Pubkey::new_unique();
+
+            program_test.add_account(
+                keypair.pubkey(),
+                Account::new(1_000_000_000, 0, &system_program::id()),
+            );
+            program_test.add_account(
+                nft_mint,
+                packed_account(
+                    Mint {
+                        mint_authority: COption::None,
+                        supply: 1,
+                        decimals: 0,
+                        is_initialized: true,
+                        freeze_authority: COption::None,
+                    },
+                    spl_token::id(),
+                ),
+            );
+            program_test.add_account(
+                nft_ata,
+                packed_account(
+                    TokenAccount {
+                        mint: nft_mint,
+                        owner: keypair.pubkey(),
+                        amount: 1,
+                        state: AccountState::Initialized,
+                        ..TokenAccount::default()
+                    },
+                    spl_token::id(),
+                ),
+            );
+
+            entrants.push(Entrant {
+                keypair,
+                nft_ata,
+                nft_mint,
+            });
+        }
+
+        let tournament_state = Pubkey::create_with_seed(
+            &admin.pubkey(),
+            &format!("{}0", TOURNAMENT_SEED_PREFIX),
+            &program_id,
+        )
+        .unwrap();
+        let (pda, _bump_seeds) = Pubkey::find_program_address(
+            &[
+                "metality_game_contract".as_bytes(),
+                tournament_state.as_ref(),
+            ],
+            &program_id,
+        );
+
+        let (banks_client, _payer, recent_blockhash) = program_test.start().await;
+
+        let mut tournament = TournamentTest {
+            banks_client,
+            recent_blockhash,
+            program_id,
+            admin,
+            program_data,
+            entrants,
+            tournament_state,
+            pda,
+            next_index: 1,
+        };
+
+        let accounts = vec![
+            AccountMeta::new(tournament.admin.pubkey(), true),
+            AccountMeta::new_readonly(tournament.pda, false),
+            AccountMeta::new(tournament.tournament_state, false),
+            AccountMeta::new(tournament.program_data, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ];
+        let admin = tournament.admin.insecure_clone();
+        tournament
+            .process(&[14, capacity as u8], accounts, &admin)
+            .await
+            .unwrap();
+
+        for index in 0..capacity {
+            let entrant = &tournament.entrants[index];
+            let user = entrant.keypair.insecure_clone();
+            let accounts = vec![
+                AccountMeta::new(entrant.pubkey(), true),
+                AccountMeta::new(entrant.nft_ata, false),
+                AccountMeta::new_readonly(entrant.nft_mint, false),
+                AccountMeta::new_readonly(tournament.pda, false),
+                AccountMeta::new(tournament.tournament_state, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(tournament.program_data, false),
+            ];
+            tournament.process(&[15], accounts, &user).await.unwrap();
+        }
+
+        tournament
+    }
+
+    impl TournamentTest {
+        /// Sends a single instruction signed and paid by `signer`, checking
+        /// the transaction fits in a packet.
+        async fn process(
+            &mut self,
+            ins_data: &[u8],
+            accounts: Vec<AccountMeta>,
+            signer: &Keypair,
+        ) -> Result<(), BanksClientError> {
+            let transaction = Transaction::new_signed_with_payer(
+                &[Instruction::new_with_bytes(
+                    self.program_id,
+                    ins_data,
+                    accounts,
+                )],
+                Some(&signer.pubkey()),
+                &[signer],
+                self.recent_blockhash,
+            );
+
+            // Signature count, signatures and the message.
+            let transaction_size =
+                1 + 64 * transaction.signatures.len() + transaction.message_data().len();
+            assert!(
+                transaction_size <= PACKET_DATA_SIZE,
+                "instruction {} needs {} bytes",
+                ins_data[0],
+                transaction_size
+            );
+
+            self.banks_client.process_transaction(transaction).await
+        }
+
+        async fn create_match(&mut self, match_index: u8) -> Pubkey {
+            let game_state = Pubkey::create_with_seed(
+                &self.admin.pubkey(),
+                &format!("{}{}", GAME_STATE_SEED_PREFIX, self.next_index),
+                &self.program_id,
+            )
+            .unwrap();
+            self.next_index += 1;
+
+            let accounts = vec![
+                AccountMeta::new(self.admin.pubkey(), true),
+                AccountMeta::new_readonly(self.pda, false),
+                AccountMeta::new(self.tournament_state, false),
+                AccountMeta::new(game_state, false),
+                AccountMeta::new(self.program_data, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ];
+            let admin = self.admin.insecure_clone();
+            self.process(&[17, match_index], accounts, &admin)
+                .await
+                .unwrap();
+
+            game_state
+        }
+
+        async fn settle_match(
+            &mut self,
+            game_state: Pubkey,
+            winner: Pubkey,
+            loser: Pubkey,
+        ) -> Result<(), BanksClientError> {
+            let accounts = vec![
+                AccountMeta::new(self.admin.pubkey(), true),
+                AccountMeta::new_readonly(self.program_data, false),
+                AccountMeta::new_readonly(winner, false),
+                AccountMeta::new(self.tournament_state, false),
+                AccountMeta::new(game_state, false),
+                AccountMeta::new_readonly(loser, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new(find_profile_address(&winner, &self.program_id).0, false),
+                AccountMeta::new(find_profile_address(&loser, &self.program_id).0, false),
+            ];
+            let admin = self.admin.insecure_clone();
+
+            self.process(&[18], accounts, &admin).await
+        }
+
+        async fn admin_close(&mut self, ins_no: u8) -> Result<(), BanksClientError> {
+            let accounts = vec![
+                AccountMeta::new_readonly(self.admin.pubkey(), true),
+                AccountMeta::new_readonly(self.program_data, false),
+                AccountMeta::new(self.tournament_state, false),
+            ];
+            let admin = self.admin.insecure_clone();
+
+            self.process(&[ins_no], accounts, &admin).await
+        }
+
+        /// Releases the stake of entrant `index` to entrant `recipient`,
+        /// paid for by the recipient.
+        async fn release(
+            &mut self,
+            index: usize,
+            recipient: usize,
+        ) -> Result<(), BanksClientError> {
+            let entrant = &self.entrants[index];
+            let payer = self.entrants[recipient].keypair.insecure_clone();
+
+            let accounts = vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new_readonly(self.pda, false),
+                AccountMeta::new(self.tournament_state, false),
+                AccountMeta::new_readonly(entrant.pubkey(), false),
+                AccountMeta::new(entrant.nft_ata, false),
+                AccountMeta::new_readonly(entrant.nft_mint, false),
+                AccountMeta::new_readonly(payer.pubkey(), false),
+                AccountMeta::new(
+                    get_associated_token_address(&payer.pubkey(), &entrant.nft_mint),
+                    false,
+                ),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            ];
+
+            self.process(&[41], accounts, &payer).await
+        }
+
+        async fn unpack<T: Pack + IsInitialized>(&mut self, address: Pubkey) -> T {
+            let account = self
+                .banks_client
+                .get_account(address)
+                .await
+                .unwrap()
+                .unwrap();
+
+            T::unpack(&account.data).unwrap()
+        }
+    }
+
+    fn assert_error(result: Result<(), BanksClientError>, error: MetalityGameContractError) {
+        assert_eq!(
+            result.unwrap_err().unwrap(),
+            TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+        );
+    }
+
+    #[tokio::test]
+    async fn play_full_tournament_test() {
+        let mut tournament = full_tournament(MAX_LOBBY_PLAYERS).await;
+
+        // User A of every match wins, which is entrant 0 all the way through.
+        for match_index in 0..MAX_LOBBY_PLAYERS as u8 - 1 {
+            let game_state = tournament.create_match(match_index).await;
+            let game: MetalityGameContractState = tournament.unpack(game_state).await;
+
+            tournament
+                .settle_match(game_state, game.user_a, game.user_b)
+                .await
+                .unwrap();
+
+            let game: MetalityGameContractState = tournament.unpack(game_state).await;
+            assert!(game.expired);
+            assert_eq!(game.outcome, GAME_OUTCOME_WON);
+        }
+
+        let tournament_state = tournament.tournament_state;
+        let state: MetalityTournamentState = tournament.unpack(tournament_state).await;
+        assert_eq!(state.champion, 0);
+
+        let champion = tournament.entrants[0].pubkey();
+        let profile = find_profile_address(&champion, &tournament.program_id).0;
+        let profile: MetalityPlayerProfileState = tournament.unpack(profile).await;
+        assert_eq!(profile.games_played, 4);
+        assert_eq!(profile.wins, 4);
+        assert!(profile.rating > DEFAULT_RATING);
+
+        tournament.admin_close(19).await.unwrap();
+
+        for index in 0..MAX_LOBBY_PLAYERS {
+            tournament.release(index, 0).await.unwrap();
+        }
+
+        let state: MetalityTournamentState = tournament.unpack(tournament_state).await;
+        assert_eq!(state.released, u16::MAX);
+
+        let own_nft: TokenAccount = tournament.unpack(tournament.entrants[0].nft_ata).await;
+        assert_eq!(own_nft.owner, champion);
+        assert_eq!(own_nft.amount, 1);
+
+        for index in 1..MAX_LOBBY_PLAYERS {
+            let won_nft =
+                get_associated_token_address(&champion, &tournament.entrants[index].nft_mint);
+            let won_nft: TokenAccount = tournament.unpack(won_nft).await;
+            assert_eq!(won_nft.amount, 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn cancel_full_tournament_test() {
+        let mut tournament = full_tournament(MAX_LOBBY_PLAYERS).await;
+
+        tournament.admin_close(16).await.unwrap();
+
+        for index in 0..MAX_LOBBY_PLAYERS {
+            tournament.release(index, index).await.unwrap();
+        }
+
+        for index in 0..MAX_LOBBY_PLAYERS {
+            let owner = tournament.entrants[index].pubkey();
+            let nft: TokenAccount = tournament.unpack(tournament.entrants[index].nft_ata).await;
+
+            assert_eq!(nft.owner, owner);
+            assert_eq!(nft.amount, 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn release_before_payout_test() {
+        let mut tournament = full_tournament(2).await;
+
+        assert_error(
+            tournament.release(0, 0).await,
+            MetalityGameContractError::StakesLocked,
+        );
+    }
+
+    #[tokio::test]
+    async fn settle_resigned_match_test() {
+        let mut tournament = full_tournament(2).await;
+
+        let game_state = tournament.create_match(0).await;
+        let game: MetalityGameContractState = tournament.unpack(game_state).await;
+
+        let resigning = tournament.entrants[1].keypair.insecure_clone();
+        let accounts = vec![
+            AccountMeta::new_readonly(resigning.pubkey(), true),
+            AccountMeta::new_readonly(game.user_a, false),
+            AccountMeta::new(game_state, false),
+        ];
+        tournament
+            .process(&[34], accounts, &resigning)
+            .await
+            .unwrap();
+
+        // The recorded winner can't be swapped for the one who resigned.
+        assert_error(
+            tournament
+                .settle_match(game_state, game.user_b, game.user_a)
+                .await,
+            MetalityGameContractError::InvalidWinner,
+        );
+
+        tournament
+            .settle_match(game_state, game.user_a, game.user_b)
+            .await
+            .unwrap();
+
+        let game: MetalityGameContractState = tournament.unpack(game_state).await;
+        assert!(game.expired);
+        assert_eq!(game.outcome, GAME_OUTCOME_FORFEIT);
+
+        let tournament_state = tournament.tournament_state;
+        let state: MetalityTournamentState = tournament.unpack(tournament_state).await;
+        assert_eq!(state.champion, 0);
+
+        let loser = find_profile_address(&game.user_b, &tournament.program_id).0;
+        let loser: MetalityPlayerProfileState = tournament.unpack(loser).await;
+        assert_eq!(loser.losses, 1);
+    }
+
+    #[tokio::test]
+    async fn settle_match_with_wrong_loser_test() {
+        let mut tournament = full_tournament(2).await;
+
+        let game_state = tournament.create_match(0).await;
+        let game: MetalityGameContractState = tournament.unpack(game_state).await;
+
+        assert_error(
+            tournament
+                .settle_match(game_state, game.user_a, game.user_a)
+                .await,
+            MetalityGameContractError::LoserMismatch,
+        );
+    }
+}
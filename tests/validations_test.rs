@@ -1,20 +1,26 @@
 use solana_sdk::account_info::AccountInfo;
+use solana_sdk::program_option::COption;
 use solana_sdk::program_pack::Pack;
 use solana_sdk::{account::Account, account_info::IntoAccountInfo, pubkey::Pubkey};
 use spl_token::state::{Account as TokenAccount, AccountState, Mint};
 
+use metality_game_contract::state::{
+    MetalityGameProgramDataState, NFT_CHECK_STRICT, PROGRAM_DATA_SEED,
+};
+
 pub fn get_token_ata<'a>(
     mint_pubkey: u8,
     owner_pubkey: u8,
     pubkey: &'a Pubkey,
     token_account: &'a mut Account,
 ) -> AccountInfo<'a> {
-    let mut default_account = TokenAccount::default();
-
-    default_account.state = AccountState::Initialized;
-    default_account.mint = Pubkey::new_from_array([mint_pubkey; 32]);
-    default_account.owner = Pubkey::new_from_array([owner_pubkey; 32]);
-    default_account.amount = 1;
+    let default_account = TokenAccount {
+        state: AccountState::Initialized,
+        mint: Pubkey::new_from_array([mint_pubkey; 32]),
+        owner: Pubkey::new_from_array([owner_pubkey; 32]),
+        amount: 1,
+        ..TokenAccount::default()
+    };
 
     let token_account_info = (pubkey, false, token_account).into_account_info();
 
@@ -24,15 +30,16 @@ pub fn get_token_ata<'a>(
     )
     .unwrap();
 
-    return token_account_info;
+    token_account_info
 }
 
 pub fn get_mint_account<'a>(pubkey: &'a Pubkey, mint_account: &'a mut Account) -> AccountInfo<'a> {
-    let mut default_account = Mint::default();
-
-    default_account.is_initialized = true;
-    default_account.decimals = 0;
-    default_account.supply = 1;
+    let default_account = Mint {
+        is_initialized: true,
+        decimals: 0,
+        supply: 1,
+        ..Mint::default()
+    };
 
     let mint_account_info = (pubkey, false, mint_account).into_account_info();
 
@@ -42,28 +49,51 @@ pub fn get_mint_account<'a>(pubkey: &'a Pubkey, mint_account: &'a mut Account) -
     )
     .unwrap();
 
-    return mint_account_info;
+    mint_account_info
 }
 
 pub fn get_account(size: usize, owner: Pubkey) -> Account {
-    let account = Account {
+    Account {
         lamports: u32::MAX as u64,
         data: vec![0u8; size],
         owner,
         executable: false,
         rent_epoch: 0,
-    };
+    }
+}
 
-    return account;
+pub fn get_program_data_address(program_id: Pubkey) -> Pubkey {
+    Pubkey::create_with_seed(&admin::id(), PROGRAM_DATA_SEED, &program_id).unwrap()
 }
 
-pub mod admin {
-    solana_sdk::declare_id!("3KBgdH5xuVWKVB85L3SaRAiHXhDb77yd9qc6rxpNL2hr");
+pub fn get_program_data_account(admin: Pubkey, program_id: Pubkey) -> Account {
+    let mut account = get_account(MetalityGameProgramDataState::LEN, program_id);
+
+    MetalityGameProgramDataState::pack(
+        MetalityGameProgramDataState {
+            is_initialized: true,
+            index: 0,
+            oracle: Pubkey::default(),
+            join_bond_lamports: 0,
+            bond_forfeit_bps: 0,
+            nft_check_level: NFT_CHECK_STRICT,
+            admin,
+        },
+        &mut account.data,
+    )
+    .unwrap();
+
+    account
 }
 
+pub use metality_game_contract::validations::admin;
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use metality_game_contract::error::MetalityGameContractError;
+    use metality_game_contract::metaplex::find_master_edition_address;
+    use metality_game_contract::state::NFT_CHECK_BASIC;
     use metality_game_contract::validations::Validator;
 
     #[test]
@@ -99,6 +129,179 @@ mod tests {
         )
     }
 
+    #[test]
+    #[should_panic]
+    fn failure_validate_token_ata_unencumbered_test() {
+        let mut mint_account = get_account(Mint::LEN, spl_token::id());
+        let mint_pubkey = Pubkey::new_from_array([11; 32]);
+        let mint_account_info = get_mint_account(&mint_pubkey, &mut mint_account);
+
+        let mut token_account = get_account(TokenAccount::LEN, spl_token::id());
+        let token_ata_pubkey = Pubkey::new_from_array([1; 32]);
+        let token_account_info = get_token_ata(11, 22, &token_ata_pubkey, &mut token_account);
+
+        let mut default_account =
+            TokenAccount::unpack(&token_account_info.try_borrow_data().unwrap()).unwrap();
+        default_account.delegate = COption::Some(Pubkey::new_from_array([44; 32]));
+        default_account.delegated_amount = 1;
+        TokenAccount::pack(
+            default_account,
+            &mut token_account_info.try_borrow_mut_data().unwrap(),
+        )
+        .unwrap();
+
+        Validator::validate_token_ata(&token_account_info, &mint_account_info).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_validate_token_ata_unencumbered_test_two() {
+        let mut mint_account = get_account(Mint::LEN, spl_token::id());
+        let mint_pubkey = Pubkey::new_from_array([11; 32]);
+        let mint_account_info = get_mint_account(&mint_pubkey, &mut mint_account);
+
+        let mut token_account = get_account(TokenAccount::LEN, spl_token::id());
+        let token_ata_pubkey = Pubkey::new_from_array([1; 32]);
+        let token_account_info = get_token_ata(11, 22, &token_ata_pubkey, &mut token_account);
+
+        let mut default_account =
+            TokenAccount::unpack(&token_account_info.try_borrow_data().unwrap()).unwrap();
+        default_account.close_authority = COption::Some(Pubkey::new_from_array([44; 32]));
+        TokenAccount::pack(
+            default_account,
+            &mut token_account_info.try_borrow_mut_data().unwrap(),
+        )
+        .unwrap();
+
+        Validator::validate_token_ata(&token_account_info, &mint_account_info).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_validate_token_ata_unencumbered_test_three() {
+        let mut mint_account = get_account(Mint::LEN, spl_token::id());
+        let mint_pubkey = Pubkey::new_from_array([11; 32]);
+        let mint_account_info = get_mint_account(&mint_pubkey, &mut mint_account);
+
+        let mut token_account = get_account(TokenAccount::LEN, spl_token::id());
+        let token_ata_pubkey = Pubkey::new_from_array([1; 32]);
+        let token_account_info = get_token_ata(11, 22, &token_ata_pubkey, &mut token_account);
+
+        let mut default_account =
+            TokenAccount::unpack(&token_account_info.try_borrow_data().unwrap()).unwrap();
+        default_account.state = AccountState::Frozen;
+        TokenAccount::pack(
+            default_account,
+            &mut token_account_info.try_borrow_mut_data().unwrap(),
+        )
+        .unwrap();
+
+        Validator::validate_token_ata(&token_account_info, &mint_account_info).unwrap();
+    }
+
+    #[test]
+    fn success_validate_nft_mint_test() {
+        let mut mint_account = get_account(Mint::LEN, spl_token::id());
+        let mint_pubkey = Pubkey::new_from_array([11; 32]);
+        let mint_account_info = get_mint_account(&mint_pubkey, &mut mint_account);
+
+        Validator::validate_nft_mint(&mint_account_info, NFT_CHECK_STRICT).unwrap();
+    }
+
+    #[test]
+    fn success_validate_nft_mint_test_two() {
+        let mut mint_account = get_account(Mint::LEN, spl_token::id());
+        let mint_pubkey = Pubkey::new_from_array([11; 32]);
+        let mint_account_info = get_mint_account(&mint_pubkey, &mut mint_account);
+
+        let mut default_account =
+            Mint::unpack(&mint_account_info.try_borrow_data().unwrap()).unwrap();
+        default_account.mint_authority = COption::Some(find_master_edition_address(&mint_pubkey).0);
+        Mint::pack(
+            default_account,
+            &mut mint_account_info.try_borrow_mut_data().unwrap(),
+        )
+        .unwrap();
+
+        Validator::validate_nft_mint(&mint_account_info, NFT_CHECK_STRICT).unwrap();
+    }
+
+    #[test]
+    fn success_validate_nft_mint_test_three() {
+        let mut mint_account = get_account(Mint::LEN, spl_token::id());
+        let mint_pubkey = Pubkey::new_from_array([11; 32]);
+        let mint_account_info = get_mint_account(&mint_pubkey, &mut mint_account);
+
+        let mut default_account =
+            Mint::unpack(&mint_account_info.try_borrow_data().unwrap()).unwrap();
+        default_account.supply = 1_000_000;
+        default_account.mint_authority = COption::Some(Pubkey::new_from_array([44; 32]));
+        Mint::pack(
+            default_account,
+            &mut mint_account_info.try_borrow_mut_data().unwrap(),
+        )
+        .unwrap();
+
+        Validator::validate_nft_mint(&mint_account_info, NFT_CHECK_BASIC).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_validate_nft_mint_test() {
+        let mut mint_account = get_account(Mint::LEN, spl_token::id());
+        let mint_pubkey = Pubkey::new_from_array([11; 32]);
+        let mint_account_info = get_mint_account(&mint_pubkey, &mut mint_account);
+
+        let mut default_account =
+            Mint::unpack(&mint_account_info.try_borrow_data().unwrap()).unwrap();
+        default_account.supply = 1_000_000;
+        Mint::pack(
+            default_account,
+            &mut mint_account_info.try_borrow_mut_data().unwrap(),
+        )
+        .unwrap();
+
+        Validator::validate_nft_mint(&mint_account_info, NFT_CHECK_STRICT).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_validate_nft_mint_test_two() {
+        let mut mint_account = get_account(Mint::LEN, spl_token::id());
+        let mint_pubkey = Pubkey::new_from_array([11; 32]);
+        let mint_account_info = get_mint_account(&mint_pubkey, &mut mint_account);
+
+        let mut default_account =
+            Mint::unpack(&mint_account_info.try_borrow_data().unwrap()).unwrap();
+        default_account.mint_authority = COption::Some(Pubkey::new_from_array([44; 32]));
+        Mint::pack(
+            default_account,
+            &mut mint_account_info.try_borrow_mut_data().unwrap(),
+        )
+        .unwrap();
+
+        Validator::validate_nft_mint(&mint_account_info, NFT_CHECK_STRICT).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_validate_nft_mint_test_three() {
+        let mut mint_account = get_account(Mint::LEN, spl_token::id());
+        let mint_pubkey = Pubkey::new_from_array([11; 32]);
+        let mint_account_info = get_mint_account(&mint_pubkey, &mut mint_account);
+
+        let mut default_account =
+            Mint::unpack(&mint_account_info.try_borrow_data().unwrap()).unwrap();
+        default_account.supply = 1;
+        Mint::pack(
+            default_account,
+            &mut mint_account_info.try_borrow_mut_data().unwrap(),
+        )
+        .unwrap();
+
+        Validator::validate_nft_mint(&mint_account_info, NFT_CHECK_STRICT + 1).unwrap();
+    }
+
     #[test]
     fn success_validate_token_owner_test() {
         let mut user_account = get_account(0, solana_sdk::system_program::id());
@@ -126,6 +329,35 @@ mod tests {
         Validator::validate_token_owner(&token_account_info, &user_account_info).unwrap();
     }
 
+    #[test]
+    fn success_validate_uninitialized_test() {
+        let mut account = get_account(0, solana_sdk::system_program::id());
+        let account_pubkey = Pubkey::new_from_array([10; 32]);
+        let account_info = (&account_pubkey, false, &mut account).into_account_info();
+
+        Validator::validate_uninitialized(&account_info).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_validate_uninitialized_test() {
+        let mut account = get_account(8, admin::id());
+        let account_pubkey = Pubkey::new_from_array([10; 32]);
+        let account_info = (&account_pubkey, false, &mut account).into_account_info();
+
+        Validator::validate_uninitialized(&account_info).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_validate_uninitialized_test_two() {
+        let mut account = get_account(0, admin::id());
+        let account_pubkey = Pubkey::new_from_array([10; 32]);
+        let account_info = (&account_pubkey, false, &mut account).into_account_info();
+
+        Validator::validate_uninitialized(&account_info).unwrap();
+    }
+
     #[test]
     fn success_validate_is_signer_test() {
         let mut user_account = get_account(0, solana_sdk::system_program::id());
@@ -148,33 +380,114 @@ mod tests {
         Validator::validate_is_signer(&user_account_info).unwrap();
     }
 
+    #[test]
+    fn success_validate_deployer_test() {
+        let mut deployer_account = get_account(0, solana_sdk::system_program::id());
+        let deployer_pubkey = admin::id();
+        let deployer_account_info =
+            (&deployer_pubkey, true, &mut deployer_account).into_account_info();
+
+        assert_eq!(
+            Validator::validate_deployer(&deployer_account_info).unwrap(),
+            ()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_validate_deployer_test() {
+        let mut deployer_account = get_account(0, solana_sdk::system_program::id());
+        let deployer_pubkey = admin::id();
+        let deployer_account_info =
+            (&deployer_pubkey, false, &mut deployer_account).into_account_info();
+
+        Validator::validate_deployer(&deployer_account_info).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_validate_deployer_test_two() {
+        let mut deployer_account = get_account(0, solana_sdk::system_program::id());
+        let deployer_pubkey = Pubkey::new_from_array([10; 32]);
+        let deployer_account_info =
+            (&deployer_pubkey, true, &mut deployer_account).into_account_info();
+
+        Validator::validate_deployer(&deployer_account_info).unwrap();
+    }
+
     #[test]
     fn success_validate_admin_test() {
+        let program_id = Pubkey::new_from_array([55; 32]);
+        let admin_pubkey = Pubkey::new_from_array([10; 32]);
+
         let mut admin_account = get_account(0, solana_sdk::system_program::id());
-        let admin_pubkey = admin::id();
         let admin_account_info = (&admin_pubkey, true, &mut admin_account).into_account_info();
 
-        assert_eq!(Validator::validate_admin(&admin_account_info).unwrap(), ());
+        let program_data_pubkey = get_program_data_address(program_id);
+        let mut program_data_account = get_program_data_account(admin_pubkey, program_id);
+        let program_data_account_info =
+            (&program_data_pubkey, false, &mut program_data_account).into_account_info();
+
+        assert_eq!(
+            Validator::validate_admin(&admin_account_info, &program_data_account_info, program_id)
+                .unwrap(),
+            ()
+        );
     }
 
     #[test]
     #[should_panic]
     fn failure_validate_admin_test() {
+        let program_id = Pubkey::new_from_array([55; 32]);
+        let admin_pubkey = Pubkey::new_from_array([10; 32]);
+
         let mut admin_account = get_account(0, solana_sdk::system_program::id());
-        let admin_pubkey = admin::id();
         let admin_account_info = (&admin_pubkey, false, &mut admin_account).into_account_info();
 
-        Validator::validate_admin(&admin_account_info).unwrap();
+        let program_data_pubkey = get_program_data_address(program_id);
+        let mut program_data_account = get_program_data_account(admin_pubkey, program_id);
+        let program_data_account_info =
+            (&program_data_pubkey, false, &mut program_data_account).into_account_info();
+
+        Validator::validate_admin(&admin_account_info, &program_data_account_info, program_id)
+            .unwrap();
     }
 
     #[test]
     #[should_panic]
     fn failure_validate_admin_test_two() {
+        let program_id = Pubkey::new_from_array([55; 32]);
+        let admin_pubkey = admin::id();
+
         let mut admin_account = get_account(0, solana_sdk::system_program::id());
+        let admin_account_info = (&admin_pubkey, true, &mut admin_account).into_account_info();
+
+        let program_data_pubkey = get_program_data_address(program_id);
+        let mut program_data_account =
+            get_program_data_account(Pubkey::new_from_array([10; 32]), program_id);
+        let program_data_account_info =
+            (&program_data_pubkey, false, &mut program_data_account).into_account_info();
+
+        Validator::validate_admin(&admin_account_info, &program_data_account_info, program_id)
+            .unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_validate_admin_test_three() {
+        let program_id = Pubkey::new_from_array([55; 32]);
         let admin_pubkey = Pubkey::new_from_array([10; 32]);
+
+        let mut admin_account = get_account(0, solana_sdk::system_program::id());
         let admin_account_info = (&admin_pubkey, true, &mut admin_account).into_account_info();
 
-        Validator::validate_admin(&admin_account_info).unwrap();
+        let program_data_pubkey = Pubkey::new_from_array([11; 32]);
+        let mut program_data_account = get_program_data_account(admin_pubkey, program_id);
+        let program_data_account_info =
+            (&program_data_pubkey, false, &mut program_data_account).into_account_info();
+
+        Validator::validate_admin(&admin_account_info, &program_data_account_info, program_id)
+            .unwrap();
     }
 
     #[test]
@@ -183,7 +496,12 @@ mod tests {
         let two_pubkey = Pubkey::new_from_array([10; 32]);
 
         assert_eq!(
-            Validator::validate_equality(one_pubkey, two_pubkey).unwrap(),
+            Validator::validate_equality(
+                one_pubkey,
+                two_pubkey,
+                MetalityGameContractError::UserAMismatch
+            )
+            .unwrap(),
             ()
         );
     }
@@ -194,7 +512,12 @@ mod tests {
         let one_pubkey = Pubkey::new_from_array([10; 32]);
         let two_pubkey = Pubkey::new_from_array([11; 32]);
 
-        Validator::validate_equality(one_pubkey, two_pubkey).unwrap();
+        Validator::validate_equality(
+            one_pubkey,
+            two_pubkey,
+            MetalityGameContractError::UserAMismatch,
+        )
+        .unwrap();
     }
 
     #[test]
@@ -219,18 +542,32 @@ mod tests {
 
     #[test]
     fn success_validate_bool_test() {
-        assert_eq!(Validator::validate_bool(true, true).unwrap(), ());
+        assert_eq!(
+            Validator::validate_bool(true, true, MetalityGameContractError::GameExpired).unwrap(),
+            ()
+        );
     }
 
     #[test]
     #[should_panic]
     fn failure_validate_bool_test() {
-        assert_eq!(Validator::validate_bool(true, false).unwrap(), ());
+        assert_eq!(
+            Validator::validate_bool(true, false, MetalityGameContractError::GameExpired).unwrap(),
+            ()
+        );
+    }
+
+    #[test]
+    fn failure_validate_bool_error_code_test() {
+        assert_eq!(
+            Validator::validate_bool(false, true, MetalityGameContractError::OpponentNotJoined),
+            Err(MetalityGameContractError::OpponentNotJoined.into())
+        );
     }
 
     #[test]
     fn success_validate_winner_test() {
-        use metality_game_contract::state::MetalityGameContractState;
+        use metality_game_contract::state::{MetalityGameContractState, MAX_PREVIOUS_OPPONENTS};
 
         let winner_pubkey = Pubkey::new_from_array([10; 32]);
 
@@ -246,6 +583,18 @@ mod tests {
             pda_account: Pubkey::new_from_array([99; 32]),
             expired: false,
             game_started: true,
+            min_opponent_rating: 0,
+            max_opponent_rating: u32::MAX,
+            side_pool: Pubkey::default(),
+            winner: Pubkey::default(),
+            cancel_proposer: Pubkey::default(),
+            outcome: 0,
+            join_bond: 0,
+            bond_forfeit_bps: 0,
+            previous_opponents: [Pubkey::default(); MAX_PREVIOUS_OPPONENTS],
+            blocked_opponents: 0,
+            next_previous_opponent: 0,
+            exit_count: 0,
         };
 
         assert_eq!(
@@ -256,7 +605,7 @@ mod tests {
 
     #[test]
     fn success_validate_winner_test_two() {
-        use metality_game_contract::state::MetalityGameContractState;
+        use metality_game_contract::state::{MetalityGameContractState, MAX_PREVIOUS_OPPONENTS};
 
         let winner_pubkey = Pubkey::new_from_array([20; 32]);
 
@@ -272,6 +621,18 @@ mod tests {
             pda_account: Pubkey::new_from_array([99; 32]),
             expired: false,
             game_started: true,
+            min_opponent_rating: 0,
+            max_opponent_rating: u32::MAX,
+            side_pool: Pubkey::default(),
+            winner: Pubkey::default(),
+            cancel_proposer: Pubkey::default(),
+            outcome: 0,
+            join_bond: 0,
+            bond_forfeit_bps: 0,
+            previous_opponents: [Pubkey::default(); MAX_PREVIOUS_OPPONENTS],
+            blocked_opponents: 0,
+            next_previous_opponent: 0,
+            exit_count: 0,
         };
 
         assert_eq!(
@@ -283,7 +644,7 @@ mod tests {
     #[test]
     #[should_panic]
     fn failure_validate_winner_test() {
-        use metality_game_contract::state::MetalityGameContractState;
+        use metality_game_contract::state::{MetalityGameContractState, MAX_PREVIOUS_OPPONENTS};
 
         let winner_pubkey = Pubkey::new_from_array([33; 32]);
 
@@ -299,11 +660,211 @@ mod tests {
             pda_account: Pubkey::new_from_array([99; 32]),
             expired: false,
             game_started: true,
+            min_opponent_rating: 0,
+            max_opponent_rating: u32::MAX,
+            side_pool: Pubkey::default(),
+            winner: Pubkey::default(),
+            cancel_proposer: Pubkey::default(),
+            outcome: 0,
+            join_bond: 0,
+            bond_forfeit_bps: 0,
+            previous_opponents: [Pubkey::default(); MAX_PREVIOUS_OPPONENTS],
+            blocked_opponents: 0,
+            next_previous_opponent: 0,
+            exit_count: 0,
         };
 
         Validator::validate_winner(game_state, winner_pubkey).unwrap();
     }
 
+    #[test]
+    fn success_validate_opponent_test() {
+        use metality_game_contract::state::{MetalityGameContractState, MAX_PREVIOUS_OPPONENTS};
+
+        let game_state = MetalityGameContractState {
+            is_initialized: true,
+            user_a: Pubkey::new_from_array([10; 32]),
+            a_nft_ata: Pubkey::new_from_array([1; 32]),
+            a_nft_mint: Pubkey::new_from_array([2; 32]),
+            user_b: Pubkey::new_from_array([20; 32]),
+            user_b_joined: true,
+            b_nft_ata: Pubkey::new_from_array([3; 32]),
+            b_nft_mint: Pubkey::new_from_array([4; 32]),
+            pda_account: Pubkey::new_from_array([99; 32]),
+            expired: false,
+            game_started: true,
+            min_opponent_rating: 0,
+            max_opponent_rating: u32::MAX,
+            side_pool: Pubkey::default(),
+            winner: Pubkey::default(),
+            cancel_proposer: Pubkey::default(),
+            outcome: 0,
+            join_bond: 0,
+            bond_forfeit_bps: 0,
+            previous_opponents: [Pubkey::default(); MAX_PREVIOUS_OPPONENTS],
+            blocked_opponents: 0,
+            next_previous_opponent: 0,
+            exit_count: 0,
+        };
+
+        assert_eq!(
+            Validator::validate_opponent(
+                game_state,
+                Pubkey::new_from_array([10; 32]),
+                Pubkey::new_from_array([20; 32])
+            )
+            .unwrap(),
+            ()
+        );
+    }
+
+    #[test]
+    fn success_validate_opponent_test_two() {
+        use metality_game_contract::state::{MetalityGameContractState, MAX_PREVIOUS_OPPONENTS};
+
+        let game_state = MetalityGameContractState {
+            is_initialized: true,
+            user_a: Pubkey::new_from_array([10; 32]),
+            a_nft_ata: Pubkey::new_from_array([1; 32]),
+            a_nft_mint: Pubkey::new_from_array([2; 32]),
+            user_b: Pubkey::new_from_array([20; 32]),
+            user_b_joined: true,
+            b_nft_ata: Pubkey::new_from_array([3; 32]),
+            b_nft_mint: Pubkey::new_from_array([4; 32]),
+            pda_account: Pubkey::new_from_array([99; 32]),
+            expired: false,
+            game_started: true,
+            min_opponent_rating: 0,
+            max_opponent_rating: u32::MAX,
+            side_pool: Pubkey::default(),
+            winner: Pubkey::default(),
+            cancel_proposer: Pubkey::default(),
+            outcome: 0,
+            join_bond: 0,
+            bond_forfeit_bps: 0,
+            previous_opponents: [Pubkey::default(); MAX_PREVIOUS_OPPONENTS],
+            blocked_opponents: 0,
+            next_previous_opponent: 0,
+            exit_count: 0,
+        };
+
+        assert_eq!(
+            Validator::validate_opponent(
+                game_state,
+                Pubkey::new_from_array([20; 32]),
+                Pubkey::new_from_array([10; 32])
+            )
+            .unwrap(),
+            ()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_validate_opponent_test() {
+        use metality_game_contract::state::{MetalityGameContractState, MAX_PREVIOUS_OPPONENTS};
+
+        let game_state = MetalityGameContractState {
+            is_initialized: true,
+            user_a: Pubkey::new_from_array([10; 32]),
+            a_nft_ata: Pubkey::new_from_array([1; 32]),
+            a_nft_mint: Pubkey::new_from_array([2; 32]),
+            user_b: Pubkey::new_from_array([20; 32]),
+            user_b_joined: true,
+            b_nft_ata: Pubkey::new_from_array([3; 32]),
+            b_nft_mint: Pubkey::new_from_array([4; 32]),
+            pda_account: Pubkey::new_from_array([99; 32]),
+            expired: false,
+            game_started: true,
+            min_opponent_rating: 0,
+            max_opponent_rating: u32::MAX,
+            side_pool: Pubkey::default(),
+            winner: Pubkey::default(),
+            cancel_proposer: Pubkey::default(),
+            outcome: 0,
+            join_bond: 0,
+            bond_forfeit_bps: 0,
+            previous_opponents: [Pubkey::default(); MAX_PREVIOUS_OPPONENTS],
+            blocked_opponents: 0,
+            next_previous_opponent: 0,
+            exit_count: 0,
+        };
+
+        Validator::validate_opponent(
+            game_state,
+            Pubkey::new_from_array([10; 32]),
+            Pubkey::new_from_array([10; 32]),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_validate_opponent_test_two() {
+        use metality_game_contract::state::{MetalityGameContractState, MAX_PREVIOUS_OPPONENTS};
+
+        let game_state = MetalityGameContractState {
+            is_initialized: true,
+            user_a: Pubkey::new_from_array([10; 32]),
+            a_nft_ata: Pubkey::new_from_array([1; 32]),
+            a_nft_mint: Pubkey::new_from_array([2; 32]),
+            user_b: Pubkey::new_from_array([20; 32]),
+            user_b_joined: true,
+            b_nft_ata: Pubkey::new_from_array([3; 32]),
+            b_nft_mint: Pubkey::new_from_array([4; 32]),
+            pda_account: Pubkey::new_from_array([99; 32]),
+            expired: false,
+            game_started: true,
+            min_opponent_rating: 0,
+            max_opponent_rating: u32::MAX,
+            side_pool: Pubkey::default(),
+            winner: Pubkey::default(),
+            cancel_proposer: Pubkey::default(),
+            outcome: 0,
+            join_bond: 0,
+            bond_forfeit_bps: 0,
+            previous_opponents: [Pubkey::default(); MAX_PREVIOUS_OPPONENTS],
+            blocked_opponents: 0,
+            next_previous_opponent: 0,
+            exit_count: 0,
+        };
+
+        Validator::validate_opponent(
+            game_state,
+            Pubkey::new_from_array([33; 32]),
+            Pubkey::new_from_array([10; 32]),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn success_validate_rating_range_test() {
+        assert_eq!(
+            Validator::validate_rating_range(1200, 1100, 1300).unwrap(),
+            ()
+        );
+        assert_eq!(
+            Validator::validate_rating_range(1100, 1100, 1300).unwrap(),
+            ()
+        );
+        assert_eq!(
+            Validator::validate_rating_range(1300, 1100, 1300).unwrap(),
+            ()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_validate_rating_range_test() {
+        Validator::validate_rating_range(1099, 1100, 1300).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_validate_rating_range_test_two() {
+        Validator::validate_rating_range(1301, 1100, 1300).unwrap();
+    }
+
     #[test]
     fn success_validate_state_account_test() {
         use metality_game_contract::state::MetalityGameContractState;
@@ -355,4 +916,156 @@ mod tests {
             ()
         );
     }
+
+    #[test]
+    fn success_validate_reward_claimant_test() {
+        let mut winner_account = get_account(0, solana_sdk::system_program::id());
+        let winner_pubkey = Pubkey::new_from_array([10; 32]);
+        let winner_account_info = (&winner_pubkey, true, &mut winner_account).into_account_info();
+
+        assert_eq!(
+            Validator::validate_reward_claimant(true, &winner_account_info, &winner_account_info)
+                .unwrap(),
+            ()
+        );
+    }
+
+    #[test]
+    fn success_validate_reward_claimant_test_two() {
+        let mut winner_account = get_account(0, solana_sdk::system_program::id());
+        let winner_pubkey = Pubkey::new_from_array([10; 32]);
+        let winner_account_info = (&winner_pubkey, false, &mut winner_account).into_account_info();
+
+        let mut payer_account = get_account(0, solana_sdk::system_program::id());
+        let payer_pubkey = Pubkey::new_from_array([11; 32]);
+        let payer_account_info = (&payer_pubkey, true, &mut payer_account).into_account_info();
+
+        assert_eq!(
+            Validator::validate_reward_claimant(false, &winner_account_info, &payer_account_info)
+                .unwrap(),
+            ()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_validate_reward_claimant_test() {
+        let mut winner_account = get_account(0, solana_sdk::system_program::id());
+        let winner_pubkey = Pubkey::new_from_array([10; 32]);
+        let winner_account_info = (&winner_pubkey, false, &mut winner_account).into_account_info();
+
+        let mut payer_account = get_account(0, solana_sdk::system_program::id());
+        let payer_pubkey = Pubkey::new_from_array([11; 32]);
+        let payer_account_info = (&payer_pubkey, true, &mut payer_account).into_account_info();
+
+        Validator::validate_reward_claimant(true, &winner_account_info, &payer_account_info)
+            .unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_validate_reward_claimant_test_two() {
+        let mut winner_account = get_account(0, solana_sdk::system_program::id());
+        let winner_pubkey = Pubkey::new_from_array([10; 32]);
+        let winner_account_info = (&winner_pubkey, false, &mut winner_account).into_account_info();
+
+        Validator::validate_reward_claimant(true, &winner_account_info, &winner_account_info)
+            .unwrap();
+    }
+
+    #[test]
+    fn success_validate_token_program_test() {
+        let mut program_account = get_account(0, Pubkey::default());
+        let program_pubkey = spl_token::ID;
+        let program_account_info =
+            (&program_pubkey, false, &mut program_account).into_account_info();
+
+        assert_eq!(
+            Validator::validate_token_program(&program_account_info).unwrap(),
+            ()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_validate_token_program_test() {
+        let mut program_account = get_account(0, Pubkey::default());
+        let program_pubkey = Pubkey::new_from_array([66; 32]);
+        let program_account_info =
+            (&program_pubkey, false, &mut program_account).into_account_info();
+
+        Validator::validate_token_program(&program_account_info).unwrap();
+    }
+
+    #[test]
+    fn success_validate_system_program_test() {
+        let mut program_account = get_account(0, Pubkey::default());
+        let program_pubkey = solana_sdk::system_program::id();
+        let program_account_info =
+            (&program_pubkey, false, &mut program_account).into_account_info();
+
+        assert_eq!(
+            Validator::validate_system_program(&program_account_info).unwrap(),
+            ()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_validate_system_program_test() {
+        let mut program_account = get_account(0, Pubkey::default());
+        let program_pubkey = Pubkey::new_from_array([66; 32]);
+        let program_account_info =
+            (&program_pubkey, false, &mut program_account).into_account_info();
+
+        Validator::validate_system_program(&program_account_info).unwrap();
+    }
+
+    #[test]
+    fn success_validate_associated_token_program_test() {
+        let mut program_account = get_account(0, Pubkey::default());
+        let program_pubkey = spl_associated_token_account::ID;
+        let program_account_info =
+            (&program_pubkey, false, &mut program_account).into_account_info();
+
+        assert_eq!(
+            Validator::validate_associated_token_program(&program_account_info).unwrap(),
+            ()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_validate_associated_token_program_test() {
+        let mut program_account = get_account(0, Pubkey::default());
+        let program_pubkey = Pubkey::new_from_array([66; 32]);
+        let program_account_info =
+            (&program_pubkey, false, &mut program_account).into_account_info();
+
+        Validator::validate_associated_token_program(&program_account_info).unwrap();
+    }
+
+    #[test]
+    fn success_validate_rent_sysvar_test() {
+        let mut program_account = get_account(0, Pubkey::default());
+        let program_pubkey = solana_sdk::sysvar::rent::id();
+        let program_account_info =
+            (&program_pubkey, false, &mut program_account).into_account_info();
+
+        assert_eq!(
+            Validator::validate_rent_sysvar(&program_account_info).unwrap(),
+            ()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn failure_validate_rent_sysvar_test() {
+        let mut program_account = get_account(0, Pubkey::default());
+        let program_pubkey = Pubkey::new_from_array([66; 32]);
+        let program_account_info =
+            (&program_pubkey, false, &mut program_account).into_account_info();
+
+        Validator::validate_rent_sysvar(&program_account_info).unwrap();
+    }
 }